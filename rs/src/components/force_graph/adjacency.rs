@@ -0,0 +1,158 @@
+//! Neighbor index over the graph's links.
+//!
+//! Kept alongside the (source, target) edge list so hover highlighting,
+//! tooltips and traversals look up a node's links directly instead of scanning
+//! every edge. [`GraphModel`](super::GraphModel) answers its ID-based queries
+//! with the same traversals.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use force_graph::DefaultNodeIdx;
+
+/// Each node's outgoing and incoming links.
+#[derive(Clone, Debug, Default)]
+pub struct Adjacency {
+	/// Targets linked from each node
+	outgoing: HashMap<DefaultNodeIdx, Vec<DefaultNodeIdx>>,
+	/// Sources linking to each node
+	incoming: HashMap<DefaultNodeIdx, Vec<DefaultNodeIdx>>,
+}
+
+impl Adjacency {
+	/// Index of the links `edges`, as (source, target) pairs.
+	pub fn new(edges: &[(DefaultNodeIdx, DefaultNodeIdx)]) -> Self {
+		let mut adjacency = Self::default();
+		for &(source, target) in edges {
+			adjacency.insert(source, target);
+		}
+		adjacency
+	}
+
+	/// Record a link from `source` to `target`.
+	pub fn insert(&mut self, source: DefaultNodeIdx, target: DefaultNodeIdx) {
+		self.outgoing.entry(source).or_default().push(target);
+		self.incoming.entry(target).or_default().push(source);
+	}
+
+	/// Nodes `idx` links to.
+	pub fn outgoing(&self, idx: DefaultNodeIdx) -> &[DefaultNodeIdx] {
+		self.outgoing.get(&idx).map_or(&[], Vec::as_slice)
+	}
+
+	/// Nodes linking to `idx`.
+	pub fn incoming(&self, idx: DefaultNodeIdx) -> &[DefaultNodeIdx] {
+		self.incoming.get(&idx).map_or(&[], Vec::as_slice)
+	}
+
+	/// Nodes linked to or from `idx`. A node linked both ways appears twice.
+	pub fn neighbors(&self, idx: DefaultNodeIdx) -> impl Iterator<Item = DefaultNodeIdx> + '_ {
+		self.outgoing(idx).iter().chain(self.incoming(idx)).copied()
+	}
+
+	/// Nodes at most `hops` links away from `idx` in either direction,
+	/// including `idx` itself.
+	pub fn within(&self, idx: DefaultNodeIdx, hops: usize) -> HashSet<DefaultNodeIdx> {
+		let mut reached = HashSet::from([idx]);
+		let mut frontier = vec![idx];
+		for _ in 0..hops {
+			frontier = frontier
+				.iter()
+				.flat_map(|&n| self.neighbors(n))
+				.filter(|&n| reached.insert(n))
+				.collect();
+		}
+		reached
+	}
+
+	/// Whether `a` and `b` are linked in either direction.
+	pub fn linked(&self, a: DefaultNodeIdx, b: DefaultNodeIdx) -> bool {
+		self.outgoing(a).contains(&b) || self.outgoing(b).contains(&a)
+	}
+
+	/// Everything `idx` links to, directly or through other nodes, nearest first.
+	pub fn dependencies(&self, idx: DefaultNodeIdx) -> Vec<DefaultNodeIdx> {
+		self.reachable(idx, &self.outgoing)
+	}
+
+	/// Everything linking to `idx`, directly or through other nodes, nearest first.
+	pub fn dependents(&self, idx: DefaultNodeIdx) -> Vec<DefaultNodeIdx> {
+		self.reachable(idx, &self.incoming)
+	}
+
+	/// A shortest chain of links from `from` to `to`, following links in either
+	/// direction, including both ends. `None` if they aren't connected.
+	pub fn shortest_path(
+		&self,
+		from: DefaultNodeIdx,
+		to: DefaultNodeIdx,
+	) -> Option<Vec<DefaultNodeIdx>> {
+		let mut previous = HashMap::from([(from, from)]);
+		let mut queue = VecDeque::from([from]);
+		while let Some(idx) = queue.pop_front() {
+			if idx == to {
+				let mut path = vec![to];
+				let mut at = to;
+				while at != from {
+					at = previous[&at];
+					path.push(at);
+				}
+				path.reverse();
+				return Some(path);
+			}
+			for next in self.neighbors(idx) {
+				if let Entry::Vacant(entry) = previous.entry(next) {
+					entry.insert(idx);
+					queue.push_back(next);
+				}
+			}
+		}
+		None
+	}
+
+	/// Connected components among `nodes`, ignoring links to nodes outside it.
+	/// Components come in the order of their first node in `nodes`, each
+	/// starting from that node.
+	pub fn components(&self, nodes: &[DefaultNodeIdx]) -> Vec<Vec<DefaultNodeIdx>> {
+		let included: HashSet<DefaultNodeIdx> = nodes.iter().copied().collect();
+		let mut seen = HashSet::new();
+		let mut components = Vec::new();
+		for &start in nodes {
+			if !seen.insert(start) {
+				continue;
+			}
+			let mut component = vec![start];
+			let mut i = 0;
+			while i < component.len() {
+				for next in self.neighbors(component[i]) {
+					if included.contains(&next) && seen.insert(next) {
+						component.push(next);
+					}
+				}
+				i += 1;
+			}
+			components.push(component);
+		}
+		components
+	}
+
+	/// Nodes reachable from `idx` along `edges`, breadth first, excluding `idx`.
+	fn reachable(
+		&self,
+		idx: DefaultNodeIdx,
+		edges: &HashMap<DefaultNodeIdx, Vec<DefaultNodeIdx>>,
+	) -> Vec<DefaultNodeIdx> {
+		let mut seen = HashSet::from([idx]);
+		let mut queue = VecDeque::from([idx]);
+		let mut found = Vec::new();
+		while let Some(at) = queue.pop_front() {
+			for &next in edges.get(&at).into_iter().flatten() {
+				if seen.insert(next) {
+					found.push(next);
+					queue.push_back(next);
+				}
+			}
+		}
+		found
+	}
+}
@@ -0,0 +1,81 @@
+//! Host elements pinned to nodes.
+//!
+//! Anchored elements live in a container over the canvas. Each sits in a
+//! wrapper naming its node, which is moved to the node's screen position
+//! whenever a frame is drawn, so the elements follow the layout, the camera
+//! and 3D projection without being re-rendered. Popovers are placed beside
+//! their node instead, on whichever side keeps them inside the canvas.
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+
+use super::state::ForceGraphState;
+
+/// CSS for the container over the canvas; only the anchored elements take
+/// pointer input.
+pub const CONTAINER_STYLE: &str =
+	"position: absolute; inset: 0; overflow: hidden; pointer-events: none;";
+
+/// CSS for an element's wrapper, hidden until first positioned.
+pub const ANCHOR_STYLE: &str =
+	"position: absolute; left: 0; top: 0; display: none; pointer-events: auto;";
+
+/// Attribute holding the ID of the node a wrapper is pinned to.
+pub const NODE_ATTRIBUTE: &str = "data-node";
+
+/// Attribute marking a wrapper as a popover.
+pub const POPOVER_ATTRIBUTE: &str = "data-popover";
+
+/// Screen distance between a popover and its node's center, in pixels.
+const POPOVER_GAP: f64 = 12.0;
+
+/// Move every wrapper in `container` so its top-left corner sits on its node's
+/// center, or a popover beside it, hiding those whose node is hidden or gone.
+pub fn position_anchors(container: &Element, state: &ForceGraphState) {
+	let wrappers = container.children();
+	for i in 0..wrappers.length() {
+		let Some(wrapper) = wrappers
+			.item(i)
+			.and_then(|w| w.dyn_into::<HtmlElement>().ok())
+		else {
+			continue;
+		};
+		let position = wrapper
+			.get_attribute(NODE_ATTRIBUTE)
+			.and_then(|id| state.node_index(&id))
+			.and_then(|idx| state.node(idx))
+			.filter(|node| !node.data.user_data.hidden)
+			.map(|node| {
+				let display = &node.data.user_data.display;
+				let t = &state.transform;
+				(t.x + display.x * t.k, t.y + display.y * t.k)
+			});
+		let style = wrapper.style();
+		let Some((mut x, mut y)) = position else {
+			let _ = style.set_property("display", "none");
+			continue;
+		};
+		let _ = style.set_property("display", "block");
+		if wrapper.has_attribute(POPOVER_ATTRIBUTE) {
+			let size = (
+				wrapper.offset_width() as f64,
+				wrapper.offset_height() as f64,
+			);
+			x = beside(x, size.0, state.width);
+			y = beside(y, size.1, state.height);
+		}
+		let _ = style.set_property("transform", &format!("translate({x:.1}px, {y:.1}px)"));
+	}
+}
+
+/// Start of a popover `size` long beside `anchor` along an axis `extent` long:
+/// after the anchor where it fits, else before it, and inside the extent as
+/// far as possible.
+fn beside(anchor: f64, size: f64, extent: f64) -> f64 {
+	let start = if anchor + POPOVER_GAP + size <= extent {
+		anchor + POPOVER_GAP
+	} else {
+		anchor - POPOVER_GAP - size
+	};
+	start.min(extent - size).max(0.0)
+}
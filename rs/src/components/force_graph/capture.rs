@@ -0,0 +1,145 @@
+//! Video capture of the canvas with `MediaRecorder`.
+//!
+//! A still screenshot can't show a layout settling; a short clip can. Frames
+//! come from the canvas's `captureStream`, so the clip shows exactly what was
+//! drawn, including an interaction [`Recording`](super::Recording) replayed
+//! while capturing. Stopping saves the clip as a WebM download.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use web_sys::{
+	Blob, BlobEvent, BlobPropertyBag, HtmlAnchorElement, HtmlCanvasElement, MediaRecorder,
+	MediaRecorderOptions, Url,
+};
+
+/// Frames per second captured from the canvas.
+const CAPTURE_FPS: f64 = 30.0;
+
+/// How often the recorder hands over encoded data, in milliseconds.
+const CHUNK_MS: i32 = 1000;
+
+/// Container formats to try, best first.
+const MIME_TYPES: [&str; 3] = [
+	"video/webm;codecs=vp9",
+	"video/webm;codecs=vp8",
+	"video/webm",
+];
+
+/// A capture in progress.
+struct ActiveCapture {
+	recorder: MediaRecorder,
+	mime_type: &'static str,
+	/// Encoded video received so far
+	chunks: Rc<RefCell<Vec<Blob>>>,
+	/// Collects `chunks`; kept alive until the recorder has flushed
+	on_data: Closure<dyn FnMut(BlobEvent)>,
+}
+
+/// Records the canvas to video on demand.
+pub struct ClipCapture {
+	canvas: HtmlCanvasElement,
+	active: Option<ActiveCapture>,
+}
+
+impl ClipCapture {
+	/// Capture of `canvas`, idle until started.
+	pub fn new(canvas: HtmlCanvasElement) -> Self {
+		Self {
+			canvas,
+			active: None,
+		}
+	}
+
+	/// Whether a capture is in progress.
+	pub fn is_capturing(&self) -> bool {
+		self.active.is_some()
+	}
+
+	/// Start capturing, restarting any capture in progress. Fails where the
+	/// browser can't record the canvas to WebM.
+	pub fn start(&mut self) -> Result<(), String> {
+		if let Some(active) = self.active.take() {
+			// Discard it: the data handler goes away with `active`
+			active.recorder.set_ondataavailable(None);
+			let _ = active.recorder.stop();
+		}
+		let mime_type = MIME_TYPES
+			.into_iter()
+			.find(|t| MediaRecorder::is_type_supported(t))
+			.ok_or("this browser can't record WebM video")?;
+		let stream = self
+			.canvas
+			.capture_stream_with_frame_request_rate(CAPTURE_FPS)
+			.map_err(|_| "the canvas can't be captured")?;
+		let options = MediaRecorderOptions::new();
+		options.set_mime_type(mime_type);
+		let recorder =
+			MediaRecorder::new_with_media_stream_and_media_recorder_options(&stream, &options)
+				.map_err(|_| "couldn't create a media recorder")?;
+
+		let chunks = Rc::new(RefCell::new(Vec::new()));
+		let chunks_data = chunks.clone();
+		let on_data = Closure::<dyn FnMut(BlobEvent)>::new(move |ev: BlobEvent| {
+			if let Some(blob) = ev.data().filter(|blob| blob.size() > 0.0) {
+				chunks_data.borrow_mut().push(blob);
+			}
+		});
+		recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+		recorder
+			.start_with_time_slice(CHUNK_MS)
+			.map_err(|_| "couldn't start recording")?;
+		self.active = Some(ActiveCapture {
+			recorder,
+			mime_type,
+			chunks,
+			on_data,
+		});
+		Ok(())
+	}
+
+	/// Stop capturing and download the clip as `file_name` once the recorder has
+	/// flushed it. Returns whether a capture was in progress.
+	pub fn stop(&mut self, file_name: &str) -> bool {
+		let Some(active) = self.active.take() else {
+			return false;
+		};
+		let file_name = file_name.to_string();
+		let recorder = active.recorder.clone();
+		let on_stop = Closure::once_into_js(move || {
+			let ActiveCapture {
+				mime_type,
+				chunks,
+				on_data,
+				..
+			} = active;
+			let parts: js_sys::Array = chunks.borrow().iter().collect();
+			let options = BlobPropertyBag::new();
+			options.set_type(mime_type);
+			if let Ok(blob) = Blob::new_with_blob_sequence_and_options(&parts, &options) {
+				download(&blob, &file_name);
+			}
+			drop(on_data);
+		});
+		recorder.set_onstop(Some(on_stop.unchecked_ref()));
+		recorder.stop().is_ok()
+	}
+}
+
+/// Save `blob` through a temporary download link.
+fn download(blob: &Blob, file_name: &str) {
+	let Ok(url) = Url::create_object_url_with_blob(blob) else {
+		return;
+	};
+	let link = web_sys::window()
+		.and_then(|w| w.document())
+		.and_then(|d| d.create_element("a").ok())
+		.and_then(|el| el.dyn_into::<HtmlAnchorElement>().ok());
+	if let Some(link) = link {
+		link.set_href(&url);
+		link.set_download(file_name);
+		link.click();
+	}
+	let _ = Url::revoke_object_url(&url);
+}
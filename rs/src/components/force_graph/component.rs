@@ -4,26 +4,606 @@
 //! handlers for node dragging, panning, and zooming. An animation loop runs via
 //! `requestAnimationFrame`, calling the physics simulation and renderer each frame.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use leptos::prelude::*;
 use wasm_bindgen::prelude::*;
-use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, MouseEvent, WheelEvent, Window};
+use web_sys::{
+	CanvasRenderingContext2d, Element, HtmlCanvasElement, IdleDeadline, KeyboardEvent, MouseEvent,
+	WheelEvent, Window,
+};
 
+use super::capture::ClipCapture;
+use super::context_menu::{
+	ContextMenuItem, ContextTarget, MenuEntry, OpenMenu, apply_builtin, builtin_actions,
+};
+use super::hooks::{EdgeDrawHook, NodeDrawHook, RenderHooks, RenderPlugin};
+use super::keymap::{KeyAction, Keymap};
+use super::layers::{LAYER_STYLE, LayerStack, Layers};
+use super::layout::Layout;
+use super::link::ViewLink;
+#[cfg(feature = "particles")]
 use super::particles::ParticleSystem;
-use super::render;
-use super::scale::ScaleConfig;
-use super::state::ForceGraphState;
+use super::physics::PhysicsConfig;
+use super::provider::{self, use_graph};
+use super::recording::{self, InputKind, Recorder};
+use super::scale::{ScaleConfig, ScaledValues};
+use super::state::{
+	BoxZoomState, CameraConfig, ComponentSummary, DebugStats, ForceGraphState, HeatLegend,
+	HeatmapConfig, HighlightConfig, LinkDraft, NodeSizing, SizeLegend, TransitionConfig,
+	ViewTransform, advance_transition,
+};
+#[cfg(feature = "particles")]
+use super::theme::ParticleStyle;
 use super::theme::Theme;
-use super::types::GraphData;
+use super::types::{GraphData, GraphNode, GraphSnapshot};
+use super::{anchors, render};
+
+/// In-frame label measurement budget (milliseconds) where `requestIdleCallback`
+/// isn't available.
+const LABEL_SLICE_MS: f64 = 2.0;
+
+/// Global object host pages find graph instances on, keyed by `js_handle`.
+const JS_REGISTRY: &str = "__impGraph";
+
+/// A method of the host page registry.
+type JsMethod = Closure<dyn Fn(JsValue) -> JsValue>;
+
+/// Expose `window.__impGraph[name]`: `setData(json)` parses graph data (a JSON
+/// string or plain object) into `latest`, reporting parse errors through
+/// `rejected`, and returns whether it was accepted; `nodeIds()` and
+/// `positions()` sample the layout of the graph in `context`. The returned
+/// closures must outlive the registration.
+fn register_js_handle(
+	name: &str,
+	latest: RwSignal<Option<GraphData>>,
+	rejected: RwSignal<Option<String>>,
+	context: Rc<RefCell<Option<GraphContext>>>,
+) -> Option<Vec<JsMethod>> {
+	let window = web_sys::window()?;
+	let key = JsValue::from_str(JS_REGISTRY);
+	let registry = js_sys::Reflect::get(&window, &key).ok()?;
+	let registry = if registry.is_object() {
+		registry
+	} else {
+		let registry = js_sys::Object::new().into();
+		js_sys::Reflect::set(&window, &key, &registry).ok()?;
+		registry
+	};
+	let set_data = JsMethod::new(move |json: JsValue| {
+		let text = json
+			.as_string()
+			.or_else(|| js_sys::JSON::stringify(&json).ok().map(String::from));
+		match serde_json::from_str::<GraphData>(&text.unwrap_or_default()) {
+			Ok(data) => {
+				rejected.set(None);
+				latest.set(Some(data));
+				JsValue::TRUE
+			}
+			Err(e) => {
+				log::warn!("imp-graph: setData rejected invalid graph data: {e}");
+				rejected.set(Some(e.to_string()));
+				JsValue::FALSE
+			}
+		}
+	});
+	let context_ids = context.clone();
+	let node_ids = JsMethod::new(move |_| {
+		let ids = context_ids
+			.borrow()
+			.as_ref()
+			.map(|c| c.state.node_order())
+			.unwrap_or_default();
+		ids.into_iter()
+			.map(JsValue::from)
+			.collect::<js_sys::Array>()
+			.into()
+	});
+	// Reused between calls; only the returned array is allocated anew
+	let scratch = RefCell::new(Vec::new());
+	let positions = JsMethod::new(move |_| {
+		let mut out = scratch.borrow_mut();
+		match context.borrow().as_ref() {
+			Some(c) => c.state.write_positions(&mut out),
+			None => out.clear(),
+		}
+		js_sys::Float32Array::from(&out[..]).into()
+	});
+	let api = js_sys::Object::new();
+	js_sys::Reflect::set(&api, &"setData".into(), set_data.as_ref()).ok()?;
+	js_sys::Reflect::set(&api, &"nodeIds".into(), node_ids.as_ref()).ok()?;
+	js_sys::Reflect::set(&api, &"positions".into(), positions.as_ref()).ok()?;
+	js_sys::Reflect::set(&registry, &name.into(), &api).ok()?;
+	Some(vec![set_data, node_ids, positions])
+}
+
+/// Remove `name` from the host page registry.
+fn unregister_js_handle(name: &str) {
+	let Some(window) = web_sys::window() else {
+		return;
+	};
+	if let Ok(registry) = js_sys::Reflect::get(&window, &JS_REGISTRY.into())
+		&& let Ok(registry) = registry.dyn_into::<js_sys::Object>()
+	{
+		let _ = js_sys::Reflect::delete_property(&registry, &name.into());
+	}
+}
+
+/// Most components listed by the component navigator.
+const NAVIGATOR_ENTRIES: usize = 12;
+
+/// Least time between `on_view_change` calls, in seconds.
+const VIEW_REPORT_INTERVAL: f64 = 0.1;
+
+/// Frames between debug panel refreshes (about a second at 60 fps).
+const DEBUG_REFRESH_FRAMES: u32 = 60;
+
+/// Simulated time per physics step, in seconds. Steps are fixed so the layout
+/// behaves the same at any refresh rate.
+const PHYSICS_STEP: f64 = 0.016;
+
+/// Longest frame counted toward animation and physics time, in seconds, so a
+/// backgrounded tab doesn't come back to a burst of catch-up.
+const MAX_FRAME_TIME: f64 = 0.1;
+
+/// Size of the WASM linear memory in bytes.
+fn wasm_heap_bytes() -> usize {
+	wasm_bindgen::memory()
+		.dyn_into::<js_sys::WebAssembly::Memory>()
+		.ok()
+		.and_then(|memory| memory.buffer().dyn_into::<js_sys::ArrayBuffer>().ok())
+		.map_or(0, |buffer| buffer.byte_length() as usize)
+}
+
+/// Boxed JS callback kept alive for the lifetime of the component.
+type JsCallback = Rc<RefCell<Option<Closure<dyn FnMut()>>>>;
+
+/// Window-level print listeners kept alive for the lifetime of the component.
+type JsPrintCallbacks = Rc<RefCell<Vec<Closure<dyn FnMut()>>>>;
+
+/// Window-level keyboard listeners kept alive for the lifetime of the component.
+type JsKeyCallbacks = Rc<RefCell<Vec<Closure<dyn FnMut(KeyboardEvent)>>>>;
+
+/// Whether a key event originated in a text field, where shortcuts must not fire.
+fn is_editable_target(ev: &KeyboardEvent) -> bool {
+	ev.target()
+		.and_then(|t| t.dyn_into::<Element>().ok())
+		.is_some_and(|el| {
+			matches!(el.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+				|| el.has_attribute("contenteditable")
+		})
+}
+
+/// Whether a wheel event looks like a two-finger trackpad scroll rather than a
+/// mouse wheel notch. Wheels report whole lines or large, purely vertical pixel
+/// steps; trackpads stream small, often fractional or horizontal pixel deltas.
+fn is_trackpad_scroll(ev: &WheelEvent) -> bool {
+	ev.delta_mode() == WheelEvent::DOM_DELTA_PIXEL
+		&& (ev.delta_x() != 0.0 || ev.delta_y().fract() != 0.0 || ev.delta_y().abs() < 50.0)
+}
+
+/// Log the settings of `theme` that have no effect.
+fn warn_theme(theme: &Theme) {
+	for warning in theme.validate() {
+		log::warn!("imp-graph: theme \"{}\": {warning}", theme.name);
+	}
+}
+
+/// Carry out a keyboard shortcut against the graph state. Zoom steps are anchored
+/// on the pointer; UI-only actions (help overlay, debug panel) are handled by the component.
+fn apply_key_action(state: &mut ForceGraphState, action: KeyAction) {
+	let center = (state.width / 2.0, state.height / 2.0);
+	let (px, py) = state.pointer.unwrap_or(center);
+	match action {
+		KeyAction::Fit => {
+			if let Some(target) = state.fit_all_transform() {
+				state.animate_to(target);
+			}
+		}
+		KeyAction::ZoomIn => state.animate_to(state.zoom_about(px, py, 1.25)),
+		KeyAction::ZoomOut => state.animate_to(state.zoom_about(px, py, 0.8)),
+		KeyAction::ResetZoom => {
+			let k = state
+				.camera
+				.as_ref()
+				.map_or(state.transform.k, |tween| tween.to.k);
+			let target = state.zoom_about(center.0, center.1, 1.0 / k);
+			state.animate_to(target);
+		}
+		KeyAction::ClearSelection => {
+			state.selection.set([]);
+			state.clear_focus();
+			state.set_hover(None);
+		}
+		KeyAction::UnpinAll => state.unpin_all(),
+		KeyAction::DeleteSelection => {
+			if state.editable {
+				let nodes: Vec<_> = state.selection.iter().collect();
+				state.remove_nodes(&nodes);
+			}
+		}
+		KeyAction::TogglePhysics => state.animation_running = !state.animation_running,
+		KeyAction::Reheat => state.reheat(1.0),
+		KeyAction::ToggleLabels => state.set_show_labels(!state.show_labels),
+		KeyAction::ToggleHelp | KeyAction::ToggleDebug => {}
+	}
+}
+
+/// Byte count in the largest fitting binary unit (e.g. 1.5 MiB).
+fn format_bytes(bytes: usize) -> String {
+	let mut value = bytes as f64;
+	for unit in ["B", "KiB", "MiB"] {
+		if value < 1024.0 {
+			return format!("{value:.1} {unit}");
+		}
+		value /= 1024.0;
+	}
+	format!("{value:.1} GiB")
+}
+
+/// Compact legend label for a metric value: whole numbers as-is, large values
+/// abbreviated (12.5k, 3.2M), everything else to two decimals.
+fn format_metric(v: f64) -> String {
+	let abs = v.abs();
+	if abs >= 1e6 {
+		format!("{:.1}M", v / 1e6)
+	} else if abs >= 1e4 {
+		format!("{:.1}k", v / 1e3)
+	} else if v.fract() == 0.0 {
+		format!("{v:.0}")
+	} else {
+		format!("{v:.2}")
+	}
+}
+
+/// A theme change in progress.
+struct ThemeFade {
+	from: Theme,
+	to: Theme,
+	/// 0.0 to 1.0
+	progress: f64,
+}
+
+/// Canvas pixels per CSS pixel when rendering for print (about 300 DPI on paper).
+const PRINT_PIXEL_RATIO: f64 = 3.0;
+
+/// Screen settings put aside while the graph is rendered for print.
+struct PrintBackup {
+	theme: Theme,
+	scale: ScaleConfig,
+	top_labels: usize,
+	animation_running: bool,
+	#[cfg(feature = "particles")]
+	particles: Option<ParticleSystem>,
+}
 
 /// Bundles graph simulation state with visual configuration (scaling, theme, particles).
-struct GraphContext {
-	state: ForceGraphState,
+pub struct GraphContext {
+	pub state: ForceGraphState,
+	pub recorder: Recorder,
+	pub clip: ClipCapture,
+	canvas: HtmlCanvasElement,
+	/// Screen settings to restore, while in print mode
+	print: Option<PrintBackup>,
+	theme_fade: Option<ThemeFade>,
 	scale: ScaleConfig,
 	theme: Theme,
+	hooks: RenderHooks,
+	/// Canvases stacked under `canvas`, with layered rendering
+	layers: Option<LayerStack>,
+	#[cfg(feature = "particles")]
 	particles: Option<ParticleSystem>,
+	/// Host override of the theme's particles setting
+	#[cfg(feature = "particles")]
+	particles_enabled: Option<bool>,
+}
+
+impl GraphContext {
+	/// Draw a frame, repainting `layers` of it where the canvases are stacked.
+	/// Printing and video capture need the whole picture on the top canvas.
+	fn render(&mut self, ctx: &CanvasRenderingContext2d, layers: Layers) {
+		let single = self.print.is_some() || self.clip.is_capturing();
+		match &mut self.layers {
+			Some(stack) if !single => {
+				stack.set_edge_opacity(render::edge_layer_opacity(&self.state));
+				let (lower, stale) = stack.contexts();
+				render::render_layers(
+					&self.state,
+					[&lower[0], &lower[1], &lower[2], ctx],
+					if stale { Layers::ALL } else { layers },
+					&self.scale,
+					&self.theme,
+					&self.hooks,
+					#[cfg(feature = "particles")]
+					self.particles.as_ref(),
+				);
+			}
+			stack => {
+				if let Some(stack) = stack {
+					stack.suspend();
+				}
+				render::render(
+					&self.state,
+					ctx,
+					&self.scale,
+					&self.theme,
+					&self.hooks,
+					#[cfg(feature = "particles")]
+					self.particles.as_ref(),
+				);
+			}
+		}
+	}
+
+	/// Layers the next frame has to repaint, whether from changes or from
+	/// effects that move with time.
+	fn frame_layers(&self) -> Layers {
+		if self.hooks.animated {
+			return Layers::ALL;
+		}
+		self.state.redraw_layers()
+			| render::animated_layers(
+				&self.state,
+				&self.scale,
+				&self.theme,
+				self.particle_count() > 0,
+			)
+	}
+
+	/// Number of ambient particles in flight.
+	fn particle_count(&self) -> usize {
+		#[cfg(feature = "particles")]
+		if let Some(ref ps) = self.particles {
+			return ps.particles.len();
+		}
+		0
+	}
+
+	/// Advance the ambient particles by `dt` seconds.
+	#[cfg_attr(not(feature = "particles"), allow(unused_variables))]
+	fn tick_particles(&mut self, dt: f64) {
+		#[cfg(feature = "particles")]
+		if let Some(ref mut ps) = self.particles {
+			let deflect = self.theme.particles.deflect.clamp(0.0, 1.0);
+			let mut obstacles = Vec::new();
+			if deflect > 0.0 {
+				let t = &self.state.transform;
+				let radius = ScaledValues::new(&self.scale, t.k).node_radius * t.k;
+				self.state.graph.visit_nodes(|node| {
+					let info = &node.data.user_data;
+					if !info.hidden {
+						let (x, y) = (info.display.x * t.k + t.x, info.display.y * t.k + t.y);
+						obstacles.push((x, y, radius * info.display_size()));
+					}
+				});
+			}
+			ps.update(dt, &obstacles, deflect);
+		}
+	}
+
+	/// Fit the ambient particles to a resized canvas.
+	#[cfg_attr(not(feature = "particles"), allow(unused_variables))]
+	fn resize_particles(&mut self, width: f64, height: f64) {
+		#[cfg(feature = "particles")]
+		if let Some(ref mut ps) = self.particles {
+			ps.resize(&self.theme.particles, width, height);
+		}
+	}
+
+	/// Turn the ambient particles on or off, or (`None`) leave it to the theme.
+	#[cfg_attr(not(feature = "particles"), allow(unused_variables))]
+	fn set_particles(&mut self, enabled: Option<bool>) {
+		#[cfg(feature = "particles")]
+		{
+			self.particles_enabled = enabled;
+			// Printing keeps them off; the setting applies afterwards
+			match self.print {
+				Some(ref backup) => {
+					let particles = self.particle_system(&backup.theme.particles);
+					if let Some(ref mut backup) = self.print {
+						backup.particles = particles;
+					}
+				}
+				None => {
+					self.particles = self.particle_system(&self.theme.particles);
+					self.state.mark_dirty();
+				}
+			}
+		}
+	}
+
+	/// Fresh ambient particles in `style`, unless they are off.
+	#[cfg(feature = "particles")]
+	fn particle_system(&self, style: &ParticleStyle) -> Option<ParticleSystem> {
+		let (w, h) = (self.state.width, self.state.height);
+		self.particles_enabled
+			.unwrap_or(style.enabled)
+			.then(|| ParticleSystem::new(style, w, h))
+	}
+
+	/// Measure and pre-render queued labels until `time_left` (milliseconds)
+	/// runs low, then place them for the current view.
+	fn prepare_labels(&mut self, ctx: &CanvasRenderingContext2d, time_left: impl Fn() -> f64) {
+		let measured = self.state.labels.measure_pending(ctx, &time_left);
+		let scale = ScaledValues::new(&self.scale, self.state.transform.k);
+		let (screen_px, color) = (scale.label_font_size * scale.k, self.theme.node.label_color);
+		let rendered = self
+			.state
+			.sprites
+			.render_pending(screen_px, color, time_left);
+		// Sprites draw slightly differently from plain text; show them once ready
+		if rendered {
+			self.state.mark_layers_dirty(Layers::NODES);
+		}
+		// Measured widths can change which labels fit
+		self.place_labels(measured);
+	}
+
+	/// Decide which resting labels the current view shows, unless the last
+	/// placement still holds.
+	fn place_labels(&mut self, force: bool) {
+		let key = self.state.placement_key();
+		if !force && self.state.placed_labels.is_current(key) {
+			return;
+		}
+		let scale = ScaledValues::new(&self.scale, self.state.transform.k);
+		let nodes = render::place_labels(&self.state, &scale);
+		if self.state.placed_labels.set(key, nodes) {
+			self.state.mark_layers_dirty(Layers::NODES);
+		}
+	}
+
+	/// Cross-fade to `theme`, with `scale` as the configured scaling for its
+	/// overrides. Node fills fade to the new palette alongside.
+	fn set_theme(&mut self, theme: Theme, scale: ScaleConfig) {
+		// Printing keeps the print theme; the new one applies afterwards
+		if self.print.is_some() {
+			#[cfg(feature = "particles")]
+			let particles = self.particle_system(&theme.particles);
+			if let Some(ref mut backup) = self.print {
+				backup.scale = scale.with_overrides(&theme.scale);
+				backup.theme = theme;
+				#[cfg(feature = "particles")]
+				{
+					backup.particles = particles;
+				}
+			}
+			return;
+		}
+		self.scale = scale.with_overrides(&theme.scale);
+		self.state.recolor(&theme);
+		#[cfg(feature = "particles")]
+		{
+			let (before, after) = (&self.theme.particles, &theme.particles);
+			if before.enabled != after.enabled
+				|| before.count != after.count
+				|| before.density != after.density
+			{
+				self.particles = self.particle_system(after);
+			}
+		}
+		let from = self.theme.clone();
+		self.theme = from.blend(&theme, 0.0);
+		self.theme_fade = Some(ThemeFade {
+			from,
+			to: theme,
+			progress: 0.0,
+		});
+		self.state.mark_dirty();
+	}
+
+	/// Advance a theme cross-fade by `dt` seconds.
+	fn tick_theme(&mut self, dt: f64) {
+		let Some(ref mut fade) = self.theme_fade else {
+			return;
+		};
+		let transitions = &self.state.transitions;
+		fade.progress = advance_transition(fade.progress, dt, transitions.theme_duration);
+		self.theme = fade
+			.from
+			.blend(&fade.to, transitions.theme_easing.apply(fade.progress));
+		if fade.progress >= 1.0 {
+			self.theme_fade = None;
+		}
+		self.state.mark_dirty();
+	}
+
+	/// Whether the graph is rendered for print.
+	pub fn is_printing(&self) -> bool {
+		self.print.is_some()
+	}
+
+	/// Whether the physics simulation is paused. Print mode's own pause
+	/// doesn't count.
+	pub fn is_paused(&self) -> bool {
+		match &self.print {
+			Some(backup) => !backup.animation_running,
+			None => !self.state.animation_running,
+		}
+	}
+
+	/// Scale nodes, edges and labels by `scale`, with the theme's overrides.
+	fn set_scale(&mut self, scale: ScaleConfig) {
+		self.scale = scale.with_overrides(&self.theme.scale);
+		self.state.mark_dirty();
+	}
+
+	/// Draw `plugin`'s layers after those of the plugins already registered.
+	pub fn add_plugin(&mut self, plugin: Rc<dyn RenderPlugin>) {
+		self.hooks.plugins.push(plugin);
+		self.state.mark_dirty();
+	}
+
+	/// Freeze or resume the layout. While printing, takes effect once printing
+	/// ends.
+	pub fn set_paused(&mut self, paused: bool) {
+		match &mut self.print {
+			Some(backup) => backup.animation_running = !paused,
+			None => {
+				self.state.mark_dirty();
+				self.state.animation_running = !paused;
+			}
+		}
+	}
+
+	/// Switch to or from rendering for print: the print theme, every label, no
+	/// animation, and a canvas at print resolution. Draws right away, so the
+	/// result is ready when called from `beforeprint`.
+	pub fn set_print_mode(&mut self, on: bool) {
+		if on == self.is_printing() {
+			return;
+		}
+		if on {
+			let theme = Theme::print();
+			let scale = self.scale.with_overrides(&theme.scale);
+			self.print = Some(PrintBackup {
+				theme: std::mem::replace(&mut self.theme, theme),
+				scale: std::mem::replace(&mut self.scale, scale),
+				top_labels: std::mem::replace(&mut self.state.top_labels, usize::MAX),
+				animation_running: std::mem::replace(&mut self.state.animation_running, false),
+				#[cfg(feature = "particles")]
+				particles: self.particles.take(),
+			});
+			self.state.pixel_ratio = PRINT_PIXEL_RATIO;
+		} else if let Some(backup) = self.print.take() {
+			self.theme = backup.theme;
+			self.scale = backup.scale;
+			self.state.top_labels = backup.top_labels;
+			self.state.animation_running = backup.animation_running;
+			#[cfg(feature = "particles")]
+			{
+				self.particles = backup.particles;
+			}
+			self.state.pixel_ratio = 1.0;
+		}
+
+		// Keep the on-screen size while the backing store grows
+		let (w, h, r) = (self.state.width, self.state.height, self.state.pixel_ratio);
+		self.canvas.set_width((w * r) as u32);
+		self.canvas.set_height((h * r) as u32);
+		let style = web_sys::HtmlElement::style(&self.canvas);
+		if on {
+			let _ = style.set_property("width", &format!("{w}px"));
+			let _ = style.set_property("height", &format!("{h}px"));
+		} else {
+			let _ = style.remove_property("width");
+			let _ = style.remove_property("height");
+		}
+		self.state.mark_dirty();
+		// The print view shows every label; there is no idle time to place them in
+		self.place_labels(false);
+		let ctx = self
+			.canvas
+			.get_context("2d")
+			.ok()
+			.flatten()
+			.and_then(|ctx| ctx.dyn_into::<CanvasRenderingContext2d>().ok());
+		if let Some(ctx) = ctx {
+			self.render(&ctx, Layers::ALL);
+		}
+	}
 }
 
 /// Renders an interactive force-directed graph on a canvas element.
@@ -32,121 +612,1228 @@ struct GraphContext {
 /// to its parent container by default; set `fullscreen = true` to fill the
 /// viewport and resize automatically with the window. Explicit `width`/`height`
 /// override automatic sizing.
+///
+/// The mouse wheel zooms around the cursor. On trackpads, pinching zooms and
+/// two-finger scrolling pans.
+///
+/// Middle-drag, or left-drag while holding space, pans the view even when the
+/// press lands on a node.
+///
+/// Dragging a node pins it where it is dropped. Alt+click a node to release it
+/// again; the context menu and the `U` shortcut unpin everything.
+///
+/// Shift-drag draws a rectangle and zooms to fit it on release. Alt-drag draws a
+/// freeform lasso that selects every node inside it.
+///
+/// Hovering a node shows its in/out degree and the first few nodes it depends on
+/// and is used by. Hovering an edge shows its endpoints. Repeated links between
+/// the same pair of nodes are drawn as one thicker edge, with the count shown in
+/// its tooltip.
+///
+/// Double-clicking a node zooms to it and fires `on_node_activate` with the node's
+/// ID; double-clicking empty space zooms to fit the whole graph.
+///
+/// Right-clicking opens a context menu with built-in actions (pin, hide, isolate,
+/// focus subtree, copy ID, show all, zoom to fit). Focusing a node keeps it and
+/// everything it links to, transitively, at full intensity and dims the rest until
+/// cleared from the menu or with `Esc`. Isolating or focusing adds a step to a
+/// breadcrumb trail shown over the graph; clicking a crumb returns to that view.
+/// Hosts add their own entries by passing a
+/// `context_menu` callback that returns extra items for the clicked target.
+///
+/// Clicking a node selects it; clicking empty space clears the selection. Pass
+/// a `selected` signal of node IDs to control the selection from outside, e.g.
+/// from a table: setting it selects those nodes, and selecting in the graph
+/// writes it.
+///
+/// Keyboard shortcuts work while the pointer is over the graph: `F` fits, `+`/`-`
+/// zoom, `0` resets zoom, `Esc` clears the selection, `P` toggles physics, `L`
+/// toggles labels and `?` lists them all. Pass a custom `keymap` to change them.
+/// `Ctrl+Z` / `Ctrl+Shift+Z` undo and redo node drags and pinning.
+///
+/// Set `snap_grid` to a spacing (in graph units) to snap dragged nodes to a grid,
+/// drawn faintly behind the graph while active.
+///
+/// Set `editable` to sketch graphs directly: click empty space to add a node,
+/// drag from one node to another to link them, click a node to select it and
+/// press `Delete` to remove the selection. Each change fires `on_edit` with the
+/// resulting graph in input format, and the background context menu can copy it
+/// as JSON.
+///
+/// `on_view_change` receives the pan and zoom as they change, at most ten
+/// times a second and always ending on the settled view, so hosts can persist
+/// it or drive a second view. For two-way binding pass a `transform` signal
+/// instead: the component writes its pan and zoom to it, and writing to it
+/// moves the camera, so two graphs sharing one signal pan and zoom together.
+///
+/// A `hovered` signal follows the ID of the node under the pointer, for
+/// surrounding UI such as a details sidebar; setting it highlights that node as
+/// if hovered. `on_hover_change` receives the same ID (or `None`) whenever it
+/// changes, and `on_background_click` fires on clicks on empty space, e.g. to
+/// close the host's own panels.
+///
+/// Pass canvases the same [`ViewLink`] as `linked` to coordinate them, e.g. a
+/// "before" and "after" pair: hovering or selecting a node in either shows it
+/// in both, and unless the link is made `without_camera` they pan and zoom
+/// together.
+///
+/// A graph without nodes shows an empty-state message instead of a blank
+/// canvas. Pass the host's loading failure as `error` to show it in its place;
+/// data rejected by `setData` is reported the same way. While `loading` is set
+/// a spinner shows instead, and `on_retry` adds a retry button to the host's
+/// error.
+///
+/// `settle_indicator = true` draws a thin progress bar along the top edge while
+/// the layout is still moving; it disappears once the layout has settled.
+/// `on_layout_start` and `on_layout_settled` fire as the layout starts moving
+/// (on mount, or when dragging, new data or a reheat disturbs it) and comes to
+/// rest, so hosts can sequence work such as a screenshot after the layout
+/// completes.
+///
+/// Inside a [`GraphProvider`](super::GraphProvider) the canvas attaches to the
+/// provided handle, so the host's own panels can follow its data, hovering and
+/// camera through [`use_graph`].
+///
+/// Set the reactive `theme` signal to restyle the graph; switching themes
+/// cross-fades colors over a fraction of a second rather than flipping.
+///
+/// Set the reactive `particles` signal to turn the theme's ambient particles
+/// on or off at runtime; `None` leaves it to the theme. A theme can give a
+/// particle `density` instead of a count, so the field scales with the canvas.
+///
+/// Set the reactive `scale` signal to tune how node radius, hit area, labels,
+/// edge dashes and arrows scale with zoom (see [`ScaleConfig`]). The theme may
+/// override some of these to suit its look (see [`Theme::scale`]).
+///
+/// Pass a `highlight` config to tune how quickly hover highlights fade in and out,
+/// a `camera` config to set the duration and easing of animated camera moves,
+/// and a `transitions` config for those of layout switches, theme fades and
+/// recoloring.
+///
+/// Camera moves animate rather than jump and are interrupted as soon as the user
+/// grabs, scrolls or zooms. Set the reactive `focus_node` signal to a node ID to
+/// glide to that node, and `zoom_controls = true` to show on-screen zoom buttons.
+/// `reheat_button = true` adds one that shakes up a layout stuck in a poor
+/// arrangement (also on `R`).
+///
+/// Set the reactive `three_d` signal for the experimental 3D mode: the layout
+/// gains depth, nodes shrink with distance and dragging orbits the camera
+/// (nodes can't be dragged or linked meanwhile). Dense graphs sometimes
+/// untangle in 3D.
+///
+/// Pass a `node_draw` hook to draw nodes yourself, e.g. version badges or mini
+/// charts, in place of or on top of the standard node (see
+/// [`NodeRenderInfo`](super::NodeRenderInfo)), and an `edge_draw` hook likewise
+/// for edges (see [`EdgeRenderInfo`](super::EdgeRenderInfo)). Frames are drawn
+/// only when something changes; set `animate_hooks = true` when hooks draw
+/// moving visuals from the clock they are given. Whole layers under the edges
+/// or over the nodes come from `plugins` (see [`RenderPlugin`]), which can
+/// also be added later with [`GraphHandle::add_plugin`](super::GraphHandle::add_plugin).
+///
+/// Pass `anchored` elements, keyed by node ID, to pin HTML to nodes, e.g.
+/// popovers, inputs or buttons: each element's top-left corner follows its
+/// node's center as the layout and camera move (offset it with CSS), and it is
+/// hidden while the node is. A `popover` renders a view for the selected node
+/// (while exactly one is selected), placed beside it on whichever side keeps it
+/// inside the canvas.
+///
+/// Set `layered = true` for large graphs: the background, edges, nodes and the
+/// interaction overlay are drawn on stacked canvases, and a frame repaints only
+/// the ones that changed, so hovering doesn't repaint thousands of edges.
+///
+/// A `paused` signal freezes the layout while set, e.g. to inspect it, and
+/// follows pauses from the `P` shortcut; drawing, panning and zooming carry on.
+/// `pause_button = true` adds an on-screen toggle.
+///
+/// Changes to `data` are applied in place: nodes that remain keep their position
+/// and new ones appear beside their neighbors. Pass a `timeline` of snapshots to
+/// get a scrubber and play button that step through them every `timeline_step`
+/// seconds, animating the graph's evolution.
+///
+/// Nodes and links flagged `hidden` in the data start out hidden. The background
+/// context menu reveals them all or one group at a time; hosts can also list
+/// groups to reveal in the reactive `revealed_groups` signal.
+///
+/// Set the reactive `group_by` signal to the name of a node `attributes` field to
+/// re-derive every node's group from it, recoloring with a short transition.
+///
+/// Set `idle_showcase` to a number of seconds to start a showcase once the graph
+/// has gone that long without input: the camera slowly drifts around the whole
+/// graph while highlights cycle through the best-connected nodes. Meant for
+/// unattended displays; any input ends it.
+///
+/// Set `js_handle` to let the host page push new data without re-mounting:
+/// `window.__impGraph[js_handle].setData(json)` takes a JSON string or object
+/// in the same format as `data` and returns whether it parsed. For companion
+/// views, exports or tests, `positions()` returns every node's graph position
+/// as a `Float32Array` of x, y pairs, ordered as `nodeIds()` lists the nodes;
+/// in Rust, [`GraphHandle::sample_positions`](super::GraphHandle::sample_positions)
+/// does the same.
+///
+/// Printing the page (or [`GraphHandle::set_print_mode`](super::GraphHandle::set_print_mode))
+/// renders the graph for paper: a white background, dark edges, every label,
+/// no animation, at print resolution.
+///
+/// Set `debug_panel` (or press `D`) to show entity counts, cache sizes and WASM
+/// heap usage, refreshed about once a second.
+///
+/// "Follow" in a node's context menu keeps the camera centered on it while the
+/// layout settles; panning hands control back.
+///
+/// A node's context menu can collapse its group into a single meta-node; links
+/// between collapsed groups merge into one thicker edge labeled with their count.
+/// Expanding the meta-node restores the members where it stood.
+///
+/// Nodes are colored by `group`. Pass `cluster_colors` (group name to CSS color)
+/// to replace the built-in imp.lib group colors; a `cluster_colors` map in the data
+/// payload itself takes precedence over both.
+///
+/// Set the reactive `heatmap` signal to color nodes by one of their `metrics`
+/// along a color ramp; a colorbar legend shows the mapped range.
+///
+/// Node size encodes degree by default; set the reactive `sizing` signal to size by
+/// a metric instead, or uniformly. `size_legend = true` shows example circles for
+/// representative values.
+///
+/// Labels declutter as the view zooms out: where two would overlap, the node
+/// with the higher `label_priority` (by default, more links) keeps its label.
+/// The `top_labels` most important labels are always shown.
+///
+/// Set `component_navigator` to list the graph's connected components by size;
+/// clicking one zooms to it, so small fragments drifting at the edges aren't lost.
+///
+/// Selected and highlighted nodes outside the view get arrow chips at the screen
+/// edge pointing toward them; clicking a chip pans there.
+///
+/// Set the reactive `layout` signal to `Layout::Swimlanes` to give each group
+/// its own column, so flows between groups read left to right; the physics only
+/// moves nodes up and down within their lane. `Layout::Axis` instead ties one
+/// axis to a data field, such as dependency depth or a timestamp metric, for
+/// tidy flow diagrams. `Layout::Timeline` spreads nodes by their `timestamp`
+/// above a time axis ruler and arcs the links between them.
+///
+/// Set the reactive `crisp` signal to draw a still layout with node centers on
+/// pixel centers, so anti-aliased edges stop shimmering.
+///
+/// Set the reactive `physics` signal to add forces on top of the simulation,
+/// such as gravity that keeps a sparse graph centered, bounds that keep stray
+/// nodes within a world rectangle, or extra repulsion for hubs (see
+/// [`PhysicsConfig`]).
+///
+/// Set the reactive `fisheye` signal to magnify the area around the cursor
+/// with a fisheye lens while keeping the whole graph in view.
 #[component]
 pub fn ForceGraphCanvas(
 	#[prop(into)] data: Signal<GraphData>,
 	#[prop(default = false)] fullscreen: bool,
 	#[prop(default = None)] width: Option<f64>,
 	#[prop(default = None)] height: Option<f64>,
+	#[prop(into, optional)] fisheye: Signal<bool>,
+	#[prop(into, optional)] snap_grid: Signal<Option<f64>>,
+	#[prop(into, optional)] crisp: Signal<bool>,
+	#[prop(into, optional)] editable: Signal<bool>,
+	#[prop(optional)] on_node_activate: Option<Callback<String>>,
+	#[prop(optional)] on_edit: Option<Callback<GraphData>>,
+	#[prop(optional)] on_view_change: Option<Callback<ViewTransform>>,
+	#[prop(optional)] transform: Option<RwSignal<ViewTransform>>,
+	#[prop(optional)] linked: Option<ViewLink>,
+	#[prop(optional)] hovered: Option<RwSignal<Option<String>>>,
+	#[prop(optional)] on_hover_change: Option<Callback<Option<String>>>,
+	#[prop(optional)] selected: Option<RwSignal<Vec<String>>>,
+	#[prop(optional)] on_background_click: Option<Callback<()>>,
+	#[prop(optional)] context_menu: Option<Callback<ContextTarget, Vec<ContextMenuItem>>>,
+	#[prop(optional)] keymap: Option<Keymap>,
+	#[prop(optional)] highlight: Option<HighlightConfig>,
+	#[prop(optional)] camera: Option<CameraConfig>,
+	#[prop(optional)] transitions: Option<TransitionConfig>,
+	#[prop(into, optional)] focus_node: Signal<Option<String>>,
+	#[prop(default = false)] zoom_controls: bool,
+	#[prop(default = false)] reheat_button: bool,
+	#[prop(optional)] paused: Option<RwSignal<bool>>,
+	#[prop(default = false)] pause_button: bool,
+	#[prop(optional)] timeline: Option<Vec<GraphSnapshot>>,
+	#[prop(optional)] cluster_colors: Option<HashMap<String, String>>,
+	#[prop(into, optional)] revealed_groups: Signal<Vec<String>>,
+	#[prop(into, optional)] group_by: Signal<Option<String>>,
+	#[prop(into, optional)] heatmap: Signal<Option<HeatmapConfig>>,
+	#[prop(into, optional)] sizing: Signal<NodeSizing>,
+	#[prop(into, optional)] layout: Signal<Layout>,
+	#[prop(into, optional)] physics: Signal<PhysicsConfig>,
+	#[prop(into, optional)] three_d: Signal<bool>,
+	#[prop(optional)] node_draw: Option<NodeDrawHook>,
+	#[prop(optional)] edge_draw: Option<EdgeDrawHook>,
+	#[prop(default = false)] animate_hooks: bool,
+	#[prop(optional)] plugins: Vec<Rc<dyn RenderPlugin>>,
+	#[prop(default = false)] layered: bool,
+	#[prop(into, optional)] anchored: Signal<Vec<(String, ViewFn)>>,
+	#[prop(optional)] popover: Option<Callback<GraphNode, AnyView>>,
+	#[prop(into, optional)] scale: Signal<ScaleConfig>,
+	#[prop(into, optional)] theme: Signal<Theme>,
+	#[prop(into, optional)] particles: Signal<Option<bool>>,
+	#[prop(default = false)] size_legend: bool,
+	#[prop(default = 1.5)] timeline_step: f64,
+	#[prop(optional)] idle_showcase: Option<f64>,
+	#[prop(default = false)] debug_panel: bool,
+	#[prop(default = false)] component_navigator: bool,
+	#[prop(default = 10)] top_labels: usize,
+	#[prop(default = false)] settle_indicator: bool,
+	#[prop(optional, into)] js_handle: Option<String>,
+	#[prop(into, optional)] error: Signal<Option<String>>,
+	#[prop(into, optional)] loading: Signal<bool>,
+	#[prop(optional)] on_retry: Option<Callback<()>>,
+	#[prop(optional)] on_layout_start: Option<Callback<()>>,
+	#[prop(optional)] on_layout_settled: Option<Callback<()>>,
 ) -> impl IntoView {
+	let graph_handle = use_graph();
+	let hovered = hovered
+		.or(linked.map(|l| l.hovered))
+		.or(graph_handle.map(|h| h.hovered()));
+	let selected = selected.or(linked.map(|l| l.selected));
+	let transform = transform
+		.or(linked.and_then(|l| l.camera))
+		.or(graph_handle.map(|h| h.transform()));
+	let timeline = StoredValue::new(timeline.unwrap_or_default());
+	let frame = RwSignal::new(0usize);
+	// The data currently shown, before `group_by` re-derives its groups
+	let shown: StoredValue<GraphData> = StoredValue::new(GraphData::default());
+	let show = move |data: GraphData| {
+		if let Some(handle) = graph_handle {
+			provider::publish(handle, &data);
+		}
+		shown.set_value(data);
+	};
+	let regroup = move |data: GraphData| match group_by.get_untracked() {
+		Some(field) => data.grouped_by(&field),
+		None => data,
+	};
+	let playing = RwSignal::new(false);
+	let keymap = keymap.unwrap_or_default();
+	let help_entries = keymap.help_entries();
 	let canvas_ref = NodeRef::<leptos::html::Canvas>::new();
+	let layer_refs: [NodeRef<leptos::html::Canvas>; 3] = std::array::from_fn(|_| NodeRef::new());
+	let anchors_ref = NodeRef::<leptos::html::Div>::new();
+	// The node a popover is shown for: the selection, while it's a single node
+	let popover_node = RwSignal::new(None::<GraphNode>);
 	let context: Rc<RefCell<Option<GraphContext>>> = Rc::new(RefCell::new(None));
-	let animate: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
-	let resize_cb: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
-	let (context_init, animate_init, resize_cb_init) =
-		(context.clone(), animate.clone(), resize_cb.clone());
+	if let Some(handle) = graph_handle {
+		provider::attach(handle, context.clone());
+	}
+	let animate: JsCallback = Rc::new(RefCell::new(None));
+	let resize_cb: JsCallback = Rc::new(RefCell::new(None));
+	let key_cbs: JsKeyCallbacks = Rc::new(RefCell::new(Vec::new()));
+	let print_cbs: JsPrintCallbacks = Rc::new(RefCell::new(Vec::new()));
+	// Keyboard input is only ours while the pointer is over the graph
+	let pointer_inside = Rc::new(Cell::new(false));
+	let (context_init, animate_init, resize_cb_init, key_cbs_init, print_cbs_init, pointer_init) = (
+		context.clone(),
+		animate.clone(),
+		resize_cb.clone(),
+		key_cbs.clone(),
+		print_cbs.clone(),
+		pointer_inside.clone(),
+	);
+	// Window listeners outlive the component unless removed with it
+	let key_listeners = StoredValue::new_local(key_cbs);
+	on_cleanup(move || {
+		if let (Some(window), Some(cbs)) = (web_sys::window(), key_listeners.try_get_value()) {
+			for (event, cb) in ["keydown", "keyup"].into_iter().zip(cbs.borrow().iter()) {
+				let _ =
+					window.remove_event_listener_with_callback(event, cb.as_ref().unchecked_ref());
+			}
+		}
+		key_listeners.dispose();
+	});
+	let print_listeners = StoredValue::new_local(print_cbs);
+	on_cleanup(move || {
+		if let (Some(window), Some(cbs)) = (web_sys::window(), print_listeners.try_get_value()) {
+			for (event, cb) in ["beforeprint", "afterprint"]
+				.into_iter()
+				.zip(cbs.borrow().iter())
+			{
+				let _ =
+					window.remove_event_listener_with_callback(event, cb.as_ref().unchecked_ref());
+			}
+		}
+		print_listeners.dispose();
+	});
+	let menu: RwSignal<Option<OpenMenu>> = RwSignal::new(None);
+	let help_open = RwSignal::new(false);
+	let debug_open = RwSignal::new(debug_panel);
+	let debug_stats: RwSignal<Option<DebugStats>> = RwSignal::new(None);
+	let crumbs: RwSignal<Vec<String>> = RwSignal::new(Vec::new());
+	// Mirror the drill-down trail into a signal for the breadcrumb overlay
+	let sync_crumbs = move |state: &ForceGraphState| {
+		let labels: Vec<String> = state.breadcrumbs.iter().map(|b| b.label.clone()).collect();
+		if crumbs.with_untracked(|current| *current != labels) {
+			crumbs.set(labels);
+		}
+	};
+	let components: RwSignal<Vec<ComponentSummary>> = RwSignal::new(Vec::new());
+	// The graph has no nodes; shows the empty state
+	let empty = RwSignal::new(false);
+	let paused = paused.unwrap_or_else(|| RwSignal::new(false));
+	// How far the layout has come to rest, while it is still moving
+	let settling: RwSignal<Option<f64>> = RwSignal::new(None);
+	// Mirror the connected components into a signal for the navigator
+	let sync_components = move |state: &ForceGraphState| {
+		if !component_navigator {
+			return;
+		}
+		let next = state.component_summaries();
+		if components.with_untracked(|current| *current != next) {
+			components.set(next);
+		}
+	};
+	// Tooltip for the edge under the pointer: screen position and text
+	let tooltip: RwSignal<Option<(f64, f64, String)>> = RwSignal::new(None);
+	let legend: RwSignal<Option<HeatLegend>> = RwSignal::new(None);
+	let sizes: RwSignal<Option<SizeLegend>> = RwSignal::new(None);
+	// Mirror the color and size encodings into signals for the legend overlays
+	let sync_legend = move |c: &GraphContext| {
+		let next = c.state.heat_legend(&c.theme);
+		if legend.with_untracked(|current| *current != next) {
+			legend.set(next);
+		}
+		let next = size_legend
+			.then(|| c.state.size_legend(c.scale.node.radius))
+			.flatten();
+		if sizes.with_untracked(|current| *current != next) {
+			sizes.set(next);
+		}
+	};
+	let cursor = RwSignal::new("grab");
+	// Only notify the canvas style when the cursor actually changes
+	let update_cursor = move |state: &ForceGraphState| {
+		let next = state.cursor();
+		if cursor.get_untracked() != next {
+			cursor.set(next);
+		}
+	};
+
+	Effect::new(move |_| {
+		let Some(canvas) = canvas_ref.get() else {
+			return;
+		};
+		let window: Window = web_sys::window().unwrap();
+
+		let (w, h) = if fullscreen {
+			(
+				window.inner_width().unwrap().as_f64().unwrap(),
+				window.inner_height().unwrap().as_f64().unwrap(),
+			)
+		} else {
+			(
+				width.unwrap_or_else(|| {
+					canvas
+						.parent_element()
+						.map(|p| p.client_width() as f64)
+						.unwrap_or(800.0)
+				}),
+				height.unwrap_or_else(|| {
+					canvas
+						.parent_element()
+						.map(|p| p.client_height() as f64)
+						.unwrap_or(600.0)
+				}),
+			)
+		};
+		canvas.set_width(w as u32);
+		canvas.set_height(h as u32);
+		let layer_stack = layered
+			.then(|| layer_refs.map(|r| r.get_untracked()))
+			.and_then(|[a, b, c]| LayerStack::new([a?, b?, c?]))
+			.map(|mut stack| {
+				stack.resize(w as u32, h as u32);
+				stack
+			});
+
+		let ctx: CanvasRenderingContext2d = canvas
+			.get_context("2d")
+			.unwrap()
+			.unwrap()
+			.dyn_into()
+			.unwrap();
+
+		let theme = theme.get_untracked();
+		warn_theme(&theme);
+		#[cfg(feature = "particles")]
+		let particles_enabled = particles.get_untracked();
+		#[cfg(feature = "particles")]
+		let particle_system = particles_enabled
+			.unwrap_or(theme.particles.enabled)
+			.then(|| ParticleSystem::new(&theme.particles, w, h));
+
+		let initial = timeline.with_value(|snapshots| {
+			snapshots
+				.first()
+				.map_or_else(|| data.get_untracked(), |s| s.data.clone())
+		});
+		show(initial.clone());
+		let initial = regroup(initial);
+		let mut state = match &cluster_colors {
+			Some(colors) => ForceGraphState::new_with_colors(&initial, w, h, &theme, colors),
+			None => ForceGraphState::new(&initial, w, h, &theme),
+		};
+		state.fisheye.enabled = fisheye.get_untracked();
+		state.grid = snap_grid.get_untracked();
+		state.crisp = crisp.get_untracked();
+		state.physics = physics.get_untracked();
+		state.animation_running = !paused.get_untracked();
+		if let Some(signal) = selected {
+			state.select_ids(&signal.get_untracked());
+		}
+		state.set_3d(three_d.get_untracked());
+		state.apply_charge();
+		if let Some(view) = transform.map(|t| t.get_untracked()).filter(|v| v.k > 0.0) {
+			state.transform = ViewTransform {
+				k: view.k.clamp(0.1, 10.0),
+				..view
+			};
+		}
+		state.showcase.delay = idle_showcase;
+		state.top_labels = top_labels;
+		state.editable = editable.get_untracked();
+		state.highlight.config = highlight.clone().unwrap_or_default();
+		state.camera_config = camera.clone().unwrap_or_default();
+		state.transitions = transitions.clone().unwrap_or_default();
+		state.heatmap = heatmap.get_untracked();
+		state.recolor(&theme);
+		state.sizing = sizing.get_untracked();
+		state.apply_sizing();
+		state.layout = layout.get_untracked();
+		state.apply_layout();
+		for group in revealed_groups.get_untracked() {
+			state.set_group_hidden(&group, false);
+		}
+
+		let graph_context = GraphContext {
+			state,
+			recorder: Recorder::default(),
+			clip: ClipCapture::new(canvas.clone()),
+			canvas: canvas.clone(),
+			print: None,
+			theme_fade: None,
+			scale: scale.get_untracked().with_overrides(&theme.scale),
+			theme,
+			hooks: RenderHooks {
+				node: node_draw.clone(),
+				edge: edge_draw.clone(),
+				plugins: plugins.clone(),
+				animated: animate_hooks,
+			},
+			layers: layer_stack,
+			#[cfg(feature = "particles")]
+			particles: particle_system,
+			#[cfg(feature = "particles")]
+			particles_enabled,
+		};
+		sync_legend(&graph_context);
+		sync_components(&graph_context.state);
+		*context_init.borrow_mut() = Some(graph_context);
+
+		if fullscreen {
+			let (context_resize, canvas_resize) = (context_init.clone(), canvas.clone());
+			*resize_cb_init.borrow_mut() = Some(Closure::new(move || {
+				let win: Window = web_sys::window().unwrap();
+				let (nw, nh) = (
+					win.inner_width().unwrap().as_f64().unwrap(),
+					win.inner_height().unwrap().as_f64().unwrap(),
+				);
+				if let Some(ref mut c) = *context_resize.borrow_mut() {
+					// The print layout's size is not the screen's
+					if c.is_printing() {
+						return;
+					}
+					canvas_resize.set_width(nw as u32);
+					canvas_resize.set_height(nh as u32);
+					if let Some(stack) = &mut c.layers {
+						stack.resize(nw as u32, nh as u32);
+					}
+					c.state.resize(nw, nh);
+					c.resize_particles(nw, nh);
+				}
+			}));
+			if let Some(ref cb) = *resize_cb_init.borrow() {
+				let _ =
+					window.add_event_listener_with_callback("resize", cb.as_ref().unchecked_ref());
+			}
+		}
+
+		// Keys are tracked at window level since the canvas rarely has focus
+		let (context_kd, pointer_kd, keymap_kd) =
+			(context_init.clone(), pointer_init.clone(), keymap.clone());
+		let keydown = Closure::<dyn FnMut(KeyboardEvent)>::new(move |ev: KeyboardEvent| {
+			if !pointer_kd.get() || is_editable_target(&ev) {
+				return;
+			}
+			if let Some(ref mut c) = *context_kd.borrow_mut() {
+				c.state.wake();
+			}
+			if ev.code() == "Space" {
+				ev.prevent_default();
+				if let Some(ref mut c) = *context_kd.borrow_mut() {
+					c.state.pan.space_held = true;
+				}
+				return;
+			}
+			if (ev.ctrl_key() || ev.meta_key()) && ev.key().eq_ignore_ascii_case("z") {
+				ev.prevent_default();
+				if let Some(ref mut c) = *context_kd.borrow_mut() {
+					if ev.shift_key() {
+						c.state.redo();
+					} else {
+						c.state.undo();
+					}
+				}
+				return;
+			}
+			// Leave other browser and OS chords (Ctrl+0, Cmd+F, ...) alone
+			if ev.ctrl_key() || ev.meta_key() || ev.alt_key() {
+				return;
+			}
+			let Some(action) = keymap_kd.action_for(&ev.key()) else {
+				return;
+			};
+			ev.prevent_default();
+			match action {
+				KeyAction::ToggleHelp => help_open.update(|open| *open = !*open),
+				KeyAction::ToggleDebug => debug_open.update(|open| *open = !*open),
+				KeyAction::ClearSelection => {
+					menu.set(None);
+					help_open.set(false);
+				}
+				_ => {}
+			}
+			let mut edited = None;
+			if let Some(ref mut c) = *context_kd.borrow_mut() {
+				let deletes = action == KeyAction::DeleteSelection
+					&& c.state.editable
+					&& !c.state.selection.is_empty();
+				apply_key_action(&mut c.state, action);
+				sync_crumbs(&c.state);
+				sync_components(&c.state);
+				edited = deletes.then(|| c.state.export_data());
+			}
+			if let (Some(data), Some(cb)) = (edited, on_edit) {
+				cb.run(data);
+			}
+		});
+		let context_ku = context_init.clone();
+		let keyup = Closure::<dyn FnMut(KeyboardEvent)>::new(move |ev: KeyboardEvent| {
+			if ev.code() == "Space"
+				&& let Some(ref mut c) = *context_ku.borrow_mut()
+			{
+				c.state.pan.space_held = false;
+			}
+		});
+		let _ =
+			window.add_event_listener_with_callback("keydown", keydown.as_ref().unchecked_ref());
+		let _ = window.add_event_listener_with_callback("keyup", keyup.as_ref().unchecked_ref());
+		*key_cbs_init.borrow_mut() = vec![keydown, keyup];
+
+		// Ctrl+P prints a light, fully labeled diagram at print resolution
+		let print_listener = |on: bool| {
+			let context_print = context_init.clone();
+			Closure::<dyn FnMut()>::new(move || {
+				if let Some(ref mut c) = *context_print.borrow_mut() {
+					c.set_print_mode(on);
+				}
+			})
+		};
+		let (before_print, after_print) = (print_listener(true), print_listener(false));
+		let _ = window
+			.add_event_listener_with_callback("beforeprint", before_print.as_ref().unchecked_ref());
+		let _ = window
+			.add_event_listener_with_callback("afterprint", after_print.as_ref().unchecked_ref());
+		*print_cbs_init.borrow_mut() = vec![before_print, after_print];
+
+		// Labels are measured and pre-rendered in idle time; frames only read the caches
+		let idle_scheduled = Rc::new(Cell::new(false));
+		let (context_idle, ctx_idle, idle_done) =
+			(context_init.clone(), ctx.clone(), idle_scheduled.clone());
+		let prepare_labels =
+			Closure::<dyn FnMut(IdleDeadline)>::new(move |deadline: IdleDeadline| {
+				idle_done.set(false);
+				if let Some(ref mut c) = *context_idle.borrow_mut() {
+					c.prepare_labels(&ctx_idle, || deadline.time_remaining());
+				}
+			});
+
+		let (context_anim, animate_inner) = (context_init.clone(), animate_init.clone());
+		let canvas_replay = canvas.clone();
+		let (mut frames_drawn, mut frames_skipped) = (0, 0);
+		let (mut reported_view, mut since_view_report) = (None, 0.0);
+		let mut layout_settled = None;
+		let (mut reported_hover, mut reported_selection) = (None::<String>, None);
+		// Real time since the last frame not yet simulated
+		let (mut last_frame, mut physics_debt) = (None::<f64>, 0.0);
+		*animate_init.borrow_mut() = Some(Closure::new(move || {
+			// Replayed input goes through the canvas's handlers, so fire it unborrowed
+			let replayed = context_anim
+				.borrow_mut()
+				.as_mut()
+				.map(|c| c.recorder.advance());
+			for input in replayed.unwrap_or_default() {
+				recording::dispatch(&canvas_replay, &input);
+			}
+			let (mut view_changed, mut view_now, mut hover_changed) = (None, None, None);
+			let (mut now_empty, mut now_settling, mut settled_changed) = (None, None, None);
+			let (mut now_paused, mut popover_changed, mut hover_reported) = (None, None, None);
+			let mut selection_changed = None;
+			let now = js_sys::Date::now() / 1000.0;
+			let elapsed = last_frame.map_or(PHYSICS_STEP, |t| (now - t).clamp(0.0, MAX_FRAME_TIME));
+			last_frame = Some(now);
+			if let Some(ref mut c) = *context_anim.borrow_mut() {
+				// Recorded sessions step once per frame, so they replay identically
+				let lockstep = c.recorder.is_recording() || c.recorder.is_replaying();
+				let dt = if lockstep { PHYSICS_STEP } else { elapsed };
+				physics_debt = if lockstep {
+					PHYSICS_STEP
+				} else {
+					physics_debt + dt
+				};
+				if c.state.animation_running {
+					let mut steps = 0;
+					while physics_debt >= PHYSICS_STEP
+						&& steps < c.state.physics.max_substeps.max(1)
+					{
+						c.state.tick(PHYSICS_STEP as f32);
+						physics_debt -= PHYSICS_STEP;
+						steps += 1;
+					}
+				}
+				// Time the budget couldn't cover is dropped rather than owed
+				if physics_debt >= PHYSICS_STEP || !c.state.animation_running {
+					physics_debt = 0.0;
+				}
+				c.tick_particles(dt);
+				c.tick_theme(dt);
+				c.state.tick_animation(dt);
+				c.state.tick_camera(dt);
+				c.state.tick_follow(dt);
+				c.state.tick_indicators(dt);
+				c.state.tick_showcase(dt);
+				c.state.tick_colors(dt);
+				c.state.tick_layout(dt);
+				c.state.prepare_frame();
+				view_now = transform.is_some().then(|| c.state.transform.clone());
+				if paused.get_untracked() != c.is_paused() {
+					now_paused = Some(c.is_paused());
+				}
+				if layout_settled != Some(c.state.is_settled()) {
+					layout_settled = Some(c.state.is_settled());
+					settled_changed = layout_settled;
+				}
+				if settle_indicator {
+					// Coarse steps keep the bar from re-rendering every frame
+					let progress = c.state.settle_progress().map(|p| (p * 50.0).round() / 50.0);
+					if settling.get_untracked() != progress {
+						now_settling = Some(progress);
+					}
+				}
+				let is_empty = c.state.graph.get_graph().node_count() == 0;
+				if empty.get_untracked() != is_empty {
+					now_empty = Some(is_empty);
+				}
+				if hovered.is_some() || on_hover_change.is_some() {
+					let id = c
+						.state
+						.highlight
+						.hovered_node
+						.and_then(|idx| c.state.node(idx));
+					let id = id.map(|node| node.data.user_data.id.as_str());
+					// Write only changes made here, and leave alone IDs this graph
+					// lacks: a linked view may have them hovered or selected
+					if reported_hover.as_deref() != id {
+						if let Some(signal) = hovered
+							&& signal.with_untracked(|current| {
+								let known = current
+									.as_deref()
+									.filter(|id| c.state.node_index(id).is_some());
+								known != id
+							}) {
+							hover_changed = Some(id.map(str::to_string));
+						}
+						reported_hover = id.map(str::to_string);
+						hover_reported = Some(reported_hover.clone());
+					}
+				}
+				if let Some(signal) = selected {
+					let ids = c.state.selected_ids();
+					if reported_selection.as_ref() != Some(&ids) {
+						let differs = signal.with_untracked(|current| {
+							let mut known: Vec<&String> = current
+								.iter()
+								.filter(|id| c.state.node_index(id).is_some())
+								.collect();
+							known.sort();
+							known.dedup();
+							!known.iter().copied().eq(ids.iter())
+						});
+						if differs {
+							selection_changed = Some(ids.clone());
+						}
+						reported_selection = Some(ids);
+					}
+				}
+				if popover.is_some() {
+					let mut selected = c.state.selection.iter();
+					let single = match (selected.next(), selected.next()) {
+						(Some(idx), None) => c.state.node(idx),
+						_ => None,
+					};
+					let single = single.map(|node| &node.data.user_data.spec);
+					let id = single.map(|node| node.id.as_str());
+					if popover_node
+						.with_untracked(|current| current.as_ref().map(|n| n.id.as_str()) != id)
+					{
+						popover_changed = Some(single.cloned());
+					}
+				}
+				// Place a new popover with the next frame
+				if popover_changed.is_some() {
+					c.state.mark_layers_dirty(Layers::OVERLAY);
+				}
+				since_view_report += dt;
+				if on_view_change.is_some()
+					&& since_view_report >= VIEW_REPORT_INTERVAL
+					&& reported_view.as_ref() != Some(&c.state.transform)
+				{
+					reported_view = Some(c.state.transform.clone());
+					view_changed = reported_view.clone();
+					since_view_report = 0.0;
+				}
+				// Settled frames are identical to the last one; skip drawing them
+				let layers = c.frame_layers();
+				if layers.any() {
+					c.render(&ctx, layers);
+					if let Some(container) = anchors_ref.get_untracked() {
+						anchors::position_anchors(&container, &c.state);
+					}
+					c.state.frame_drawn();
+					frames_drawn += 1;
+				} else {
+					frames_skipped += 1;
+				}
+				if frames_drawn + frames_skipped >= DEBUG_REFRESH_FRAMES {
+					if debug_open.get_untracked() {
+						debug_stats.set(Some(DebugStats {
+							css_strings: render::css_cache_entries(),
+							particles: c.particle_count(),
+							heap_bytes: wasm_heap_bytes(),
+							frames_drawn,
+							frames_skipped,
+							..c.state.debug_stats()
+						}));
+					}
+					(frames_drawn, frames_skipped) = (0, 0);
+				}
+				let labels_pending = c.state.labels.has_pending()
+					|| c.state.sprites.has_pending()
+					|| !c.state.placed_labels.is_current(c.state.placement_key());
+				if labels_pending && !idle_scheduled.get() {
+					let window = web_sys::window().unwrap();
+					match window.request_idle_callback(prepare_labels.as_ref().unchecked_ref()) {
+						Ok(_) => idle_scheduled.set(true),
+						Err(_) => {
+							let end = js_sys::Date::now() + LABEL_SLICE_MS;
+							c.prepare_labels(&ctx, || end - js_sys::Date::now());
+						}
+					}
+				}
+			}
+			if let (Some(view), Some(cb)) = (view_changed, on_view_change) {
+				cb.run(view);
+			}
+			// Only write actual changes, so views sharing the signal don't echo each other
+			if let (Some(view), Some(signal)) = (view_now, transform)
+				&& signal.with_untracked(|current| *current != view)
+			{
+				signal.set(view);
+			}
+			if let (Some(id), Some(signal)) = (hover_changed, hovered) {
+				signal.set(id);
+			}
+			if let (Some(ids), Some(signal)) = (selection_changed, selected) {
+				signal.set(ids);
+			}
+			if let (Some(id), Some(cb)) = (hover_reported, on_hover_change) {
+				cb.run(id);
+			}
+			if let Some(is_empty) = now_empty {
+				empty.set(is_empty);
+			}
+			if let Some(is_paused) = now_paused {
+				paused.set(is_paused);
+			}
+			if let Some(node) = popover_changed {
+				popover_node.set(node);
+			}
+			if let Some(progress) = now_settling {
+				settling.set(progress);
+			}
+			if let Some(settled) = settled_changed {
+				let cb = if settled {
+					on_layout_settled
+				} else {
+					on_layout_start
+				};
+				if let Some(cb) = cb {
+					cb.run(());
+				}
+			}
+			if let Some(ref cb) = *animate_inner.borrow() {
+				let _ = web_sys::window()
+					.unwrap()
+					.request_animation_frame(cb.as_ref().unchecked_ref());
+			}
+		}));
+		if let Some(ref cb) = *animate_init.borrow() {
+			let _ = window.request_animation_frame(cb.as_ref().unchecked_ref());
+		}
+	});
+
+	if let Some(signal) = transform {
+		let context_view = context.clone();
+		Effect::new(move |prev: Option<()>| {
+			let next = signal.get();
+			if prev.is_none() || next.k <= 0.0 {
+				return;
+			}
+			if let Some(ref mut c) = *context_view.borrow_mut()
+				&& c.state.transform != next
+			{
+				c.state.camera = None;
+				c.state.transform = ViewTransform {
+					k: next.k.clamp(0.1, 10.0),
+					..next
+				};
+			}
+		});
+	}
+
+	if let Some(signal) = selected {
+		let context_select = context.clone();
+		Effect::new(move |prev: Option<()>| {
+			let ids = signal.get();
+			if prev.is_none() {
+				return;
+			}
+			if let Some(ref mut c) = *context_select.borrow_mut() {
+				c.state.select_ids(&ids);
+			}
+		});
+	}
+
+	if let Some(signal) = hovered {
+		let context_hover = context.clone();
+		Effect::new(move |prev: Option<()>| {
+			let id = signal.get();
+			if prev.is_none() {
+				return;
+			}
+			if let Some(ref mut c) = *context_hover.borrow_mut() {
+				let node = id.as_deref().and_then(|id| c.state.node_index(id));
+				c.state.set_hover(node);
+			}
+		});
+	}
+
+	// New anchored elements are placed with the next frame
+	let context_anchors = context.clone();
+	Effect::new(move |_| {
+		anchored.track();
+		if let Some(ref mut c) = *context_anchors.borrow_mut() {
+			c.state.mark_layers_dirty(Layers::OVERLAY);
+		}
+	});
+
+	let context_fe = context.clone();
+	Effect::new(move |_| {
+		let enabled = fisheye.get();
+		if let Some(ref mut c) = *context_fe.borrow_mut() {
+			c.state.set_fisheye(enabled);
+		}
+	});
+
+	let context_grid = context.clone();
+	Effect::new(move |_| {
+		let grid = snap_grid.get();
+		if let Some(ref mut c) = *context_grid.borrow_mut() {
+			c.state.set_grid(grid);
+		}
+	});
+
+	let context_crisp = context.clone();
+	Effect::new(move |_| {
+		let enabled = crisp.get();
+		if let Some(ref mut c) = *context_crisp.borrow_mut() {
+			c.state.set_crisp(enabled);
+		}
+	});
+
+	let context_edit = context.clone();
+	Effect::new(move |_| {
+		let enabled = editable.get();
+		if let Some(ref mut c) = *context_edit.borrow_mut() {
+			c.state.set_editable(enabled);
+		}
+	});
+
+	let context_focus = context.clone();
+	Effect::new(move |_| {
+		let Some(id) = focus_node.get() else {
+			return;
+		};
+		if let Some(ref mut c) = *context_focus.borrow_mut()
+			&& let Some(target) = c
+				.state
+				.node_index(&id)
+				.and_then(|idx| c.state.focus_node_transform(idx))
+		{
+			c.state.animate_to(target);
+		}
+	});
+
+	// Latest data from the `data` signal or the host page's `setData`, whichever
+	// changed last
+	let latest = RwSignal::new(None::<GraphData>);
+	// Why the host page's last `setData` was rejected
+	let rejected = RwSignal::new(None::<String>);
+	Effect::new(move |prev: Option<()>| {
+		let data = data.get();
+		if prev.is_some() {
+			latest.set(Some(data));
+		}
+	});
+	if let Some(name) = js_handle {
+		let closures = register_js_handle(&name, latest, rejected, context.clone());
+		let handle = StoredValue::new_local(closures);
+		on_cleanup(move || {
+			unregister_js_handle(&name);
+			handle.dispose();
+		});
+	}
+
+	let context_data = context.clone();
+	Effect::new(move |_| {
+		// Stays `None` until there is new data; the mount effect built the graph
+		let Some(data) = latest.get() else {
+			return;
+		};
+		menu.set(None);
+		show(data.clone());
+		if let Some(ref mut c) = *context_data.borrow_mut() {
+			c.state.apply_data(&regroup(data), &c.theme);
+			sync_crumbs(&c.state);
+			sync_components(&c.state);
+			sync_legend(c);
+		}
+	});
+
+	let context_frame = context.clone();
+	Effect::new(move |prev: Option<usize>| {
+		let index = frame.get();
+		if prev.is_none_or(|p| p == index) {
+			return index;
+		}
+		menu.set(None);
+		if let Some(ref mut c) = *context_frame.borrow_mut() {
+			if let Some(data) =
+				timeline.with_value(|snapshots| snapshots.get(index).map(|s| s.data.clone()))
+			{
+				show(data.clone());
+				c.state.apply_data(&regroup(data), &c.theme);
+			}
+			sync_crumbs(&c.state);
+			sync_components(&c.state);
+			sync_legend(c);
+		}
+		index
+	});
+
+	let context_group = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		group_by.track();
+		if prev.is_none() {
+			return;
+		}
+		if let Some(ref mut c) = *context_group.borrow_mut() {
+			c.state.apply_data(&regroup(shown.get_value()), &c.theme);
+			sync_crumbs(&c.state);
+			sync_components(&c.state);
+			sync_legend(c);
+		}
+	});
+
+	let context_heat = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		let config = heatmap.get();
+		if prev.is_none() {
+			return;
+		}
+		if let Some(ref mut c) = *context_heat.borrow_mut() {
+			c.state.set_heatmap(config, &c.theme);
+			sync_legend(c);
+		}
+	});
+
+	let context_reveal = context.clone();
+	Effect::new(move |prev: Option<Vec<String>>| {
+		let groups = revealed_groups.get();
+		if let Some(prev) = prev
+			&& let Some(ref mut c) = *context_reveal.borrow_mut()
+		{
+			for group in prev.iter().filter(|g| !groups.contains(g)) {
+				c.state.set_group_hidden(group, true);
+			}
+			for group in groups.iter().filter(|g| !prev.contains(g)) {
+				c.state.set_group_hidden(group, false);
+			}
+			sync_crumbs(&c.state);
+			sync_components(&c.state);
+		}
+		groups
+	});
 
-	Effect::new(move |_| {
-		let Some(canvas) = canvas_ref.get() else {
+	let context_size = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		let next = sizing.get();
+		if prev.is_none() {
 			return;
-		};
-		let canvas: HtmlCanvasElement = canvas.into();
-		let window: Window = web_sys::window().unwrap();
+		}
+		if let Some(ref mut c) = *context_size.borrow_mut() {
+			c.state.set_sizing(next);
+			sync_legend(c);
+		}
+	});
 
-		let (w, h) = if fullscreen {
-			(
-				window.inner_width().unwrap().as_f64().unwrap(),
-				window.inner_height().unwrap().as_f64().unwrap(),
-			)
-		} else {
-			(
-				width.unwrap_or_else(|| {
-					canvas
-						.parent_element()
-						.map(|p| p.client_width() as f64)
-						.unwrap_or(800.0)
-				}),
-				height.unwrap_or_else(|| {
-					canvas
-						.parent_element()
-						.map(|p| p.client_height() as f64)
-						.unwrap_or(600.0)
-				}),
-			)
-		};
-		canvas.set_width(w as u32);
-		canvas.set_height(h as u32);
+	let context_scale = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		let next = scale.get();
+		if prev.is_none() {
+			return;
+		}
+		if let Some(ref mut c) = *context_scale.borrow_mut() {
+			c.set_scale(next);
+			sync_legend(c);
+		}
+	});
 
-		let ctx: CanvasRenderingContext2d = canvas
-			.get_context("2d")
-			.unwrap()
-			.unwrap()
-			.dyn_into()
-			.unwrap();
+	let context_theme = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		let next = theme.get();
+		if prev.is_none() {
+			return;
+		}
+		warn_theme(&next);
+		if let Some(ref mut c) = *context_theme.borrow_mut() {
+			c.set_theme(next, scale.get_untracked());
+			sync_legend(c);
+		}
+	});
 
-		let theme = Theme::default();
-		let particles = if theme.particles.enabled {
-			Some(ParticleSystem::new(&theme.particles, w, h))
-		} else {
-			None
-		};
+	let context_particles = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		let enabled = particles.get();
+		if prev.is_none() {
+			return;
+		}
+		if let Some(ref mut c) = *context_particles.borrow_mut() {
+			c.set_particles(enabled);
+		}
+	});
 
-		*context_init.borrow_mut() = Some(GraphContext {
-			state: ForceGraphState::new(&data.get(), w, h, &theme),
-			scale: ScaleConfig::default(),
-			theme,
-			particles,
-		});
+	let context_paused = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		let next = paused.get();
+		if prev.is_none() {
+			return;
+		}
+		if let Some(ref mut c) = *context_paused.borrow_mut()
+			&& c.is_paused() != next
+		{
+			c.set_paused(next);
+		}
+	});
 
-		if fullscreen {
-			let (context_resize, canvas_resize) = (context_init.clone(), canvas.clone());
-			*resize_cb_init.borrow_mut() = Some(Closure::new(move || {
-				let win: Window = web_sys::window().unwrap();
-				let (nw, nh) = (
-					win.inner_width().unwrap().as_f64().unwrap(),
-					win.inner_height().unwrap().as_f64().unwrap(),
-				);
-				canvas_resize.set_width(nw as u32);
-				canvas_resize.set_height(nh as u32);
-				if let Some(ref mut c) = *context_resize.borrow_mut() {
-					c.state.resize(nw, nh);
-					if let Some(ref mut ps) = c.particles {
-						ps.resize(nw, nh);
-					}
-				}
-			}));
-			if let Some(ref cb) = *resize_cb_init.borrow() {
-				let _ =
-					window.add_event_listener_with_callback("resize", cb.as_ref().unchecked_ref());
+	let context_3d = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		let next = three_d.get();
+		if prev.is_none() {
+			return;
+		}
+		if let Some(ref mut c) = *context_3d.borrow_mut() {
+			c.state.wake();
+			c.state.set_3d(next);
+		}
+	});
+
+	let context_physics = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		let next = physics.get();
+		if prev.is_none() {
+			return;
+		}
+		if let Some(ref mut c) = *context_physics.borrow_mut() {
+			c.state.wake();
+			c.state.set_physics(next);
+		}
+	});
+
+	let context_layout = context.clone();
+	Effect::new(move |prev: Option<()>| {
+		let next = layout.get();
+		if prev.is_none() {
+			return;
+		}
+		if let Some(ref mut c) = *context_layout.borrow_mut() {
+			c.state.set_layout(next);
+			if let Some(target) = c.state.fit_all_transform() {
+				c.state.animate_to(target);
 			}
 		}
+	});
 
-		let (context_anim, animate_inner) = (context_init.clone(), animate_init.clone());
-		*animate_init.borrow_mut() = Some(Closure::new(move || {
-			if let Some(ref mut c) = *context_anim.borrow_mut() {
-				let dt = 0.016;
-				if c.state.animation_running {
-					c.state.tick(dt as f32);
+	let interval: StoredValue<Option<IntervalHandle>> = StoredValue::new(None);
+	Effect::new(move |_| {
+		if let Some(handle) = interval.get_value() {
+			handle.clear();
+			interval.set_value(None);
+		}
+		if !playing.get() {
+			return;
+		}
+		let last = timeline.with_value(|snapshots| snapshots.len().saturating_sub(1));
+		let step = std::time::Duration::from_secs_f64(timeline_step.max(0.05));
+		let handle = set_interval_with_handle(
+			move || {
+				let next = frame.get_untracked() + 1;
+				if next >= last {
+					playing.set(false);
 				}
-				if let Some(ref mut ps) = c.particles {
-					ps.update(dt);
+				if next <= last {
+					frame.set(next);
 				}
-				render::render(&c.state, &ctx, &c.scale, &c.theme, c.particles.as_ref());
-			}
-			if let Some(ref cb) = *animate_inner.borrow() {
-				let _ = web_sys::window()
-					.unwrap()
-					.request_animation_frame(cb.as_ref().unchecked_ref());
-			}
-		}));
-		if let Some(ref cb) = *animate_init.borrow() {
-			let _ = window.request_animation_frame(cb.as_ref().unchecked_ref());
+			},
+			step,
+		);
+		interval.set_value(handle.ok());
+	});
+	on_cleanup(move || {
+		if let Some(handle) = interval.get_value() {
+			handle.clear();
 		}
 	});
 
 	let context_md = context.clone();
 	let on_mousedown = move |ev: MouseEvent| {
-		let canvas: HtmlCanvasElement = canvas_ref.get().unwrap().into();
+		menu.set(None);
+		// Right button belongs to the context menu
+		if ev.button() == 2 {
+			return;
+		}
+		let canvas: HtmlCanvasElement = canvas_ref.get().unwrap();
 		let rect = canvas.get_bounding_client_rect();
 		let (x, y) = (
 			ev.client_x() as f64 - rect.left(),
@@ -154,30 +1841,70 @@ pub fn ForceGraphCanvas(
 		);
 
 		if let Some(ref mut c) = *context_md.borrow_mut() {
-			if let Some(idx) = c.state.node_at_position(x, y, &c.scale) {
-				c.state.drag.active = true;
-				c.state.drag.node_idx = Some(idx);
-				c.state.drag.start_x = x;
-				c.state.drag.start_y = y;
-				c.state.graph.visit_nodes(|node| {
-					if node.index() == idx {
-						c.state.drag.node_start_x = node.x();
-						c.state.drag.node_start_y = node.y();
-					}
-				});
+			if !c.recorder.capture(InputKind::MouseDown, x, y, &ev) {
+				return;
+			}
+			c.state.mark_layers_dirty(Layers::POINTER);
+			c.state.wake();
+			// Any direct manipulation interrupts an animated camera move
+			c.state.camera = None;
+			c.state.gesture.begin(x, y);
+
+			// Off-screen indicator chips jump to their node
+			if ev.button() == 0
+				&& let Some(idx) = c.state.indicator_at(x, y)
+			{
+				if let Some(target) = c.state.center_node_transform(idx) {
+					c.state.animate_to(target);
+				}
+				return;
+			}
+
+			// Middle-drag and space-drag always pan, even over a node
+			if ev.button() == 1 || c.state.pan.space_held {
+				ev.prevent_default();
+				c.state.begin_pan(x, y);
+			} else if ev.alt_key() {
+				c.state.lasso.active = true;
+				c.state.lasso.points = vec![(x, y)];
+			} else if ev.shift_key() {
+				c.state.box_zoom = BoxZoomState {
+					active: true,
+					start_x: x,
+					start_y: y,
+					current_x: x,
+					current_y: y,
+				};
+			} else if let Some(space) = &mut c.state.space {
+				// Nodes have no single plane to be dragged in; dragging orbits instead
+				space.grab(x, y);
+			} else if let Some(idx) = c.state.node_at_position(x, y, &c.scale) {
+				if c.state.editable {
+					// In edit mode dragging out of a node draws a new link
+					c.state.link_draft = Some(LinkDraft { source: idx, x, y });
+				} else {
+					c.state.drag.active = true;
+					c.state.drag.node_idx = Some(idx);
+					c.state.drag.before = c.state.snapshot_nodes(&[idx]);
+					c.state.drag.start_x = x;
+					c.state.drag.start_y = y;
+					c.state.graph.visit_nodes(|node| {
+						if node.index() == idx {
+							c.state.drag.node_start_x = node.x();
+							c.state.drag.node_start_y = node.y();
+						}
+					});
+				}
 			} else {
-				c.state.pan.active = true;
-				c.state.pan.start_x = x;
-				c.state.pan.start_y = y;
-				c.state.pan.transform_start_x = c.state.transform.x;
-				c.state.pan.transform_start_y = c.state.transform.y;
+				c.state.begin_pan(x, y);
 			}
+			update_cursor(&c.state);
 		}
 	};
 
 	let context_mm = context.clone();
 	let on_mousemove = move |ev: MouseEvent| {
-		let canvas: HtmlCanvasElement = canvas_ref.get().unwrap().into();
+		let canvas: HtmlCanvasElement = canvas_ref.get().unwrap();
 		let rect = canvas.get_bounding_client_rect();
 		let (x, y) = (
 			ev.client_x() as f64 - rect.left(),
@@ -185,19 +1912,64 @@ pub fn ForceGraphCanvas(
 		);
 
 		if let Some(ref mut c) = *context_mm.borrow_mut() {
+			if !c.recorder.capture(InputKind::MouseMove, x, y, &ev) {
+				return;
+			}
+			// Moving nodes or the camera repaints everything by itself; the lens
+			// moves every node near the pointer
+			if c.state.fisheye.is_active() {
+				c.state.mark_dirty();
+			} else {
+				c.state.mark_layers_dirty(Layers::POINTER);
+			}
+			c.state.wake();
+			c.state.pointer = Some((x, y));
+			c.state.fisheye.focus = Some((x, y));
+			c.state.gesture.update(x, y);
+
 			// Update hover state when not dragging
 			if !c.state.drag.active {
 				let hovered = c.state.node_at_position(x, y, &c.scale);
 				c.state.set_hover(hovered);
 			}
 
-			if c.state.drag.active {
+			let idle = !(c.state.drag.active
+				|| c.state.pan.active
+				|| c.state.lasso.active
+				|| c.state.box_zoom.active
+				|| c.state.link_draft.is_some());
+			let tip = match c.state.highlight.hovered_node {
+				_ if !idle => None,
+				Some(idx) => c.state.node_tooltip(idx),
+				None => c
+					.state
+					.edge_at_position(x, y)
+					.and_then(|(src, tgt)| c.state.edge_tooltip(src, tgt)),
+			};
+			match tip {
+				Some(text) => tooltip.set(Some((x, y, text))),
+				None if tooltip.with_untracked(Option::is_some) => tooltip.set(None),
+				None => {}
+			}
+
+			if let Some(ref mut draft) = c.state.link_draft {
+				draft.x = x;
+				draft.y = y;
+			} else if let Some(space) = c.state.space.as_mut().filter(|s| s.is_grabbed()) {
+				space.drag_to(x, y);
+			} else if c.state.lasso.active {
+				c.state.lasso.points.push((x, y));
+			} else if c.state.box_zoom.active {
+				c.state.box_zoom.current_x = x;
+				c.state.box_zoom.current_y = y;
+			} else if c.state.drag.active && c.state.gesture.moved {
 				if let Some(idx) = c.state.drag.node_idx {
+					c.state.heat_around(idx);
 					let (dx, dy) = (
 						(x - c.state.drag.start_x) / c.state.transform.k,
 						(y - c.state.drag.start_y) / c.state.transform.k,
 					);
-					let (nx, ny) = (
+					let (nx, ny) = c.state.snap(
 						c.state.drag.node_start_x + dx as f32,
 						c.state.drag.node_start_y + dy as f32,
 					);
@@ -213,67 +1985,702 @@ pub fn ForceGraphCanvas(
 				c.state.transform.x = c.state.pan.transform_start_x + (x - c.state.pan.start_x);
 				c.state.transform.y = c.state.pan.transform_start_y + (y - c.state.pan.start_y);
 			}
+			update_cursor(&c.state);
 		}
 	};
 
 	let context_mu = context.clone();
-	let on_mouseup = move |_: MouseEvent| {
+	let on_mouseup = move |ev: MouseEvent| {
+		let canvas: HtmlCanvasElement = canvas_ref.get().unwrap();
+		let rect = canvas.get_bounding_client_rect();
+		let (x, y) = (
+			ev.client_x() as f64 - rect.left(),
+			ev.client_y() as f64 - rect.top(),
+		);
+
+		let (mut edited, mut background_clicked) = (None, false);
 		if let Some(ref mut c) = *context_mu.borrow_mut() {
-			if c.state.drag.active {
-				if let Some(idx) = c.state.drag.node_idx {
-					c.state.graph.visit_nodes_mut(|node| {
-						if node.index() == idx {
-							node.data.is_anchor = true;
-						}
-					});
+			if !c.recorder.capture(InputKind::MouseUp, x, y, &ev) {
+				return;
+			}
+			c.state.mark_layers_dirty(Layers::POINTER);
+			let mut changed = false;
+			if let Some(draft) = c.state.link_draft.take() {
+				if !c.state.gesture.moved {
+					c.state.selection.set([draft.source]);
+				} else if let Some(target) = c.state.node_at_position(x, y, &c.scale) {
+					changed = c.state.add_link(draft.source, target);
+				}
+			}
+			// In edit mode a plain click on empty space creates a node there
+			if c.state.editable
+				&& c.state.pan.active
+				&& !c.state.gesture.moved
+				&& ev.button() == 0
+				&& !c.state.pan.space_held
+			{
+				let (gx, gy) = c.state.screen_to_graph(x, y);
+				let count = c.state.graph.get_graph().node_count();
+				let color = c.theme.palette.get(count).to_css_rgb();
+				c.state.add_node_at(gx, gy, color);
+				changed = true;
+			}
+			if c.state.lasso.active {
+				// Alt+click (no drag) on a node releases it instead of lassoing
+				let (down_x, down_y) = (c.state.gesture.down_x, c.state.gesture.down_y);
+				let clicked = (!c.state.gesture.moved)
+					.then(|| c.state.node_at_position(down_x, down_y, &c.scale))
+					.flatten();
+				if let Some(idx) = clicked {
+					c.state.lasso.active = false;
+					c.state.lasso.points.clear();
+					c.state.set_pinned(&[idx], false);
+				} else {
+					c.state.complete_lasso();
+				}
+			}
+			if c.state.box_zoom.active {
+				c.state.box_zoom.active = false;
+				let rect = c.state.box_zoom.rect();
+				// Ignore accidental shift-clicks
+				if rect.2 > 4.0 && rect.3 > 4.0 {
+					let target = c.state.fit_screen_rect(rect);
+					c.state.animate_to(target);
 				}
 			}
+			if !c.state.gesture.moved && ev.button() == 0 {
+				if let (true, Some(idx)) = (c.state.drag.active, c.state.drag.node_idx) {
+					c.state.selection.set([idx]);
+				} else if c.state.pan.active && !c.state.pan.space_held {
+					c.state.selection.set([]);
+					background_clicked = true;
+				}
+			}
+			// A press-release without movement is a click, not a drag: don't pin
+			if c.state.drag.active
+				&& c.state.gesture.moved
+				&& let Some(idx) = c.state.drag.node_idx
+			{
+				c.state.graph.visit_nodes_mut(|node| {
+					if node.index() == idx {
+						node.data.is_anchor = true;
+					}
+				});
+				let before = std::mem::take(&mut c.state.drag.before);
+				c.state.record(before);
+			}
 			c.state.drag.active = false;
 			c.state.drag.node_idx = None;
 			c.state.pan.active = false;
+			if let Some(space) = &mut c.state.space {
+				space.release();
+			}
+			update_cursor(&c.state);
+			if changed {
+				sync_components(&c.state);
+			}
+			edited = changed.then(|| c.state.export_data());
+		}
+		if let (Some(data), Some(cb)) = (edited, on_edit) {
+			cb.run(data);
+		}
+		if let (true, Some(cb)) = (background_clicked, on_background_click) {
+			cb.run(());
+		}
+	};
+
+	let context_dc = context.clone();
+	let on_dblclick = move |ev: MouseEvent| {
+		let canvas: HtmlCanvasElement = canvas_ref.get().unwrap();
+		let rect = canvas.get_bounding_client_rect();
+		let (x, y) = (
+			ev.client_x() as f64 - rect.left(),
+			ev.client_y() as f64 - rect.top(),
+		);
+
+		let mut activated = None;
+		if let Some(ref mut c) = *context_dc.borrow_mut() {
+			if !c.recorder.capture(InputKind::DblClick, x, y, &ev) {
+				return;
+			}
+			// The second click of a double-click that ended a drag isn't an activation,
+			// and double-clicking an off-screen indicator just jumps twice
+			if c.state.gesture.moved || c.state.indicator_at(x, y).is_some() {
+				return;
+			}
+			let target = match c.state.node_at_position(x, y, &c.scale) {
+				Some(idx) => {
+					activated = c.state.node_id(idx);
+					c.state.focus_node_transform(idx)
+				}
+				None => c.state.fit_all_transform(),
+			};
+			if let Some(target) = target {
+				c.state.animate_to(target);
+			}
+		}
+		// Run the callback after releasing the borrow so it may call back into the graph
+		if let (Some(id), Some(cb)) = (activated, on_node_activate) {
+			cb.run(id);
 		}
 	};
 
 	let context_ml = context.clone();
-	let on_mouseleave = move |_: MouseEvent| {
+	let on_mouseleave = move |ev: MouseEvent| {
 		if let Some(ref mut c) = *context_ml.borrow_mut() {
+			if !c.recorder.capture(InputKind::MouseLeave, 0.0, 0.0, &ev) {
+				return;
+			}
+			c.state.mark_layers_dirty(Layers::POINTER);
 			c.state.drag.active = false;
 			c.state.drag.node_idx = None;
 			c.state.pan.active = false;
+			if let Some(space) = &mut c.state.space {
+				space.release();
+			}
+			c.state.box_zoom.active = false;
+			c.state.lasso.active = false;
+			c.state.lasso.points.clear();
+			c.state.link_draft = None;
 			c.state.set_hover(None);
+			c.state.fisheye.focus = None;
+			c.state.pointer = None;
+			update_cursor(&c.state);
 		}
+		tooltip.set(None);
 	};
 
 	let context_wh = context.clone();
 	let on_wheel = move |ev: WheelEvent| {
 		ev.prevent_default();
-		let canvas: HtmlCanvasElement = canvas_ref.get().unwrap().into();
+		let canvas: HtmlCanvasElement = canvas_ref.get().unwrap();
 		let rect = canvas.get_bounding_client_rect();
 		let (x, y) = (
 			ev.client_x() as f64 - rect.left(),
 			ev.client_y() as f64 - rect.top(),
 		);
 
+		menu.set(None);
 		if let Some(ref mut c) = *context_wh.borrow_mut() {
-			let factor = if ev.delta_y() > 0.0 { 0.9 } else { 1.1 };
-			let new_k = (c.state.transform.k * factor).clamp(0.1, 10.0);
-			let ratio = new_k / c.state.transform.k;
-			c.state.transform.x = x - (x - c.state.transform.x) * ratio;
-			c.state.transform.y = y - (y - c.state.transform.y) * ratio;
-			c.state.transform.k = new_k;
+			if !c.recorder.capture(InputKind::Wheel, x, y, &ev) {
+				return;
+			}
+			c.state.wake();
+			c.state.camera = None;
+			if ev.ctrl_key() {
+				// Trackpad pinch arrives as ctrl+wheel with small deltas: zoom smoothly
+				let factor = (-ev.delta_y() * 0.01).clamp(-0.5, 0.5).exp();
+				c.state.transform = c.state.zoom_about(x, y, factor);
+			} else if is_trackpad_scroll(&ev) {
+				c.state.follow = None;
+				c.state.transform.x -= ev.delta_x();
+				c.state.transform.y -= ev.delta_y();
+			} else {
+				let factor = if ev.delta_y() > 0.0 { 0.9 } else { 1.1 };
+				c.state.transform = c.state.zoom_about(x, y, factor);
+			}
+		}
+	};
+
+	let context_cm = context.clone();
+	let on_contextmenu = move |ev: MouseEvent| {
+		ev.prevent_default();
+		let canvas: HtmlCanvasElement = canvas_ref.get().unwrap();
+		let rect = canvas.get_bounding_client_rect();
+		let (x, y) = (
+			ev.client_x() as f64 - rect.left(),
+			ev.client_y() as f64 - rect.top(),
+		);
+
+		let (target, builtins) = {
+			let Some(ref c) = *context_cm.borrow() else {
+				return;
+			};
+			let target = if let Some(idx) = c.state.node_at_position(x, y, &c.scale) {
+				ContextTarget::Node(c.state.node_id(idx).unwrap_or_default())
+			} else if let Some((src, tgt)) = c.state.edge_at_position(x, y) {
+				ContextTarget::Edge {
+					source: c.state.node_id(src).unwrap_or_default(),
+					target: c.state.node_id(tgt).unwrap_or_default(),
+				}
+			} else {
+				let (gx, gy) = c.state.screen_to_graph(x, y);
+				ContextTarget::Background { x: gx, y: gy }
+			};
+			let builtins = builtin_actions(&target, &c.state);
+			(target, builtins)
+		};
+
+		let mut entries: Vec<MenuEntry> = builtins.into_iter().map(MenuEntry::Builtin).collect();
+		if let Some(cb) = context_menu {
+			entries.extend(cb.run(target.clone()).into_iter().map(MenuEntry::Custom));
+		}
+		menu.set(Some(OpenMenu {
+			x,
+			y,
+			target,
+			entries,
+		}));
+	};
+
+	// Reactive views must be `Send`; keep the context in local arena storage for them
+	let context_local = StoredValue::new_local(context.clone());
+	let menu_view = move || {
+		let open = menu.get()?;
+		let items = open
+			.entries
+			.into_iter()
+			.map(|entry| {
+				let label = entry.label();
+				let target = open.target.clone();
+				let on_click = move |_: MouseEvent| {
+					menu.set(None);
+					match &entry {
+						MenuEntry::Builtin(action) => context_local.with_value(|context| {
+							if let Some(ref mut c) = *context.borrow_mut() {
+								apply_builtin(action.clone(), &target, &mut c.state, &c.theme);
+								sync_crumbs(&c.state);
+								sync_components(&c.state);
+							}
+						}),
+						MenuEntry::Custom(item) => item.action.run(target.clone()),
+					}
+				};
+				view! {
+					<button class="force-graph-menu-item" on:click=on_click>
+						{label}
+					</button>
+				}
+			})
+			.collect_view();
+		Some(view! {
+			<div
+				class="force-graph-menu"
+				style=format!("position: absolute; left: {}px; top: {}px;", open.x, open.y)
+				on:contextmenu=|ev: MouseEvent| ev.prevent_default()
+			>
+				{items}
+			</div>
+		})
+	};
+
+	let help_view = move || {
+		if !help_open.get() {
+			return None;
+		}
+		let rows = help_entries
+			.iter()
+			.map(|(keys, description)| {
+				let keys = keys
+					.iter()
+					.map(|key| {
+						let key = if key.chars().count() == 1 {
+							key.to_uppercase()
+						} else {
+							key.clone()
+						};
+						view! { <kbd>{key}</kbd> }
+					})
+					.collect_view();
+				view! {
+					<tr>
+						<td class="force-graph-help-keys">{keys}</td>
+						<td>{*description}</td>
+					</tr>
+				}
+			})
+			.collect_view();
+		Some(view! {
+			<div class="force-graph-help" on:click=move |_| help_open.set(false)>
+				<div class="force-graph-help-title">"Keyboard shortcuts"</div>
+				<table>{rows}</table>
+			</div>
+		})
+	};
+
+	let breadcrumb_view = move || {
+		let labels = crumbs.get();
+		if labels.is_empty() {
+			return None;
+		}
+		let last = labels.len();
+		let items = std::iter::once("Full graph".to_string())
+			.chain(labels)
+			.enumerate()
+			.map(|(depth, label)| {
+				let separator = (depth > 0).then_some(view! {
+					<span class="force-graph-breadcrumb-separator">"\u{203a}"</span>
+				});
+				let crumb = if depth == last {
+					view! { <span class="force-graph-breadcrumb current">{label}</span> }.into_any()
+				} else {
+					let on_click = move |_: MouseEvent| {
+						context_local.with_value(|context| {
+							if let Some(ref mut c) = *context.borrow_mut() {
+								c.state.restore_breadcrumb(depth);
+								sync_crumbs(&c.state);
+								sync_components(&c.state);
+							}
+						})
+					};
+					view! {
+						<button class="force-graph-breadcrumb" on:click=on_click>
+							{label}
+						</button>
+					}
+					.into_any()
+				};
+				view! { {separator} {crumb} }
+			})
+			.collect_view();
+		Some(view! { <nav class="force-graph-breadcrumbs">{items}</nav> })
+	};
+
+	let zoom_button = move |label: &'static str, title: &'static str, action: KeyAction| {
+		let on_click = move |_: MouseEvent| {
+			context_local.with_value(|context| {
+				if let Some(ref mut c) = *context.borrow_mut() {
+					apply_key_action(&mut c.state, action);
+				}
+			})
+		};
+		view! {
+			<button class="force-graph-zoom-button" title=title on:click=on_click>
+				{label}
+			</button>
+		}
+	};
+	let zoom_view = (zoom_controls || reheat_button || pause_button).then(|| {
+		let zoom = zoom_controls.then(|| {
+			view! {
+				{zoom_button("+", "Zoom in", KeyAction::ZoomIn)}
+				{zoom_button("\u{2212}", "Zoom out", KeyAction::ZoomOut)}
+				{zoom_button("\u{2922}", "Zoom to fit", KeyAction::Fit)}
+			}
+		});
+		let reheat =
+			reheat_button.then(|| zoom_button("\u{21bb}", "Reheat the layout", KeyAction::Reheat));
+		let pause = pause_button.then(|| {
+			let on_click = move |_: MouseEvent| paused.update(|p| *p = !*p);
+			view! {
+				<button
+					class="force-graph-zoom-button"
+					title=move || if paused.get() { "Resume physics" } else { "Pause physics" }
+					on:click=on_click
+				>
+					{move || if paused.get() { "\u{25b6}" } else { "\u{23f8}" }}
+				</button>
+			}
+		});
+		view! { <div class="force-graph-zoom-controls">{zoom}{reheat}{pause}</div> }
+	});
+
+	let timeline_view = move || {
+		let count = timeline.with_value(|snapshots| snapshots.len());
+		if count == 0 {
+			return None;
+		}
+		let toggle = move |_: MouseEvent| {
+			if !playing.get_untracked() && frame.get_untracked() + 1 >= count {
+				frame.set(0);
+			}
+			playing.update(|p| *p = !*p);
+		};
+		let on_input = move |ev: web_sys::Event| {
+			if let Ok(index) = event_target_value(&ev).parse::<usize>() {
+				playing.set(false);
+				frame.set(index.min(count - 1));
+			}
+		};
+		let label = move || {
+			let index = frame.get();
+			timeline.with_value(|snapshots| {
+				snapshots
+					.get(index)
+					.map(|s| s.label.clone())
+					.unwrap_or_default()
+			})
+		};
+		Some(view! {
+			<div class="force-graph-timeline">
+				<button
+					class="force-graph-timeline-play"
+					title=move || if playing.get() { "Pause" } else { "Play" }
+					on:click=toggle
+				>
+					{move || if playing.get() { "\u{275a}\u{275a}" } else { "\u{25b6}" }}
+				</button>
+				<input
+					class="force-graph-timeline-slider"
+					type="range"
+					min="0"
+					max=count - 1
+					prop:value=move || frame.get().to_string()
+					on:input=on_input
+				/>
+				<span class="force-graph-timeline-label">{label}</span>
+			</div>
+		})
+	};
+
+	let tooltip_view = move || {
+		tooltip.get().map(|(x, y, text)| {
+			view! {
+				<div
+					class="force-graph-tooltip"
+					style=format!("position: absolute; left: {}px; top: {}px;", x + 12.0, y + 12.0)
+				>
+					{text}
+				</div>
+			}
+		})
+	};
+
+	let popover_view = move || {
+		let render = popover?;
+		popover_node.get().map(|node| {
+			view! {
+				<div
+					class="force-graph-popover"
+					style=anchors::ANCHOR_STYLE
+					attr:data-node=node.id.clone()
+					attr:data-popover=""
+				>
+					{render.run(node.clone())}
+				</div>
+			}
+		})
+	};
+
+	let legend_view = move || {
+		legend.get().map(|legend| {
+			view! {
+				<div class="force-graph-colorbar">
+					<div class="force-graph-colorbar-title">{legend.metric}</div>
+					<div
+						class="force-graph-colorbar-ramp"
+						style=format!("background: {};", legend.gradient)
+					></div>
+					<div class="force-graph-colorbar-scale">
+						<span>{format_metric(legend.min)}</span>
+						<span>{format_metric(legend.max)}</span>
+					</div>
+				</div>
+			}
+		})
+	};
+
+	let size_legend_view = move || {
+		sizes.get().map(|legend| {
+			let items = legend
+				.entries
+				.into_iter()
+				.map(|(value, radius)| {
+					let style = format!("width: {0}px; height: {0}px;", 2.0 * radius);
+					view! {
+						<div class="force-graph-size-legend-item">
+							<span class="force-graph-size-legend-circle" style=style></span>
+							<span>{format_metric(value)}</span>
+						</div>
+					}
+				})
+				.collect_view();
+			view! {
+				<div class="force-graph-size-legend">
+					<div class="force-graph-size-legend-title">{legend.title}</div>
+					<div class="force-graph-size-legend-items">{items}</div>
+				</div>
+			}
+		})
+	};
+
+	let debug_view = move || {
+		if !debug_open.get() {
+			return None;
+		}
+		let s = debug_stats.get().unwrap_or_default();
+		let rows = [
+			("Nodes", format!("{} ({} hidden)", s.nodes, s.hidden_nodes)),
+			("Edges", s.edges.to_string()),
+			("Selected", s.selected.to_string()),
+			("Highlight entries", s.highlight_entries.to_string()),
+			("Label widths", s.label_widths.to_string()),
+			(
+				"Label sprites",
+				format!("{} ({})", s.sprites, format_bytes(s.sprite_bytes)),
+			),
+			("CSS strings", s.css_strings.to_string()),
+			("Particles", s.particles.to_string()),
+			("WASM heap", format_bytes(s.heap_bytes)),
+			(
+				"Frames",
+				format!("{} drawn, {} skipped", s.frames_drawn, s.frames_skipped),
+			),
+		]
+		.into_iter()
+		.map(|(name, value)| {
+			view! {
+				<tr>
+					<td class="force-graph-debug-name">{name}</td>
+					<td>{value}</td>
+				</tr>
+			}
+		})
+		.collect_view();
+		Some(view! {
+			<div class="force-graph-debug">
+				<table>{rows}</table>
+			</div>
+		})
+	};
+
+	let status_view = move || {
+		let (class, title, detail) = if loading.get() {
+			("force-graph-status", "Loading graph\u{2026}", String::new())
+		} else if let Some(message) = error.get().or_else(|| rejected.get()) {
+			(
+				"force-graph-status force-graph-status-error",
+				"Couldn't load graph data",
+				message,
+			)
+		} else if empty.get() {
+			let hint = if editable.get() {
+				"Click anywhere to add a node."
+			} else {
+				"There is nothing to show yet."
+			};
+			("force-graph-status", "No graph data", hint.to_string())
+		} else {
+			return None;
+		};
+		let spinner = loading
+			.get()
+			.then(|| view! { <div class="force-graph-spinner"></div> });
+		// Only the host's own errors can be retried; `setData` failures are the page's to fix
+		let retry = on_retry.filter(|_| !loading.get() && error.get().is_some());
+		let retry = retry.map(|cb| {
+			view! {
+				<button class="force-graph-status-retry" on:click=move |_| cb.run(())>
+					"Retry"
+				</button>
+			}
+		});
+		Some(view! {
+			<div class=class>
+				{spinner}
+				<div class="force-graph-status-title">{title}</div>
+				<div class="force-graph-status-detail">{detail}</div>
+				{retry}
+			</div>
+		})
+	};
+
+	let settle_view = move || {
+		settling.get().map(|progress| {
+			view! {
+				<div class="force-graph-settle" title="Settling\u{2026}">
+					<div
+						class="force-graph-settle-bar"
+						style:width=format!("{:.0}%", progress * 100.0)
+					></div>
+				</div>
+			}
+		})
+	};
+
+	let components_view = move || {
+		let list = components.get();
+		if list.len() < 2 {
+			return None;
 		}
+		let more = list.len().saturating_sub(NAVIGATOR_ENTRIES);
+		let items = list
+			.into_iter()
+			.take(NAVIGATOR_ENTRIES)
+			.enumerate()
+			.map(|(i, component)| {
+				let on_click = move |_: MouseEvent| {
+					context_local.with_value(|context| {
+						if let Some(ref mut c) = *context.borrow_mut() {
+							let nodes = c.state.connected_components().into_iter().nth(i);
+							if let Some(target) =
+								nodes.and_then(|nodes| c.state.fit_nodes_transform(&nodes))
+							{
+								c.state.animate_to(target);
+							}
+						}
+					})
+				};
+				view! {
+					<button class="force-graph-component" on:click=on_click>
+						<span class="force-graph-component-label">{component.label}</span>
+						<span class="force-graph-component-size">{component.size}</span>
+					</button>
+				}
+			})
+			.collect_view();
+		let more = (more > 0).then(|| {
+			view! { <div class="force-graph-components-more">{format!("+{more} smaller")}</div> }
+		});
+		Some(view! {
+			<div class="force-graph-components">
+				<div class="force-graph-components-title">"Components"</div>
+				{items}
+				{more}
+			</div>
+		})
 	};
 
+	let (pointer_enter, pointer_leave) = (pointer_inside.clone(), pointer_inside);
 	view! {
-		<canvas
-			node_ref=canvas_ref
-			class="force-graph-canvas"
-			on:mousedown=on_mousedown
-			on:mousemove=on_mousemove
-			on:mouseup=on_mouseup
-			on:mouseleave=on_mouseleave
-			on:wheel=on_wheel
-			style="display: block; cursor: grab;"
-		/>
+		<div
+			class="force-graph"
+			style="position: relative; width: 100%; height: 100%;"
+			on:mouseenter=move |_| pointer_enter.set(true)
+			on:mouseleave=move |_| pointer_leave.set(false)
+		>
+			{layered
+				.then(|| {
+					layer_refs
+						.map(|layer| view! { <canvas node_ref=layer style=LAYER_STYLE /> })
+				})}
+			<canvas
+				node_ref=canvas_ref
+				class="force-graph-canvas"
+				on:mousedown=on_mousedown
+				on:mousemove=on_mousemove
+				on:mouseup=on_mouseup
+				on:mouseleave=on_mouseleave
+				on:dblclick=on_dblclick
+				on:wheel=on_wheel
+				on:contextmenu=on_contextmenu
+				style="display: block; position: relative;"
+				style:cursor=move || cursor.get()
+			/>
+			<div node_ref=anchors_ref class="force-graph-anchors" style=anchors::CONTAINER_STYLE>
+				<For each=move || anchored.get() key=|(id, _)| id.clone() let((id, content))>
+					<div
+						class="force-graph-anchor"
+						style=anchors::ANCHOR_STYLE
+						attr:data-node=id
+					>
+						{content.run()}
+					</div>
+				</For>
+				{popover_view}
+			</div>
+			{tooltip_view}
+			{breadcrumb_view}
+			{menu_view}
+			{help_view}
+			{zoom_view}
+			{timeline_view}
+			{legend_view}
+			{size_legend_view}
+			{debug_view}
+			{components_view}
+			{status_view}
+			{settle_view}
+		</div>
 	}
 }
@@ -0,0 +1,279 @@
+//! Right-click context menu: targets, built-in actions, and host-provided items.
+//!
+//! Right-clicking the canvas resolves what is under the cursor into a
+//! [`ContextTarget`]. The menu then lists the built-in actions for that target
+//! followed by any items the host returns from its `context_menu` callback.
+
+use leptos::prelude::*;
+
+use super::state::ForceGraphState;
+use super::theme::Theme;
+use super::view_model;
+
+/// What a context menu was opened on.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContextTarget {
+	/// A node, by ID.
+	Node(String),
+	/// An edge, by its endpoint IDs.
+	Edge {
+		/// Source node ID.
+		source: String,
+		/// Target node ID.
+		target: String,
+	},
+	/// Empty canvas, with the clicked position in graph (world) coordinates.
+	Background {
+		/// Horizontal world coordinate.
+		x: f64,
+		/// Vertical world coordinate.
+		y: f64,
+	},
+}
+
+/// A host-provided context menu entry.
+#[derive(Clone)]
+pub struct ContextMenuItem {
+	/// Text shown in the menu.
+	pub label: String,
+	/// Invoked with the menu's target when the item is chosen.
+	pub action: Callback<ContextTarget>,
+}
+
+impl ContextMenuItem {
+	/// Create a menu item running `action` when chosen.
+	pub fn new(
+		label: impl Into<String>,
+		action: impl Fn(ContextTarget) + Send + Sync + 'static,
+	) -> Self {
+		Self {
+			label: label.into(),
+			action: Callback::new(action),
+		}
+	}
+}
+
+/// Actions the graph implements itself.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuiltinAction {
+	Pin,
+	Unpin,
+	UnpinAll,
+	Hide,
+	Isolate,
+	Focus,
+	ClearFocus,
+	CopyId,
+	CopyJson,
+	ShowAll,
+	/// Reveal the nodes of a group that the data marks as hidden.
+	ShowGroup(String),
+	/// Collapse the node's group into a single meta-node.
+	CollapseGroup,
+	/// Expand a collapsed group's meta-node back into its members.
+	ExpandGroup,
+	/// Keep the camera centered on the node as it moves.
+	Follow,
+	StopFollowing,
+	ZoomToFit,
+}
+
+impl BuiltinAction {
+	fn label(&self) -> String {
+		let label = match self {
+			BuiltinAction::Pin => "Pin",
+			BuiltinAction::Unpin => "Unpin",
+			BuiltinAction::UnpinAll => "Unpin all",
+			BuiltinAction::Hide => "Hide",
+			BuiltinAction::Isolate => "Isolate",
+			BuiltinAction::Focus => "Focus subtree",
+			BuiltinAction::ClearFocus => "Clear focus",
+			BuiltinAction::CopyId => "Copy ID",
+			BuiltinAction::CopyJson => "Copy graph as JSON",
+			BuiltinAction::ShowAll => "Show all",
+			BuiltinAction::ShowGroup(group) => return format!("Show {group}"),
+			BuiltinAction::CollapseGroup => "Collapse group",
+			BuiltinAction::ExpandGroup => "Expand group",
+			BuiltinAction::Follow => "Follow",
+			BuiltinAction::StopFollowing => "Stop following",
+			BuiltinAction::ZoomToFit => "Zoom to fit",
+		};
+		label.to_string()
+	}
+}
+
+/// One row in an open menu.
+#[derive(Clone)]
+pub enum MenuEntry {
+	Builtin(BuiltinAction),
+	Custom(ContextMenuItem),
+}
+
+impl MenuEntry {
+	pub fn label(&self) -> String {
+		match self {
+			MenuEntry::Builtin(action) => action.label(),
+			MenuEntry::Custom(item) => item.label.clone(),
+		}
+	}
+}
+
+/// A menu currently shown at a screen position (relative to the canvas).
+#[derive(Clone)]
+pub struct OpenMenu {
+	pub x: f64,
+	pub y: f64,
+	pub target: ContextTarget,
+	pub entries: Vec<MenuEntry>,
+}
+
+/// Built-in actions offered for a target, given the current graph state.
+pub fn builtin_actions(target: &ContextTarget, state: &ForceGraphState) -> Vec<BuiltinAction> {
+	match target {
+		ContextTarget::Node(id) => {
+			let idx = state.node_index(id);
+			let pinned = idx
+				.and_then(|idx| state.node(idx))
+				.is_some_and(|node| node.data.is_anchor);
+			let focused_root = state.focus.as_ref().is_some_and(|f| Some(f.root) == idx);
+			let mut actions = vec![
+				if pinned {
+					BuiltinAction::Unpin
+				} else {
+					BuiltinAction::Pin
+				},
+				BuiltinAction::Hide,
+				BuiltinAction::Isolate,
+			];
+			if !focused_root {
+				actions.push(BuiltinAction::Focus);
+			}
+			actions.push(if idx.is_some() && state.follow == idx {
+				BuiltinAction::StopFollowing
+			} else {
+				BuiltinAction::Follow
+			});
+			if state.focus.is_some() {
+				actions.push(BuiltinAction::ClearFocus);
+			}
+			if view_model::meta_group(id).is_some() {
+				actions.push(BuiltinAction::ExpandGroup);
+			} else if idx
+				.and_then(|idx| state.node(idx))
+				.is_some_and(|node| node.data.user_data.spec.group.is_some())
+			{
+				actions.push(BuiltinAction::CollapseGroup);
+			}
+			actions.push(BuiltinAction::CopyId);
+			actions
+		}
+		ContextTarget::Edge { .. } => vec![BuiltinAction::CopyId],
+		ContextTarget::Background { .. } => {
+			let mut actions = vec![BuiltinAction::ZoomToFit];
+			if state.focus.is_some() {
+				actions.push(BuiltinAction::ClearFocus);
+			}
+			if state.follow.is_some() {
+				actions.push(BuiltinAction::StopFollowing);
+			}
+			if state.has_hidden() {
+				actions.push(BuiltinAction::ShowAll);
+			}
+			actions.extend(
+				state
+					.hidden_groups()
+					.into_iter()
+					.map(BuiltinAction::ShowGroup),
+			);
+			if !state.pinned_nodes().is_empty() {
+				actions.push(BuiltinAction::UnpinAll);
+			}
+			actions.push(BuiltinAction::CopyJson);
+			actions
+		}
+	}
+}
+
+/// Apply a built-in action to the graph.
+pub fn apply_builtin(
+	action: BuiltinAction,
+	target: &ContextTarget,
+	state: &mut ForceGraphState,
+	theme: &Theme,
+) {
+	let node = match target {
+		ContextTarget::Node(id) => state.node_index(id),
+		_ => None,
+	};
+	match (action, node) {
+		(BuiltinAction::Pin, Some(idx)) => {
+			let nodes = state.action_targets(idx);
+			state.set_pinned(&nodes, true);
+		}
+		(BuiltinAction::Unpin, Some(idx)) => {
+			let nodes = state.action_targets(idx);
+			state.set_pinned(&nodes, false);
+		}
+		(BuiltinAction::UnpinAll, _) => state.unpin_all(),
+		(BuiltinAction::Hide, Some(idx)) => {
+			let nodes = state.action_targets(idx);
+			state.set_hidden(&nodes, true);
+		}
+		(BuiltinAction::Isolate, Some(idx)) => {
+			state.push_breadcrumb(idx);
+			state.isolate(idx);
+		}
+		(BuiltinAction::Focus, Some(idx)) => {
+			state.push_breadcrumb(idx);
+			state.focus_subtree(idx);
+		}
+		(BuiltinAction::ClearFocus, _) => state.clear_focus(),
+		(BuiltinAction::CopyId, _) => {
+			let text = match target {
+				ContextTarget::Node(id) => id.clone(),
+				ContextTarget::Edge { source, target } => format!("{source} -> {target}"),
+				ContextTarget::Background { .. } => return,
+			};
+			copy_to_clipboard(&text);
+		}
+		(BuiltinAction::CopyJson, _) => {
+			if let Ok(json) = serde_json::to_string_pretty(&state.export_data()) {
+				copy_to_clipboard(&json);
+			}
+		}
+		(BuiltinAction::ShowAll, _) => state.show_all(),
+		(BuiltinAction::ShowGroup(group), _) => state.set_group_hidden(&group, false),
+		(BuiltinAction::CollapseGroup, Some(idx)) => {
+			let group = state
+				.node(idx)
+				.and_then(|node| node.data.user_data.spec.group.clone());
+			if let Some(group) = group {
+				state.set_collapsed(&group, true, theme);
+			}
+		}
+		(BuiltinAction::ExpandGroup, _) => {
+			if let ContextTarget::Node(id) = target
+				&& let Some(group) = view_model::meta_group(id)
+			{
+				state.set_collapsed(group, false, theme);
+			}
+		}
+		(BuiltinAction::Follow, Some(idx)) => {
+			state.camera = None;
+			state.follow = Some(idx);
+		}
+		(BuiltinAction::StopFollowing, _) => state.follow = None,
+		(BuiltinAction::ZoomToFit, _) => {
+			if let Some(target) = state.fit_all_transform() {
+				state.animate_to(target);
+			}
+		}
+		_ => {}
+	}
+}
+
+fn copy_to_clipboard(text: &str) {
+	if let Some(window) = web_sys::window() {
+		let _ = window.navigator().clipboard().write_text(text);
+	}
+}
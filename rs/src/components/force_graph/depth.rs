@@ -0,0 +1,183 @@
+//! Experimental 3D mode.
+//!
+//! The `force_graph` simulation is planar, so in 3D the wrapper runs its own
+//! step with the same forces and parameters over (x, y, z). Nodes are then
+//! rotated by an orbit camera and projected with a simple perspective onto the
+//! plane the renderer draws, shrinking with distance; drawing back to front
+//! keeps near nodes on top. Dense graphs that tangle in a plane often pull apart
+//! once they have the extra dimension.
+
+use std::collections::HashMap;
+
+use force_graph::{DefaultNodeIdx, ForceGraph};
+
+use super::state::{EdgeInfo, NodeInfo};
+
+/// Distance from the camera to the orbit center, in graph units. Smaller
+/// values exaggerate the perspective.
+const CAMERA_DISTANCE: f64 = 900.0;
+
+/// Closest a node may come to the camera, as a fraction of
+/// [`CAMERA_DISTANCE`], so nodes passing the camera don't blow up.
+const NEAR_PLANE: f64 = 0.2;
+
+/// Orbit rotation per screen pixel dragged, in radians.
+const ORBIT_RATE: f64 = 0.008;
+
+/// Steepest the camera may look up or down, in radians, short of the poles
+/// where yaw would flip.
+const MAX_PITCH: f64 = 1.5;
+
+/// Depth spread given to a planar layout entering 3D, in graph units.
+const INITIAL_DEPTH: f32 = 120.0;
+
+/// 3D simulation and camera state, present while 3D mode is on.
+#[derive(Clone, Debug, Default)]
+pub struct Space {
+	/// Rotation about the vertical axis, in radians.
+	pub yaw: f64,
+	/// Rotation about the horizontal axis, in radians.
+	pub pitch: f64,
+	/// Pointer position and rotation an orbit drag started from
+	grab: Option<(f64, f64, f64, f64)>,
+	/// Per-node velocity, kept here because the planar simulation's is private
+	velocity: HashMap<DefaultNodeIdx, [f32; 3]>,
+	/// Visible nodes back to front, refreshed every frame
+	pub order: Vec<DefaultNodeIdx>,
+}
+
+impl Space {
+	/// Lift a planar layout into 3D, spreading nodes in depth so the
+	/// simulation has something to separate.
+	pub fn enter(graph: &mut ForceGraph<NodeInfo, EdgeInfo>) -> Self {
+		graph.visit_nodes_mut(|node| {
+			// Spread by index, so the same graph lifts the same way every time
+			let i = node.index().index() as f32;
+			node.data.user_data.z = INITIAL_DEPTH * ((i * 0.618_034).fract() * 2.0 - 1.0);
+		});
+		Self::default()
+	}
+
+	/// Flatten the layout back onto the plane.
+	pub fn leave(graph: &mut ForceGraph<NodeInfo, EdgeInfo>) {
+		graph.visit_nodes_mut(|node| node.data.user_data.z = 0.0);
+	}
+
+	/// Advance the simulation `dt` seconds in three dimensions, with the same
+	/// forces as the planar simulation.
+	pub fn step(&mut self, graph: &mut ForceGraph<NodeInfo, EdgeInfo>, dt: f32) {
+		let p = &graph.parameters;
+		let mut nodes = Vec::new();
+		let mut slot = HashMap::new();
+		graph.visit_nodes(|node| {
+			slot.insert(node.index(), nodes.len());
+			let d = &node.data;
+			nodes.push(([d.x, d.y, d.user_data.z], d.mass, d.is_anchor));
+		});
+		let mut accel = vec![[0.0f32; 3]; nodes.len()];
+		let clamp = |f: f32| f.clamp(-p.force_max, p.force_max) * dt;
+		let apply = |a: &mut [f32; 3], dir: [f32; 3], strength: f32| {
+			for axis in 0..3 {
+				a[axis] += clamp(dir[axis] * strength);
+			}
+		};
+
+		// Springs pull both ends of every link toward each other
+		graph.visit_edges(|n1, n2, _| {
+			let (i, j) = (slot[&n1.index()], slot[&n2.index()]);
+			let (dir, distance) = direction(nodes[i].0, nodes[j].0);
+			let strength = p.force_spring * distance * 0.5;
+			apply(&mut accel[i], dir, strength);
+			apply(&mut accel[j], dir, -strength);
+		});
+		// Every pair repels, in proportion to both masses
+		for i in 0..nodes.len() {
+			for j in i + 1..nodes.len() {
+				let (dir, distance) = direction(nodes[i].0, nodes[j].0);
+				let strength = -p.force_charge * nodes[i].1 * nodes[j].1 / (distance * distance);
+				if !nodes[i].2 {
+					apply(&mut accel[i], dir, strength);
+				}
+				if !nodes[j].2 {
+					apply(&mut accel[j], dir, -strength);
+				}
+			}
+		}
+
+		let (speed, damping) = (p.node_speed, p.damping_factor);
+		let velocity = &mut self.velocity;
+		graph.visit_nodes_mut(|node| {
+			let i = slot[&node.index()];
+			if node.data.is_anchor {
+				return;
+			}
+			let v = velocity.entry(node.index()).or_default();
+			for axis in 0..3 {
+				v[axis] = (v[axis] + accel[i][axis] * dt * speed) * damping;
+			}
+			node.data.x += v[0] * dt;
+			node.data.y += v[1] * dt;
+			node.data.user_data.z += v[2] * dt;
+		});
+	}
+
+	/// Forget node velocities, e.g. once node indices have been reassigned.
+	pub fn reset_motion(&mut self) {
+		self.velocity.clear();
+	}
+
+	/// Start an orbit drag at screen point (`x`, `y`).
+	pub fn grab(&mut self, x: f64, y: f64) {
+		self.grab = Some((x, y, self.yaw, self.pitch));
+	}
+
+	/// Whether an orbit drag is in progress.
+	pub fn is_grabbed(&self) -> bool {
+		self.grab.is_some()
+	}
+
+	/// Follow an orbit drag to screen point (`x`, `y`).
+	pub fn drag_to(&mut self, x: f64, y: f64) {
+		if let Some((x0, y0, yaw, pitch)) = self.grab {
+			self.yaw = yaw + (x - x0) * ORBIT_RATE;
+			self.pitch = (pitch + (y - y0) * ORBIT_RATE).clamp(-MAX_PITCH, MAX_PITCH);
+		}
+	}
+
+	/// End an orbit drag.
+	pub fn release(&mut self) {
+		self.grab = None;
+	}
+
+	/// Project a node at (`x`, `y`, `z`) around orbit center `center`, returning
+	/// its position on the drawing plane, size factor and depth (larger is
+	/// farther).
+	pub fn project(
+		&self,
+		(x, y, z): (f64, f64, f64),
+		center: (f64, f64, f64),
+	) -> (f64, f64, f64, f64) {
+		let (x, y, z) = (x - center.0, y - center.1, z - center.2);
+		let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+		let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+		let (x1, z1) = (x * cos_yaw + z * sin_yaw, z * cos_yaw - x * sin_yaw);
+		let (y2, z2) = (
+			y * cos_pitch - z1 * sin_pitch,
+			y * sin_pitch + z1 * cos_pitch,
+		);
+		let scale = CAMERA_DISTANCE / (CAMERA_DISTANCE + z2).max(CAMERA_DISTANCE * NEAR_PLANE);
+		(center.0 + x1 * scale, center.1 + y2 * scale, scale, z2)
+	}
+}
+
+/// Unit vector from `a` to `b` and the distance between them. Coincident
+/// points count as 1 apart, as in the planar simulation.
+fn direction(a: [f32; 3], b: [f32; 3]) -> ([f32; 3], f32) {
+	let d = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+	let distance = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+	let distance = if distance == 0.0 { 1.0 } else { distance };
+	(
+		[d[0] / distance, d[1] / distance, d[2] / distance],
+		distance,
+	)
+}
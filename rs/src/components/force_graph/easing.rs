@@ -0,0 +1,41 @@
+//! Easing curves shared by the graph's animations.
+//!
+//! Every animated transition (hover highlights, camera moves, layout switches,
+//! theme and color fades) maps its linear progress through an [`Easing`] so the
+//! feel of each can be tuned on its own.
+
+use std::f64::consts::PI;
+
+/// Maps linear progress `t` in `0.0..=1.0` to eased progress.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Easing {
+	/// Constant speed.
+	Linear,
+	/// Slow start and end (smoothstep).
+	#[default]
+	EaseInOut,
+	/// Fast start, gentle cubic deceleration.
+	Cubic,
+	/// Damped spring that overshoots slightly before settling.
+	Spring,
+}
+
+impl Easing {
+	/// Eased progress for `t`, clamped to `0.0..=1.0`. Always 0 at the start and 1
+	/// at the end, though [`Easing::Spring`] may briefly exceed 1 in between.
+	pub fn apply(self, t: f64) -> f64 {
+		let t = t.clamp(0.0, 1.0);
+		match self {
+			Easing::Linear => t,
+			Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+			Easing::Cubic => 1.0 - (1.0 - t).powi(3),
+			Easing::Spring => {
+				if t >= 1.0 {
+					1.0
+				} else {
+					1.0 - (-6.0 * t).exp() * (3.0 * PI * t).cos()
+				}
+			}
+		}
+	}
+}
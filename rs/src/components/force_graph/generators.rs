@@ -0,0 +1,127 @@
+//! Synthetic graphs for demos, stress tests and benchmarks.
+//!
+//! Every generator is deterministic: the same arguments and `seed` give the
+//! same [`GraphData`]. Nodes are named `n0`, `n1`, … in creation order.
+
+use std::collections::HashSet;
+
+use super::types::{GraphData, GraphLink, GraphNode};
+
+/// Random G(n, p) graph: `n` nodes, with each ordered pair of distinct nodes
+/// linked with probability `p`.
+pub fn random(n: usize, p: f64, seed: u64) -> GraphData {
+	let mut rng = Rng(seed);
+	let mut links = Vec::new();
+	for a in 0..n {
+		for b in 0..n {
+			if a != b && rng.next() < p {
+				links.push((a, b));
+			}
+		}
+	}
+	graph(n, links)
+}
+
+/// Scale-free Barabási–Albert graph: each node after the first links to `m`
+/// distinct earlier nodes, picked with probability proportional to their
+/// number of links, so a few hubs collect most of them.
+pub fn scale_free(n: usize, m: usize, seed: u64) -> GraphData {
+	let mut rng = Rng(seed);
+	let mut links = Vec::new();
+	// Each node appears once per link end, so a uniform pick is degree-weighted
+	let mut ends: Vec<usize> = Vec::new();
+	for node in 1..n {
+		let wanted = m.min(node);
+		let mut targets = HashSet::new();
+		while targets.len() < wanted {
+			let target = if ends.is_empty() || rng.next() < 0.1 {
+				// Occasionally pick uniformly so isolated early nodes still get links
+				rng.below(node)
+			} else {
+				ends[rng.below(ends.len())]
+			};
+			targets.insert(target);
+		}
+		let mut targets: Vec<usize> = targets.into_iter().collect();
+		targets.sort_unstable();
+		for target in targets {
+			links.push((node, target));
+			ends.extend([node, target]);
+		}
+	}
+	graph(n, links)
+}
+
+/// Complete tree of `n` nodes where each node has up to `branching` children,
+/// linked from parent to child. Nodes are grouped by depth.
+pub fn tree(n: usize, branching: usize) -> GraphData {
+	let branching = branching.max(1);
+	let links = (1..n)
+		.map(|child| ((child - 1) / branching, child))
+		.collect();
+	let mut data = graph(n, links);
+	let mut depth = vec![0; n];
+	for child in 1..n {
+		depth[child] = depth[(child - 1) / branching] + 1;
+	}
+	for (node, depth) in data.nodes.iter_mut().zip(depth) {
+		node.group = Some(format!("depth {depth}"));
+	}
+	data
+}
+
+/// Random directed acyclic graph: `n` nodes in a fixed order, with each node
+/// linked to each later node with probability `p`.
+pub fn dag(n: usize, p: f64, seed: u64) -> GraphData {
+	let mut rng = Rng(seed);
+	let mut links = Vec::new();
+	for a in 0..n {
+		for b in a + 1..n {
+			if rng.next() < p {
+				links.push((a, b));
+			}
+		}
+	}
+	graph(n, links)
+}
+
+/// Graph data for nodes `0..n` and the (source, target) links between them.
+fn graph(n: usize, links: Vec<(usize, usize)>) -> GraphData {
+	let id = |i: usize| format!("n{i}");
+	GraphData {
+		nodes: (0..n)
+			.map(|i| GraphNode {
+				id: id(i),
+				..GraphNode::default()
+			})
+			.collect(),
+		links: links
+			.into_iter()
+			.map(|(source, target)| GraphLink {
+				source: id(source),
+				target: id(target),
+				..GraphLink::default()
+			})
+			.collect(),
+		..GraphData::default()
+	}
+}
+
+/// splitmix64 pseudo-random sequence.
+struct Rng(u64);
+
+impl Rng {
+	/// Next number in 0..1.
+	fn next(&mut self) -> f64 {
+		self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		(z ^ (z >> 31)) as f64 / u64::MAX as f64
+	}
+
+	/// Next index in 0..n.
+	fn below(&mut self, n: usize) -> usize {
+		((self.next() * n as f64) as usize).min(n - 1)
+	}
+}
@@ -0,0 +1,69 @@
+//! Undo/redo history for direct node manipulation (drags and pinning).
+//!
+//! Each entry stores the affected nodes' position and pin state before and
+//! after the operation, so undo and redo are plain restores rather than
+//! inverse operations.
+
+use force_graph::DefaultNodeIdx;
+
+/// Maximum number of undoable operations kept.
+const MAX_ENTRIES: usize = 100;
+
+/// Position and pin state of one node at a point in time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeSnapshot {
+	pub idx: DefaultNodeIdx,
+	pub x: f32,
+	pub y: f32,
+	pub pinned: bool,
+}
+
+/// One undoable operation.
+#[derive(Clone, Debug)]
+struct HistoryEntry {
+	before: Vec<NodeSnapshot>,
+	after: Vec<NodeSnapshot>,
+}
+
+/// Undo and redo stacks of node operations.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+	undo: Vec<HistoryEntry>,
+	redo: Vec<HistoryEntry>,
+}
+
+impl History {
+	/// Record an operation. Starting a new branch discards anything redoable.
+	pub fn push(&mut self, before: Vec<NodeSnapshot>, after: Vec<NodeSnapshot>) {
+		if before == after {
+			return;
+		}
+		self.redo.clear();
+		self.undo.push(HistoryEntry { before, after });
+		if self.undo.len() > MAX_ENTRIES {
+			self.undo.remove(0);
+		}
+	}
+
+	/// Forget everything, e.g. after nodes are removed and their indices may be reused.
+	pub fn clear(&mut self) {
+		self.undo.clear();
+		self.redo.clear();
+	}
+
+	/// Step back, returning the snapshots to restore.
+	pub fn undo(&mut self) -> Option<Vec<NodeSnapshot>> {
+		let entry = self.undo.pop()?;
+		let before = entry.before.clone();
+		self.redo.push(entry);
+		Some(before)
+	}
+
+	/// Step forward again, returning the snapshots to restore.
+	pub fn redo(&mut self) -> Option<Vec<NodeSnapshot>> {
+		let entry = self.redo.pop()?;
+		let after = entry.after.clone();
+		self.undo.push(entry);
+		Some(after)
+	}
+}
@@ -0,0 +1,122 @@
+//! Host hooks into rendering.
+//!
+//! Hooks let an application draw its own visuals with the canvas API without
+//! forking the renderer. They run with the world-space transform applied, so
+//! positions are in graph units; divide by [`zoom`](NodeRenderInfo::zoom) for
+//! sizes that should stay constant on screen.
+//!
+//! Node and edge hooks replace single elements; a [`RenderPlugin`] draws
+//! whole layers under the edges or over the nodes, e.g. annotations, region
+//! overlays or watermarks.
+
+use std::rc::Rc;
+
+use web_sys::CanvasRenderingContext2d;
+
+use super::types::GraphNode;
+
+/// What a node draw hook knows about the node being drawn.
+pub struct NodeRenderInfo<'a> {
+	/// The input node.
+	pub node: &'a GraphNode,
+	/// Center x, in graph units.
+	pub x: f64,
+	/// Center y, in graph units.
+	pub y: f64,
+	/// Radius the standard disc is drawn with, in graph units, including
+	/// highlight growth and pulses.
+	pub radius: f64,
+	/// Fill color as CSS, mid-transition while recoloring.
+	pub color: &'a str,
+	/// Opacity applied to the node (already set as the global alpha).
+	pub alpha: f64,
+	/// Hover highlight intensity, from 0.0 to 1.0.
+	pub highlight: f64,
+	/// Whether the node is selected.
+	pub selected: bool,
+	/// Current zoom factor.
+	pub zoom: f64,
+	/// Draws the standard node (disc, border, ring and badge). Call it to
+	/// augment the node, or leave it out to replace it. The label is drawn
+	/// either way.
+	pub draw_default: &'a dyn Fn(),
+}
+
+/// Draws nodes in place of, or on top of, the standard node. The canvas state
+/// is restored afterwards, so the hook may change styles freely.
+pub type NodeDrawHook = Rc<dyn Fn(&CanvasRenderingContext2d, &NodeRenderInfo)>;
+
+/// What an edge draw hook knows about the edge being drawn.
+pub struct EdgeRenderInfo<'a> {
+	/// The source node.
+	pub source: &'a GraphNode,
+	/// The target node.
+	pub target: &'a GraphNode,
+	/// Center of the source node, in graph units.
+	pub from: (f64, f64),
+	/// Center of the target node, in graph units.
+	pub to: (f64, f64),
+	/// Control point of the quadratic curve the edge follows, or `None` when
+	/// it is straight.
+	pub control: Option<(f64, f64)>,
+	/// Edge category from the input data.
+	pub kind: Option<&'a str>,
+	/// Edge weight (1.0 = normal).
+	pub weight: f64,
+	/// Number of identical links folded into this edge.
+	pub count: u32,
+	/// Opacity applied to the edge (already set as the global alpha).
+	pub alpha: f64,
+	/// Hover highlight intensity, from 0.0 to 1.0.
+	pub highlight: f64,
+	/// Current zoom factor.
+	pub zoom: f64,
+	/// Animation clock in seconds, for moving visuals (see the component's
+	/// `animate_hooks`).
+	pub time: f64,
+	/// Draws the standard edge line and arrowhead. Call it to augment the
+	/// edge, or leave it out to replace it.
+	pub draw_default: &'a dyn Fn(),
+}
+
+/// Draws edges in place of, or on top of, the standard edge. The canvas state
+/// is restored afterwards, so the hook may change styles freely.
+pub type EdgeDrawHook = Rc<dyn Fn(&CanvasRenderingContext2d, &EdgeRenderInfo)>;
+
+/// What a render plugin knows about the frame being drawn.
+pub struct RenderFrame<'a> {
+	/// Current zoom factor.
+	pub zoom: f64,
+	/// Animation clock in seconds (see the component's `animate_hooks`).
+	pub time: f64,
+	/// Visible area as (min x, min y, max x, max y), in graph units.
+	pub view: (f64, f64, f64, f64),
+	/// Position the node with the given ID is drawn at, or `None` if there is
+	/// no such visible node.
+	pub position: &'a dyn Fn(&str) -> Option<(f64, f64)>,
+}
+
+/// Draws layers of its own around the graph. Both methods run with the
+/// world-space transform applied and the canvas state restored afterwards;
+/// implement whichever is needed.
+pub trait RenderPlugin {
+	/// Draw under everything in the graph, before the edges.
+	fn before_edges(&self, _ctx: &CanvasRenderingContext2d, _frame: &RenderFrame) {}
+
+	/// Draw over everything in the graph, after the nodes.
+	fn after_nodes(&self, _ctx: &CanvasRenderingContext2d, _frame: &RenderFrame) {}
+}
+
+/// Every hook the host registered, handed to the renderer.
+#[derive(Clone, Default)]
+pub struct RenderHooks {
+	/// Per-node drawing
+	pub node: Option<NodeDrawHook>,
+	/// Per-edge drawing
+	pub edge: Option<EdgeDrawHook>,
+	/// Layer plugins, drawn in registration order
+	pub plugins: Vec<Rc<dyn RenderPlugin>>,
+	/// Hooks draw moving visuals, so frames are drawn even when nothing else
+	/// changes
+	pub animated: bool,
+}
@@ -0,0 +1,141 @@
+//! Keyboard shortcuts: the actions the graph understands and which keys trigger them.
+//!
+//! Keys are matched against `KeyboardEvent.key`, so bindings name the character
+//! produced ("f", "+", "?") or the named key ("Escape"). Single-character keys
+//! match case-insensitively. Shortcuts fire only while the pointer is over the
+//! graph and never while typing in a text field.
+
+/// Something a keyboard shortcut can do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyAction {
+	/// Zoom to fit all visible nodes.
+	Fit,
+	/// Zoom in one step.
+	ZoomIn,
+	/// Zoom out one step.
+	ZoomOut,
+	/// Return to 100% zoom.
+	ResetZoom,
+	/// Clear the selection, focus and hover highlight.
+	ClearSelection,
+	/// Release every pinned node back to the simulation.
+	UnpinAll,
+	/// Delete the selected nodes (edit mode only).
+	DeleteSelection,
+	/// Pause or resume the physics simulation.
+	TogglePhysics,
+	/// Re-energize the layout so it can find a better arrangement.
+	Reheat,
+	/// Show or hide node labels.
+	ToggleLabels,
+	/// Show or hide the shortcut help overlay.
+	ToggleHelp,
+	/// Show or hide the debug panel.
+	ToggleDebug,
+}
+
+impl KeyAction {
+	/// Human-readable description shown in the help overlay.
+	pub fn description(self) -> &'static str {
+		match self {
+			KeyAction::Fit => "Zoom to fit",
+			KeyAction::ZoomIn => "Zoom in",
+			KeyAction::ZoomOut => "Zoom out",
+			KeyAction::ResetZoom => "Reset zoom",
+			KeyAction::ClearSelection => "Clear selection and focus",
+			KeyAction::UnpinAll => "Unpin all nodes",
+			KeyAction::DeleteSelection => "Delete selection (edit mode)",
+			KeyAction::TogglePhysics => "Pause / resume physics",
+			KeyAction::Reheat => "Reheat the layout",
+			KeyAction::ToggleLabels => "Show / hide labels",
+			KeyAction::ToggleHelp => "Show / hide this help",
+			KeyAction::ToggleDebug => "Show / hide debug panel",
+		}
+	}
+}
+
+/// Maps keys to [`KeyAction`]s.
+///
+/// Start from [`Keymap::default`] and adjust with [`bind`](Keymap::bind) and
+/// [`unbind`](Keymap::unbind), or build from scratch with [`Keymap::empty`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keymap {
+	bindings: Vec<(String, KeyAction)>,
+}
+
+impl Default for Keymap {
+	fn default() -> Self {
+		Self::empty()
+			.bind("f", KeyAction::Fit)
+			.bind("+", KeyAction::ZoomIn)
+			.bind("=", KeyAction::ZoomIn)
+			.bind("-", KeyAction::ZoomOut)
+			.bind("0", KeyAction::ResetZoom)
+			.bind("Escape", KeyAction::ClearSelection)
+			.bind("u", KeyAction::UnpinAll)
+			.bind("Delete", KeyAction::DeleteSelection)
+			.bind("Backspace", KeyAction::DeleteSelection)
+			.bind("p", KeyAction::TogglePhysics)
+			.bind("r", KeyAction::Reheat)
+			.bind("l", KeyAction::ToggleLabels)
+			.bind("?", KeyAction::ToggleHelp)
+			.bind("d", KeyAction::ToggleDebug)
+	}
+}
+
+impl Keymap {
+	/// A keymap with no bindings.
+	pub fn empty() -> Self {
+		Self {
+			bindings: Vec::new(),
+		}
+	}
+
+	/// Bind `key` to `action`, replacing any existing binding for that key.
+	pub fn bind(mut self, key: impl Into<String>, action: KeyAction) -> Self {
+		let key = normalize(&key.into());
+		self.bindings.retain(|(k, _)| *k != key);
+		self.bindings.push((key, action));
+		self
+	}
+
+	/// Remove the binding for `key`, if any.
+	pub fn unbind(mut self, key: &str) -> Self {
+		let key = normalize(key);
+		self.bindings.retain(|(k, _)| *k != key);
+		self
+	}
+
+	/// The action bound to `key` (a `KeyboardEvent.key` value).
+	pub fn action_for(&self, key: &str) -> Option<KeyAction> {
+		let key = normalize(key);
+		self.bindings
+			.iter()
+			.find(|(k, _)| *k == key)
+			.map(|(_, action)| *action)
+	}
+
+	/// Help overlay rows: each bound action with all of its keys, in binding order.
+	pub fn help_entries(&self) -> Vec<(Vec<String>, &'static str)> {
+		let mut entries: Vec<(KeyAction, Vec<String>)> = Vec::new();
+		for (key, action) in &self.bindings {
+			match entries.iter_mut().find(|(a, _)| a == action) {
+				Some((_, keys)) => keys.push(key.clone()),
+				None => entries.push((*action, vec![key.clone()])),
+			}
+		}
+		entries
+			.into_iter()
+			.map(|(action, keys)| (keys, action.description()))
+			.collect()
+	}
+}
+
+/// Single characters compare case-insensitively; named keys are kept as-is.
+fn normalize(key: &str) -> String {
+	if key.chars().count() == 1 {
+		key.to_lowercase()
+	} else {
+		key.to_string()
+	}
+}
@@ -0,0 +1,300 @@
+//! Label text metrics, measured outside the frame.
+//!
+//! Measuring text is slow enough to cause hitches when hundreds of labels are
+//! measured in one frame. The renderer only ever reads cached widths; a miss
+//! queues the label, and the queue is drained in idle time within a time budget.
+//! Widths are cached per zoom bucket (font sizes a quarter octave apart) and
+//! scaled linearly to the exact font size, so zooming rarely invalidates them.
+//!
+//! Labels are also pre-rendered to offscreen sprites at their on-screen size, so
+//! a frame draws each with one `drawImage` instead of laying out text. Sprites
+//! are rebuilt in idle time whenever the zoom bucket or label color changes;
+//! until then the renderer falls back to drawing text directly. Both caches
+//! drop the labels of nodes that leave the graph.
+//!
+//! Which resting labels fit without overlapping is decided in idle time too,
+//! for a view: a zoom bucket, the pan to within a few pixels, the viewport
+//! size and a generation the state bumps whenever nodes come, go, hide or
+//! move. Frames draw the last placement until a fresh one is made.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use force_graph::DefaultNodeIdx;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use super::state::ViewTransform;
+use super::theme::Color;
+
+/// Font family labels are drawn in (see `ScaledValues::label_font`).
+pub const LABEL_FAMILY: &str = "sans-serif";
+
+/// Zoom buckets per doubling of the font size.
+const BUCKETS_PER_OCTAVE: f64 = 4.0;
+
+/// Pan, in pixels, that places labels anew.
+const PLACEMENT_PAN: f64 = 16.0;
+
+/// Cached label widths and the labels still waiting to be measured.
+#[derive(Debug, Default)]
+pub struct LabelMetrics {
+	/// Width by label text, then zoom bucket
+	widths: HashMap<String, HashMap<i32, f64>>,
+	/// Labels (with zoom bucket) the renderer asked for but that aren't measured yet
+	pending: RefCell<HashSet<(String, i32)>>,
+}
+
+/// Zoom bucket for a font size in pixels.
+fn bucket(font_px: f64) -> i32 {
+	(font_px.max(0.01).log2() * BUCKETS_PER_OCTAVE).round() as i32
+}
+
+/// The font size a bucket is measured at.
+fn bucket_size(bucket: i32) -> f64 {
+	(bucket as f64 / BUCKETS_PER_OCTAVE).exp2()
+}
+
+impl LabelMetrics {
+	/// Width of `label` at `font_px`, or `None` (queuing it for measurement) if
+	/// it hasn't been measured at this zoom bucket yet.
+	pub fn width(&self, label: &str, font_px: f64) -> Option<f64> {
+		let b = bucket(font_px);
+		match self.widths.get(label).and_then(|widths| widths.get(&b)) {
+			Some(width) => Some(width * font_px / bucket_size(b)),
+			None => {
+				self.pending.borrow_mut().insert((label.to_string(), b));
+				None
+			}
+		}
+	}
+
+	/// Forget labels outside `live`, e.g. once their nodes are gone.
+	pub fn retain(&mut self, live: &HashSet<&str>) {
+		self.widths.retain(|label, _| live.contains(label.as_str()));
+		self.pending
+			.get_mut()
+			.retain(|(label, _)| live.contains(label.as_str()));
+	}
+
+	/// Number of cached (label, zoom bucket) widths.
+	pub fn cached(&self) -> usize {
+		self.widths.values().map(HashMap::len).sum()
+	}
+
+	/// Whether any labels are waiting to be measured.
+	pub fn has_pending(&self) -> bool {
+		!self.pending.borrow().is_empty()
+	}
+
+	/// Measure queued labels until `time_left` (milliseconds) runs low.
+	/// Returns whether any width was measured.
+	pub fn measure_pending(
+		&mut self,
+		ctx: &CanvasRenderingContext2d,
+		time_left: impl Fn() -> f64,
+	) -> bool {
+		let pending = self.pending.get_mut();
+		let mut font_bucket = None;
+		let mut measured = false;
+		ctx.save();
+		while time_left() > 1.0 {
+			let Some((label, b)) = pending.iter().next().cloned() else {
+				break;
+			};
+			pending.remove(&(label.clone(), b));
+			if font_bucket != Some(b) {
+				ctx.set_font(&format!("{}px {LABEL_FAMILY}", bucket_size(b)));
+				font_bucket = Some(b);
+			}
+			if let Ok(metrics) = ctx.measure_text(&label) {
+				self.widths
+					.entry(label)
+					.or_default()
+					.insert(b, metrics.width());
+				measured = true;
+			}
+		}
+		ctx.restore();
+		measured
+	}
+}
+
+/// A label pre-rendered at its zoom bucket's font size.
+#[derive(Debug)]
+pub struct Sprite {
+	pub canvas: HtmlCanvasElement,
+	/// Font size the sprite was drawn at, in pixels
+	pub font_px: f64,
+	/// Distance from the top of the sprite to the text baseline, in pixels
+	pub baseline: f64,
+}
+
+/// Offscreen label sprites for the current zoom bucket and label color.
+#[derive(Debug, Default)]
+pub struct LabelSprites {
+	/// Zoom bucket (of the on-screen font size) and color the sprites are drawn for
+	key: Option<(i32, Color)>,
+	sprites: HashMap<String, Sprite>,
+	/// Labels the renderer asked for but that have no current sprite yet
+	pending: RefCell<HashSet<String>>,
+}
+
+impl LabelSprites {
+	/// The sprite for `label` shown at `screen_px` in `color`, or `None` (queuing
+	/// it) if there is no up-to-date one.
+	pub fn get(&self, label: &str, screen_px: f64, color: Color) -> Option<&Sprite> {
+		let sprite = self
+			.sprites
+			.get(label)
+			.filter(|_| self.key == Some((bucket(screen_px), color)));
+		if sprite.is_none() {
+			let mut pending = self.pending.borrow_mut();
+			if !pending.contains(label) {
+				pending.insert(label.to_string());
+			}
+		}
+		sprite
+	}
+
+	/// Drop sprites for labels outside `live`, e.g. once their nodes are gone.
+	pub fn retain(&mut self, live: &HashSet<&str>) {
+		self.sprites
+			.retain(|label, _| live.contains(label.as_str()));
+		self.pending
+			.get_mut()
+			.retain(|label| live.contains(label.as_str()));
+	}
+
+	/// Number of sprites and their combined pixel memory in bytes.
+	pub fn memory(&self) -> (usize, usize) {
+		let bytes = self
+			.sprites
+			.values()
+			.map(|s| s.canvas.width() as usize * s.canvas.height() as usize * 4)
+			.sum();
+		(self.sprites.len(), bytes)
+	}
+
+	/// Whether any sprites are waiting to be drawn.
+	pub fn has_pending(&self) -> bool {
+		!self.pending.borrow().is_empty()
+	}
+
+	/// Draw queued sprites until `time_left` (milliseconds) runs low. A new zoom
+	/// bucket or color discards every existing sprite first. Returns whether
+	/// any sprite was drawn.
+	pub fn render_pending(
+		&mut self,
+		screen_px: f64,
+		color: Color,
+		time_left: impl Fn() -> f64,
+	) -> bool {
+		let key = (bucket(screen_px), color);
+		if self.key != Some(key) {
+			self.key = Some(key);
+			self.pending
+				.get_mut()
+				.extend(self.sprites.drain().map(|(label, _)| label));
+		}
+		let font_px = bucket_size(key.0);
+		let font = format!("{font_px}px {LABEL_FAMILY}");
+		let pending = self.pending.get_mut();
+		let mut rendered = false;
+		while time_left() > 1.0 {
+			let Some(label) = pending.iter().next().cloned() else {
+				break;
+			};
+			pending.remove(&label);
+			if let Some(sprite) = render_sprite(&label, &font, font_px, color) {
+				self.sprites.insert(label, sprite);
+				rendered = true;
+			}
+		}
+		rendered
+	}
+}
+
+/// Draw `label` onto a fresh canvas just large enough to hold it.
+fn render_sprite(label: &str, font: &str, font_px: f64, color: Color) -> Option<Sprite> {
+	let canvas: HtmlCanvasElement = web_sys::window()?
+		.document()?
+		.create_element("canvas")
+		.ok()?
+		.dyn_into()
+		.ok()?;
+	let ctx: CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+	ctx.set_font(font);
+	let width = ctx.measure_text(label).ok()?.width();
+	// Room for descenders below the baseline
+	let baseline = font_px.ceil();
+	canvas.set_width(width.ceil().max(1.0) as u32);
+	canvas.set_height((font_px * 1.3).ceil() as u32);
+	// Resizing resets the context state
+	ctx.set_font(font);
+	ctx.set_fill_style_str(&color.to_css());
+	ctx.fill_text(label, 0.0, baseline).ok()?;
+	Some(Sprite {
+		canvas,
+		font_px,
+		baseline,
+	})
+}
+
+/// The view a label placement was made for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlacementKey {
+	zoom: i32,
+	pan: (i64, i64),
+	size: (i64, i64),
+	top_labels: usize,
+	generation: u64,
+}
+
+impl PlacementKey {
+	/// Key for `transform` on a `size` viewport showing the top `top_labels`
+	/// labels always, at layout `generation`.
+	pub fn new(
+		transform: &ViewTransform,
+		size: (f64, f64),
+		top_labels: usize,
+		generation: u64,
+	) -> Self {
+		let cell = |v: f64| (v / PLACEMENT_PAN).round() as i64;
+		Self {
+			zoom: bucket(transform.k),
+			pan: (cell(transform.x), cell(transform.y)),
+			size: (size.0.round() as i64, size.1.round() as i64),
+			top_labels,
+			generation,
+		}
+	}
+}
+
+/// Nodes whose resting labels are shown, as last placed.
+#[derive(Debug, Default)]
+pub struct PlacedLabels {
+	key: Option<PlacementKey>,
+	nodes: HashSet<DefaultNodeIdx>,
+}
+
+impl PlacedLabels {
+	/// Whether the placement was made for the view `key` describes.
+	pub fn is_current(&self, key: PlacementKey) -> bool {
+		self.key == Some(key)
+	}
+
+	/// Replace the placement with `nodes`, placed for `key`. Returns whether
+	/// a different set of labels is shown.
+	pub fn set(&mut self, key: PlacementKey, nodes: HashSet<DefaultNodeIdx>) -> bool {
+		self.key = Some(key);
+		let changed = nodes != self.nodes;
+		self.nodes = nodes;
+		changed
+	}
+
+	/// Whether `idx`'s resting label is shown.
+	pub fn contains(&self, idx: DefaultNodeIdx) -> bool {
+		self.nodes.contains(&idx)
+	}
+}
@@ -0,0 +1,167 @@
+//! Stacked canvases, so a frame repaints only the layers that changed.
+//!
+//! With layered rendering the picture is split over four canvases, bottom to
+//! top: the background (fill, pattern, particles, grid, lanes and plugin
+//! underlays), the edges, the nodes (with labels and plugin overlays), and the
+//! interaction overlay (selection rectangle, lasso, link drafts, off-screen
+//! indicators), which is the component's own canvas and receives the pointer.
+//!
+//! Hovering dims the edge canvas as a whole through its CSS opacity and draws
+//! the highlighted edges again on the node canvas, so the edge canvas, usually
+//! the costliest to paint, is left alone while the pointer moves.
+
+use std::ops::{BitOr, BitOrAssign};
+
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlElement};
+
+/// CSS for the canvases stacked under the component's own.
+pub const LAYER_STYLE: &str = "position: absolute; top: 0; left: 0; pointer-events: none;";
+
+/// A set of canvas layers, e.g. those a frame has to repaint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Layers {
+	/// Fill, pattern, particles, grid, lanes and plugin underlays
+	pub background: bool,
+	/// Edge lines, arrows, counts and traffic
+	pub edges: bool,
+	/// Nodes, labels, highlighted edges and plugin overlays
+	pub nodes: bool,
+	/// Interaction feedback, indicators and the vignette
+	pub overlay: bool,
+}
+
+impl Layers {
+	/// Every layer.
+	pub const ALL: Self = Self {
+		background: true,
+		edges: true,
+		nodes: true,
+		overlay: true,
+	};
+
+	/// The node layer alone, e.g. when labels change.
+	pub const NODES: Self = Self {
+		background: false,
+		edges: false,
+		nodes: true,
+		overlay: false,
+	};
+
+	/// The interaction overlay alone, which also places elements pinned to
+	/// nodes.
+	pub const OVERLAY: Self = Self {
+		background: false,
+		edges: false,
+		nodes: false,
+		overlay: true,
+	};
+
+	/// The layers the pointer affects without moving nodes or the camera:
+	/// hover highlighting and the interaction overlay.
+	pub const POINTER: Self = Self {
+		background: false,
+		edges: false,
+		nodes: true,
+		overlay: true,
+	};
+
+	/// Whether the set contains any layer.
+	pub fn any(self) -> bool {
+		self.background || self.edges || self.nodes || self.overlay
+	}
+}
+
+impl BitOr for Layers {
+	type Output = Self;
+
+	fn bitor(self, other: Self) -> Self {
+		Self {
+			background: self.background || other.background,
+			edges: self.edges || other.edges,
+			nodes: self.nodes || other.nodes,
+			overlay: self.overlay || other.overlay,
+		}
+	}
+}
+
+impl BitOrAssign for Layers {
+	fn bitor_assign(&mut self, other: Self) {
+		*self = *self | other;
+	}
+}
+
+/// The background, edge and node canvases under the component's own.
+pub struct LayerStack {
+	canvases: [HtmlCanvasElement; 3],
+	contexts: [CanvasRenderingContext2d; 3],
+	/// CSS opacity last given to the edge canvas
+	edge_opacity: f64,
+	/// Whether the stack holds the current picture, rather than the top canvas
+	/// holding all of it (while printing or capturing video)
+	active: bool,
+}
+
+impl LayerStack {
+	/// Stack for the background, edge and node canvases, bottom to top.
+	pub fn new(canvases: [HtmlCanvasElement; 3]) -> Option<Self> {
+		let context = |canvas: &HtmlCanvasElement| {
+			canvas
+				.get_context("2d")
+				.ok()
+				.flatten()?
+				.dyn_into::<CanvasRenderingContext2d>()
+				.ok()
+		};
+		let contexts = [
+			context(&canvases[0])?,
+			context(&canvases[1])?,
+			context(&canvases[2])?,
+		];
+		Some(Self {
+			canvases,
+			contexts,
+			edge_opacity: 1.0,
+			active: false,
+		})
+	}
+
+	/// Match the canvases' backing stores to the top canvas's.
+	pub fn resize(&mut self, width: u32, height: u32) {
+		for canvas in &self.canvases {
+			canvas.set_width(width);
+			canvas.set_height(height);
+		}
+		self.active = false;
+	}
+
+	/// Contexts to draw the background, edges and nodes with. Returns whether
+	/// the stack was showing an older picture, so every layer must be drawn.
+	pub fn contexts(&mut self) -> (&[CanvasRenderingContext2d; 3], bool) {
+		let stale = !self.active;
+		self.active = true;
+		(&self.contexts, stale)
+	}
+
+	/// Dim the edge canvas to `opacity`.
+	pub fn set_edge_opacity(&mut self, opacity: f64) {
+		if (opacity - self.edge_opacity).abs() < 0.005 {
+			return;
+		}
+		self.edge_opacity = opacity;
+		let style = HtmlElement::style(&self.canvases[1]);
+		let _ = style.set_property("opacity", &format!("{opacity:.2}"));
+	}
+
+	/// Clear the stack while the top canvas draws the whole picture.
+	pub fn suspend(&mut self) {
+		if !self.active {
+			return;
+		}
+		self.active = false;
+		for (canvas, ctx) in self.canvases.iter().zip(&self.contexts) {
+			let _ = ctx.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+			ctx.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+		}
+	}
+}
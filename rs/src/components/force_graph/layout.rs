@@ -0,0 +1,305 @@
+//! Layout modes that constrain where the simulation may place nodes.
+//!
+//! The physics still arranges the graph; a layout only restricts each node's
+//! position along one or both axes, enforced after every simulation step.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use force_graph::DefaultNodeIdx;
+
+use super::adjacency::Adjacency;
+
+/// Width of one swimlane in graph units.
+const LANE_WIDTH: f64 = 260.0;
+
+/// Gap kept free on either side of a lane's nodes, in graph units.
+const LANE_PADDING: f64 = 30.0;
+
+/// Title of the lane holding nodes without a group.
+const UNGROUPED_LANE: &str = "(ungrouped)";
+
+/// Fraction of the remaining distance an axis layout pulls a node each step.
+const AXIS_PULL: f64 = 0.25;
+
+/// How nodes are arranged on top of the force simulation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Layout {
+	/// Nodes go wherever the forces take them.
+	#[default]
+	Free,
+	/// One vertical lane per group, left to right. Nodes move freely up and down
+	/// but never leave their group's lane. Groups listed in `order` come first, in
+	/// that order; the rest follow in order of first appearance, with ungrouped
+	/// nodes in a lane of their own at the end.
+	Swimlanes {
+		/// Group names in lane order, left to right.
+		order: Vec<String>,
+	},
+	/// Position along `axis` follows `field`, spread over `length` graph units,
+	/// while the forces arrange the other axis. Nodes without a value for the
+	/// field are left free.
+	Axis {
+		/// The axis the field drives.
+		axis: Axis,
+		/// What each node is positioned by.
+		field: AxisField,
+		/// Distance between the lowest and highest value, in graph units.
+		length: f64,
+	},
+	/// Nodes placed left to right by their `timestamp` over `length` graph units,
+	/// above a time axis ruler, with links drawn as arcs. Nodes without a
+	/// timestamp are left free.
+	Timeline {
+		/// Distance between the earliest and latest timestamp, in graph units.
+		length: f64,
+	},
+}
+
+/// A screen axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Axis {
+	/// Horizontal, increasing to the right.
+	X,
+	/// Vertical, increasing downward.
+	Y,
+}
+
+/// Per-node value an axis layout positions nodes by.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AxisField {
+	/// Number of links from the nearest node nothing links to (a root).
+	Depth,
+	/// A key into each node's `metrics` map.
+	Metric(String),
+	/// The node's `timestamp`.
+	Timestamp,
+}
+
+/// Limit on one coordinate of a node, in graph units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AxisConstraint {
+	/// Keep the coordinate within `min..=max`.
+	Band { min: f64, max: f64 },
+	/// Pull the coordinate toward `value`, closing `strength` of the distance
+	/// each step.
+	Target { value: f64, strength: f64 },
+}
+
+impl AxisConstraint {
+	/// Pull toward `value` at the axis layout's strength.
+	pub fn target(value: f64) -> Self {
+		AxisConstraint::Target {
+			value,
+			strength: AXIS_PULL,
+		}
+	}
+
+	/// `value` moved onto (or toward) the constraint.
+	pub fn apply(self, value: f32) -> f32 {
+		match self {
+			AxisConstraint::Band { min, max } => value.clamp(min as f32, max as f32),
+			AxisConstraint::Target {
+				value: target,
+				strength,
+			} => value + ((target as f32) - value) * strength as f32,
+		}
+	}
+}
+
+/// Per-axis limits a layout places on a node.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NodeConstraint {
+	pub x: Option<AxisConstraint>,
+	pub y: Option<AxisConstraint>,
+}
+
+/// One swimlane: its title and horizontal extent in graph units.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lane {
+	pub title: String,
+	/// The node group the lane holds; `None` for the ungrouped lane
+	pub group: Option<String>,
+	pub min: f64,
+	pub max: f64,
+}
+
+impl Lane {
+	/// Constraint keeping a node inside this lane.
+	pub fn constraint(&self) -> NodeConstraint {
+		NodeConstraint {
+			x: Some(AxisConstraint::Band {
+				min: self.min + LANE_PADDING,
+				max: self.max - LANE_PADDING,
+			}),
+			y: None,
+		}
+	}
+}
+
+/// Lanes for nodes with `groups`, side by side and centered on `center_x`.
+pub fn swimlanes<'a>(
+	order: &[String],
+	groups: impl IntoIterator<Item = Option<&'a str>>,
+	center_x: f64,
+) -> Vec<Lane> {
+	let mut names: Vec<Option<String>> = order.iter().cloned().map(Some).collect();
+	let mut ungrouped = false;
+	for group in groups {
+		match group {
+			Some(g) if !names.iter().any(|n| n.as_deref() == Some(g)) => {
+				names.push(Some(g.to_string()))
+			}
+			Some(_) => {}
+			None => ungrouped = true,
+		}
+	}
+	if ungrouped {
+		names.push(None);
+	}
+	let left = center_x - names.len() as f64 * LANE_WIDTH / 2.0;
+	names
+		.into_iter()
+		.enumerate()
+		.map(|(i, group)| Lane {
+			title: group.clone().unwrap_or_else(|| UNGROUPED_LANE.to_string()),
+			group,
+			min: left + i as f64 * LANE_WIDTH,
+			max: left + (i + 1) as f64 * LANE_WIDTH,
+		})
+		.collect()
+}
+
+/// Link distance of each node from the nearest root (a node nothing links to),
+/// following links from source to target. Nodes only reachable through a cycle
+/// get no depth.
+pub fn depths(
+	nodes: impl IntoIterator<Item = DefaultNodeIdx>,
+	adjacency: &Adjacency,
+) -> HashMap<DefaultNodeIdx, usize> {
+	let mut depths = HashMap::new();
+	let mut queue = VecDeque::new();
+	for idx in nodes {
+		if adjacency.incoming(idx).is_empty() {
+			depths.insert(idx, 0);
+			queue.push_back(idx);
+		}
+	}
+	while let Some(idx) = queue.pop_front() {
+		let depth = depths[&idx] + 1;
+		for &next in adjacency.outgoing(idx) {
+			if let Entry::Vacant(entry) = depths.entry(next) {
+				entry.insert(depth);
+				queue.push_back(next);
+			}
+		}
+	}
+	depths
+}
+
+/// Average length of a calendar month, in seconds.
+const MONTH: f64 = 365.25 / 12.0 * 86400.0;
+
+/// Candidate tick spacings for the time axis, in seconds.
+const TICK_STEPS: [f64; 10] = [
+	60.0,
+	3600.0,
+	6.0 * 3600.0,
+	86400.0,
+	7.0 * 86400.0,
+	MONTH,
+	3.0 * MONTH,
+	12.0 * MONTH,
+	60.0 * MONTH,
+	120.0 * MONTH,
+];
+
+/// Linear mapping between time (Unix seconds) and horizontal graph position,
+/// for the timeline layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeScale {
+	/// Earliest and latest time on the axis
+	pub start: f64,
+	pub end: f64,
+	/// Graph x positions of `start` and `end`
+	pub left: f64,
+	pub right: f64,
+}
+
+impl TimeScale {
+	/// Graph x position of time `t`.
+	pub fn x(&self, t: f64) -> f64 {
+		if self.end > self.start {
+			self.left + (t - self.start) / (self.end - self.start) * (self.right - self.left)
+		} else {
+			(self.left + self.right) / 2.0
+		}
+	}
+
+	/// Time at graph x position `x`.
+	pub fn time(&self, x: f64) -> f64 {
+		if self.right > self.left {
+			self.start + (x - self.left) / (self.right - self.left) * (self.end - self.start)
+		} else {
+			self.start
+		}
+	}
+
+	/// Tick times and labels between `from` and `to` (Unix seconds), spaced so
+	/// neighbors are at least `min_gap` graph units apart.
+	pub fn ticks(&self, from: f64, to: f64, min_gap: f64) -> Vec<(f64, String)> {
+		let per_unit = (self.end - self.start) / (self.right - self.left).max(f64::EPSILON);
+		let Some(&step) = TICK_STEPS
+			.iter()
+			.find(|&&step| step / per_unit.max(f64::EPSILON) >= min_gap)
+		else {
+			return Vec::new();
+		};
+		let mut ticks = Vec::new();
+		if step < MONTH {
+			let mut t = (from / step).ceil() * step;
+			while t <= to {
+				ticks.push((t, tick_label(t, step)));
+				t += step;
+			}
+			return ticks;
+		}
+		// Month-sized steps follow the calendar, so ticks land on the 1st
+		let months = (step / MONTH).round() as i32;
+		let first = js_sys::Date::new(&(from * 1000.0).into());
+		let mut month = first.get_utc_full_year() as i32 * 12 + first.get_utc_month() as i32;
+		month = month.div_euclid(months) * months;
+		loop {
+			let t = js_sys::Date::utc(month.div_euclid(12) as f64, month.rem_euclid(12) as f64)
+				/ 1000.0;
+			if t > to {
+				break;
+			}
+			if t >= from {
+				ticks.push((t, tick_label(t, step)));
+			}
+			month += months;
+		}
+		ticks
+	}
+}
+
+/// Label for a tick at `t` (Unix seconds), as precise as the tick `step` needs.
+fn tick_label(t: f64, step: f64) -> String {
+	let date = js_sys::Date::new(&(t * 1000.0).into());
+	let (year, month, day) = (
+		date.get_utc_full_year(),
+		date.get_utc_month() + 1,
+		date.get_utc_date(),
+	);
+	if step >= 12.0 * MONTH {
+		format!("{year}")
+	} else if step >= MONTH {
+		format!("{year}-{month:02}")
+	} else if step >= 86400.0 {
+		format!("{year}-{month:02}-{day:02}")
+	} else {
+		let (hours, minutes) = (date.get_utc_hours(), date.get_utc_minutes());
+		format!("{month:02}-{day:02} {hours:02}:{minutes:02}")
+	}
+}
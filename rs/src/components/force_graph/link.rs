@@ -0,0 +1,50 @@
+//! Linking several canvases into coordinated views.
+//!
+//! Canvases passed the same [`ViewLink`] share their hovered node, selection
+//! and (optionally) camera, so a "before" and "after" pair of graphs highlight
+//! the same node in both while either is hovered. Views may show different
+//! nodes: an ID one graph lacks leaves it unhighlighted without clearing it
+//! from the others.
+
+use leptos::prelude::*;
+
+use super::state::ViewTransform;
+
+/// Signals shared by linked [`ForceGraphCanvas`](super::ForceGraphCanvas)
+/// instances, passed to each as `linked`. Signals a canvas is given directly
+/// (`hovered`, `selected`, `transform`) take precedence.
+#[derive(Clone, Copy)]
+pub struct ViewLink {
+	/// ID of the node hovered in any of the views.
+	pub hovered: RwSignal<Option<String>>,
+	/// IDs of the nodes selected in any of the views.
+	pub selected: RwSignal<Vec<String>>,
+	/// Pan and zoom shared by the views, if they move together.
+	pub camera: Option<RwSignal<ViewTransform>>,
+}
+
+impl ViewLink {
+	/// Views sharing hover, selection and camera.
+	pub fn new() -> Self {
+		Self {
+			hovered: RwSignal::new(None),
+			selected: RwSignal::new(Vec::new()),
+			camera: Some(RwSignal::new(ViewTransform::default())),
+		}
+	}
+
+	/// The same link with each view keeping its own camera, e.g. for graphs
+	/// whose layouts differ too much to line up.
+	pub fn without_camera(self) -> Self {
+		Self {
+			camera: None,
+			..self
+		}
+	}
+}
+
+impl Default for ViewLink {
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -2,10 +2,26 @@
 //!
 //! Renders an interactive force-directed graph on an HTML canvas with:
 //! - Physics-based node positioning via force simulation
-//! - Pan, zoom, and node dragging interactions
+//! - Pan, zoom, and node dragging interactions, with optional grid snapping
+//! - Configurable keyboard shortcuts with a help overlay
+//! - Optional fisheye lens that magnifies the area around the cursor
 //! - Smooth highlight transitions on hover
+//! - Collapsible groups, with their links merged into weighted meta-edges
+//! - Swimlane, data-driven axis and timeline layouts
+//! - Labels revealed by priority as you zoom in, without overlaps
 //! - Configurable theming and visual scaling
 //!
+//! [`GraphModel`] answers neighbor, dependency and path queries over the same
+//! [`GraphData`] by node ID, and [`generators`] builds synthetic graphs for
+//! demos and stress tests. Pointer input can be recorded and replayed frame by
+//! frame through a [`GraphHandle`], for repeatable demos and bug reports, and
+//! the canvas can be captured to a WebM clip.
+//!
+//! Particles, glows, the extra themes and metric heatmaps sit behind the
+//! `particles`, `glow`, `themes` and `analysis` cargo features (all on by
+//! default); embeds that only need nodes, edges and pan/zoom can disable them
+//! for a smaller `.wasm`.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -24,14 +40,54 @@
 //! view! { <ForceGraphCanvas data=data.into() fullscreen=true /> }
 //! ```
 
+mod adjacency;
+mod anchors;
+mod capture;
 mod component;
+mod context_menu;
+mod depth;
+mod easing;
+mod fisheye;
+pub mod generators;
+mod history;
+mod hooks;
+mod keymap;
+mod labels;
+mod layers;
+mod layout;
+mod link;
+mod model;
+#[cfg(feature = "particles")]
 mod particles;
+mod physics;
+mod provider;
+mod recording;
 mod render;
 pub mod scale;
+mod showcase;
 mod state;
 pub mod theme;
 mod types;
+mod view_model;
 
 pub use component::ForceGraphCanvas;
+pub use context_menu::{ContextMenuItem, ContextTarget};
+pub use easing::Easing;
+pub use hooks::{
+	EdgeDrawHook, EdgeRenderInfo, NodeDrawHook, NodeRenderInfo, RenderFrame, RenderPlugin,
+};
+pub use keymap::{KeyAction, Keymap};
+pub use layout::{Axis, AxisField, Layout};
+pub use link::ViewLink;
+pub use model::GraphModel;
+pub use physics::PhysicsConfig;
+pub use provider::{GraphHandle, GraphProvider, use_graph};
+pub use recording::{InputKind, RecordedInput, Recording};
+pub use scale::ScaleConfig;
+pub use state::{
+	CameraConfig, HeatmapConfig, HighlightConfig, NodeSizing, TransitionConfig, ViewTransform,
+};
 pub use theme::Theme;
-pub use types::{GraphData, GraphLink, GraphNode};
+pub use types::{
+	GraphData, GraphLink, GraphNode, GraphSnapshot, NodeBadge, NodePulse, RingSegment,
+};
@@ -0,0 +1,137 @@
+//! Queries over graph data by node ID.
+//!
+//! A link from `a` to `b` reads as "`a` depends on `b`", as in the hover
+//! tooltip. Application code can answer questions about a graph here instead
+//! of walking its links by hand.
+
+use std::collections::{HashMap, HashSet};
+
+use force_graph::DefaultNodeIdx;
+
+use super::adjacency::Adjacency;
+use super::types::GraphData;
+
+/// Indexed, read-only view of a [`GraphData`] for neighbor, dependency and
+/// path queries. Links to unknown IDs and duplicate links are ignored.
+///
+/// Nodes are indexed by their position in the data, over the same neighbor
+/// index the canvas uses for hovering, tooltips and its component navigator.
+#[derive(Clone, Debug, Default)]
+pub struct GraphModel {
+	data: GraphData,
+	/// Position in `data.nodes` by node ID
+	index: HashMap<String, DefaultNodeIdx>,
+	/// Links between positions
+	adjacency: Adjacency,
+}
+
+impl GraphModel {
+	/// Index `data` for queries.
+	pub fn new(data: GraphData) -> Self {
+		let index: HashMap<String, DefaultNodeIdx> = data
+			.nodes
+			.iter()
+			.enumerate()
+			.map(|(i, node)| (node.id.clone(), DefaultNodeIdx::new(i)))
+			.collect();
+		let mut adjacency = Adjacency::default();
+		for link in &data.links {
+			let (Some(&a), Some(&b)) = (index.get(&link.source), index.get(&link.target)) else {
+				continue;
+			};
+			if !adjacency.outgoing(a).contains(&b) {
+				adjacency.insert(a, b);
+			}
+		}
+		Self {
+			data,
+			index,
+			adjacency,
+		}
+	}
+
+	/// The data the model was built from.
+	pub fn data(&self) -> &GraphData {
+		&self.data
+	}
+
+	/// Whether a node with `id` exists.
+	pub fn contains(&self, id: &str) -> bool {
+		self.index.contains_key(id)
+	}
+
+	/// Nodes linked to or from `id`, each once: what it depends on, then what
+	/// depends on it.
+	pub fn neighbors(&self, id: &str) -> Vec<&str> {
+		let Some(&idx) = self.index.get(id) else {
+			return Vec::new();
+		};
+		let mut seen = HashSet::new();
+		self.adjacency
+			.neighbors(idx)
+			.filter(|&n| seen.insert(n))
+			.map(|n| self.id(n))
+			.collect()
+	}
+
+	/// Everything `id` depends on, directly or through other nodes, nearest first.
+	pub fn dependencies(&self, id: &str) -> Vec<&str> {
+		self.index
+			.get(id)
+			.map_or_else(Vec::new, |&idx| self.ids(self.adjacency.dependencies(idx)))
+	}
+
+	/// Everything that depends on `id`, directly or through other nodes, nearest
+	/// first.
+	pub fn dependents(&self, id: &str) -> Vec<&str> {
+		self.index
+			.get(id)
+			.map_or_else(Vec::new, |&idx| self.ids(self.adjacency.dependents(idx)))
+	}
+
+	/// A shortest chain of links from `from` to `to`, following links in either
+	/// direction, including both ends. `None` if they aren't connected.
+	pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<&str>> {
+		let (&start, &goal) = (self.index.get(from)?, self.index.get(to)?);
+		self.adjacency
+			.shortest_path(start, goal)
+			.map(|path| self.ids(path))
+	}
+
+	/// The nodes in `ids` and the links between them, as new graph data. Unknown
+	/// IDs are skipped.
+	pub fn subgraph<'a>(&self, ids: impl IntoIterator<Item = &'a str>) -> GraphData {
+		let keep: HashSet<DefaultNodeIdx> = ids
+			.into_iter()
+			.filter_map(|id| self.index.get(id).copied())
+			.collect();
+		let kept = |id: &String| self.index.get(id).is_some_and(|i| keep.contains(i));
+		GraphData {
+			nodes: self
+				.data
+				.nodes
+				.iter()
+				.filter(|node| kept(&node.id))
+				.cloned()
+				.collect(),
+			links: self
+				.data
+				.links
+				.iter()
+				.filter(|link| kept(&link.source) && kept(&link.target))
+				.cloned()
+				.collect(),
+			cluster_colors: self.data.cluster_colors.clone(),
+		}
+	}
+
+	/// ID of the node at `idx`.
+	fn id(&self, idx: DefaultNodeIdx) -> &str {
+		&self.data.nodes[idx.index()].id
+	}
+
+	/// IDs of the nodes in `nodes`, in order.
+	fn ids(&self, nodes: Vec<DefaultNodeIdx>) -> Vec<&str> {
+		nodes.into_iter().map(|idx| self.id(idx)).collect()
+	}
+}
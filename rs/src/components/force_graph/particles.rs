@@ -1,7 +1,23 @@
 //! Ambient particle effects for visual atmosphere.
+//!
+//! Particles drift in screen space. With a theme `deflect` strength they also
+//! stream around the nodes, like flow around obstacles, so the atmosphere
+//! follows the graph's shape.
+
+use std::collections::HashMap;
 
 use super::theme::ParticleStyle;
 
+/// Side of the grid cells nodes are bucketed into for deflection, in pixels.
+/// Also the furthest a node's influence reaches.
+const DEFLECT_CELL: f64 = 64.0;
+
+/// Clearance around a node within which particles are deflected, in pixels.
+const DEFLECT_MARGIN: f64 = 24.0;
+
+/// Obstacles (screen x, y and radius) by grid cell.
+type ObstacleGrid = HashMap<(i64, i64), Vec<(f64, f64, f64)>>;
+
 /// A single floating particle.
 #[derive(Clone, Debug)]
 pub struct Particle {
@@ -23,46 +39,64 @@ pub struct ParticleSystem {
 
 impl ParticleSystem {
 	pub fn new(style: &ParticleStyle, width: f64, height: f64) -> Self {
-		let mut particles = Vec::with_capacity(style.count);
-
-		for i in 0..style.count {
-			// Use deterministic pseudo-random based on index for consistent look
-			let seed = i as f64;
-			let px = Self::pseudo_random(seed * 1.1) * width;
-			let py = Self::pseudo_random(seed * 2.3) * height;
-			let angle = Self::pseudo_random(seed * 3.7) * std::f64::consts::TAU;
-			let speed = style.speed * (0.5 + Self::pseudo_random(seed * 4.1) * 0.5);
-
-			particles.push(Particle {
-				x: px,
-				y: py,
-				vx: angle.cos() * speed,
-				vy: angle.sin() * speed,
-				size: style.size_min
-					+ Self::pseudo_random(seed * 5.3) * (style.size_max - style.size_min),
-				alpha: style.opacity * (0.3 + Self::pseudo_random(seed * 6.7) * 0.7),
-				phase: Self::pseudo_random(seed * 7.9) * std::f64::consts::TAU,
-			});
-		}
-
+		let count = style.count_for(width, height);
 		Self {
-			particles,
+			particles: (0..count)
+				.map(|i| Self::spawn(style, i, width, height))
+				.collect(),
 			width,
 			height,
 		}
 	}
 
+	/// The `i`th particle of a `width` × `height` field.
+	fn spawn(style: &ParticleStyle, i: usize, width: f64, height: f64) -> Particle {
+		// Use deterministic pseudo-random based on index for consistent look
+		let seed = i as f64;
+		let px = Self::pseudo_random(seed * 1.1) * width;
+		let py = Self::pseudo_random(seed * 2.3) * height;
+		let angle = Self::pseudo_random(seed * 3.7) * std::f64::consts::TAU;
+		let speed = style.speed * (0.5 + Self::pseudo_random(seed * 4.1) * 0.5);
+
+		Particle {
+			x: px,
+			y: py,
+			vx: angle.cos() * speed,
+			vy: angle.sin() * speed,
+			size: style.size_min
+				+ Self::pseudo_random(seed * 5.3) * (style.size_max - style.size_min),
+			alpha: style.opacity * (0.3 + Self::pseudo_random(seed * 6.7) * 0.7),
+			phase: Self::pseudo_random(seed * 7.9) * std::f64::consts::TAU,
+		}
+	}
+
 	/// Simple pseudo-random function (deterministic)
 	fn pseudo_random(seed: f64) -> f64 {
 		let x = (seed * 12.9898 + seed * 78.233).sin() * 43758.5453;
 		x - x.floor()
 	}
 
-	/// Update particle positions
-	pub fn update(&mut self, dt: f64) {
+	/// Update particle positions, steering them around `obstacles` (screen x, y
+	/// and radius) by `deflect` (0.0 to 1.0).
+	pub fn update(&mut self, dt: f64, obstacles: &[(f64, f64, f64)], deflect: f64) {
+		let mut cells = ObstacleGrid::new();
+		if deflect > 0.0 {
+			for &(x, y, r) in obstacles {
+				let cell = (
+					(x / DEFLECT_CELL).floor() as i64,
+					(y / DEFLECT_CELL).floor() as i64,
+				);
+				cells.entry(cell).or_default().push((x, y, r));
+			}
+		}
 		for p in &mut self.particles {
-			p.x += p.vx * dt * 60.0;
-			p.y += p.vy * dt * 60.0;
+			let (dx, dy) = if cells.is_empty() {
+				(0.0, 0.0)
+			} else {
+				Self::deflection(p, &cells, deflect)
+			};
+			p.x += (p.vx + dx) * dt * 60.0;
+			p.y += (p.vy + dy) * dt * 60.0;
 			p.phase += dt * 2.0;
 
 			// Wrap around screen edges
@@ -79,8 +113,43 @@ impl ParticleSystem {
 		}
 	}
 
-	/// Resize the particle system bounds
-	pub fn resize(&mut self, width: f64, height: f64) {
+	/// Extra velocity steering `p` around nearby nodes: mostly sideways, along
+	/// the side its heading already favors, with a little push outward. Its own
+	/// velocity is left alone, so it resumes its course once past.
+	fn deflection(p: &Particle, cells: &ObstacleGrid, deflect: f64) -> (f64, f64) {
+		let speed = p.vx.hypot(p.vy);
+		let (cx, cy) = (
+			(p.x / DEFLECT_CELL).floor() as i64,
+			(p.y / DEFLECT_CELL).floor() as i64,
+		);
+		let (mut dx, mut dy) = (0.0, 0.0);
+		for cell in (cx - 1..=cx + 1).flat_map(|x| (cy - 1..=cy + 1).map(move |y| (x, y))) {
+			for &(ox, oy, r) in cells.get(&cell).into_iter().flatten() {
+				let (nx, ny) = (p.x - ox, p.y - oy);
+				let dist = nx.hypot(ny).max(0.001);
+				let reach = (r + DEFLECT_MARGIN).min(DEFLECT_CELL);
+				if dist >= reach {
+					continue;
+				}
+				let (nx, ny) = (nx / dist, ny / dist);
+				// Pass on whichever side the particle is already heading for
+				let side = if p.vx * -ny + p.vy * nx >= 0.0 {
+					1.0
+				} else {
+					-1.0
+				};
+				let falloff = 1.0 - dist / reach;
+				let push = deflect * falloff * speed;
+				dx += push * (-ny * side + nx * 0.5);
+				dy += push * (nx * side + ny * 0.5);
+			}
+		}
+		(dx, dy)
+	}
+
+	/// Resize the particle system bounds, adding or dropping particles to keep
+	/// the style's density
+	pub fn resize(&mut self, style: &ParticleStyle, width: f64, height: f64) {
 		// Scale particle positions proportionally
 		let scale_x = width / self.width;
 		let scale_y = height / self.height;
@@ -92,6 +161,12 @@ impl ParticleSystem {
 
 		self.width = width;
 		self.height = height;
+
+		let count = style.count_for(width, height);
+		self.particles.truncate(count);
+		for i in self.particles.len()..count {
+			self.particles.push(Self::spawn(style, i, width, height));
+		}
 	}
 
 	/// Get twinkle alpha for a particle
@@ -0,0 +1,102 @@
+//! Forces layered on top of the `force_graph` simulation.
+//!
+//! The simulation itself only knows charge and springs. These extra forces are
+//! applied as position corrections after every step, the same way layouts
+//! constrain nodes (see [`Layout`](super::Layout)).
+
+/// Tuning for the extra forces acting on the layout.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhysicsConfig {
+	/// Most fixed-length simulation steps run per animation frame to catch up
+	/// with real time (default 4). Time beyond that is dropped, so slow devices
+	/// see a slower layout rather than an unstable one.
+	pub max_substeps: u32,
+	/// Links out from a dragged node that the simulation keeps moving when the
+	/// drag disturbs a settled layout (default 2). The rest holds still until
+	/// the layout settles again, so adjusting one cluster doesn't set the whole
+	/// graph wobbling; `None` lets every node move.
+	pub drag_hops: Option<usize>,
+	/// Fraction of the gap between the nodes' center of mass and `center`
+	/// closed each step, in 0..1 (default 0, off). The whole layout shifts
+	/// together, so its shape is untouched while it stays on screen.
+	pub centering: f64,
+	/// Point in graph units the layout is centered on; the origin is the canvas
+	/// center before any panning.
+	pub center: (f64, f64),
+	/// Scale each node's repulsion with the square root of its number of links,
+	/// so hubs push harder and claim room for their spokes (default off).
+	pub degree_charge: bool,
+	/// Fraction of the difference between an edge's length and its preferred
+	/// [`length`](super::GraphLink::length) undone each step, split between its
+	/// ends (default 0.1).
+	pub length_stiffness: f64,
+	/// World rectangle (x, y, width, height) nodes are kept inside, so a stray
+	/// node can't fly off and ruin zoom-to-fit; `None` leaves them unbounded.
+	pub bounds: Option<(f64, f64, f64, f64)>,
+	/// How far inside the bounds nodes start being pushed back, in graph units
+	/// (default 20).
+	pub bounds_padding: f64,
+	/// Fraction of a node's overshoot past the padding undone each step (default
+	/// 0.2). 1.0 clamps hard.
+	pub bounds_stiffness: f64,
+}
+
+impl Default for PhysicsConfig {
+	fn default() -> Self {
+		Self {
+			max_substeps: 4,
+			drag_hops: Some(2),
+			centering: 0.0,
+			center: (0.0, 0.0),
+			degree_charge: false,
+			length_stiffness: 0.1,
+			bounds: None,
+			bounds_padding: 20.0,
+			bounds_stiffness: 0.2,
+		}
+	}
+}
+
+impl PhysicsConfig {
+	/// Whether any of the extra forces is active.
+	pub fn is_active(&self) -> bool {
+		self.centering > 0.0 || self.bounds.is_some()
+	}
+
+	/// Offset to move every free node by this step, given the free nodes'
+	/// center of mass.
+	pub fn centering_shift(&self, (mean_x, mean_y): (f64, f64)) -> (f64, f64) {
+		let strength = self.centering.clamp(0.0, 1.0);
+		(
+			(self.center.0 - mean_x) * strength,
+			(self.center.1 - mean_y) * strength,
+		)
+	}
+
+	/// Position (`x`, `y`) after one step of the bounding force.
+	pub fn bound(&self, x: f64, y: f64) -> (f64, f64) {
+		let Some((left, top, width, height)) = self.bounds else {
+			return (x, y);
+		};
+		// Padding can't exceed half the box, or the two sides would fight
+		let (pad_x, pad_y) = (
+			self.bounds_padding.clamp(0.0, width / 2.0),
+			self.bounds_padding.clamp(0.0, height / 2.0),
+		);
+		let stiffness = self.bounds_stiffness.clamp(0.0, 1.0);
+		let pull = |v: f64, lo: f64, hi: f64| {
+			let overshoot = if v < lo {
+				v - lo
+			} else if v > hi {
+				v - hi
+			} else {
+				0.0
+			};
+			v - overshoot * stiffness
+		};
+		(
+			pull(x, left + pad_x, left + width - pad_x),
+			pull(y, top + pad_y, top + height - pad_y),
+		)
+	}
+}
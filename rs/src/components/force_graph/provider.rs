@@ -0,0 +1,243 @@
+//! Graph handle shared with companion components through Leptos context.
+//!
+//! Wrap a [`ForceGraphCanvas`](super::ForceGraphCanvas) and the host's own
+//! panels (a legend, a search box, a stats panel) in a [`GraphProvider`]. The
+//! canvas attaches itself to the provided [`GraphHandle`], and the panels reach
+//! it with [`use_graph`] to read the data, follow hovering and move the camera.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use leptos::prelude::*;
+
+use super::component::GraphContext;
+use super::hooks::RenderPlugin;
+use super::model::GraphModel;
+use super::recording::Recording;
+use super::state::ViewTransform;
+use super::types::GraphData;
+
+/// The canvas's graph context, shared with its event handlers
+type SharedContext = Rc<RefCell<Option<GraphContext>>>;
+
+/// Access to a [`ForceGraphCanvas`](super::ForceGraphCanvas) from outside it:
+/// the data it shows, its hovered node and camera, and camera controls.
+#[derive(Clone, Copy)]
+pub struct GraphHandle {
+	/// The attached canvas's context, while it is mounted
+	context: StoredValue<Option<SharedContext>, LocalStorage>,
+	data: RwSignal<Option<GraphData>>,
+	hovered: RwSignal<Option<String>>,
+	transform: RwSignal<ViewTransform>,
+}
+
+impl GraphHandle {
+	fn new() -> Self {
+		Self {
+			context: StoredValue::new_local(None),
+			data: RwSignal::new(None),
+			hovered: RwSignal::new(None),
+			transform: RwSignal::new(ViewTransform::default()),
+		}
+	}
+
+	/// The data the canvas shows (from its `data` prop, `setData`, or the
+	/// current timeline snapshot); `None` until a canvas attaches. Reactive.
+	pub fn data(&self) -> Option<GraphData> {
+		self.data.get()
+	}
+
+	/// A [`GraphModel`] of [`data`](Self::data) for queries. Reactive.
+	pub fn model(&self) -> Option<GraphModel> {
+		self.data().map(GraphModel::new)
+	}
+
+	/// ID of the node under the pointer; setting it highlights that node.
+	pub fn hovered(&self) -> RwSignal<Option<String>> {
+		self.hovered
+	}
+
+	/// The canvas's pan and zoom; setting it moves the camera.
+	pub fn transform(&self) -> RwSignal<ViewTransform> {
+		self.transform
+	}
+
+	/// Zoom to the node with `id`. Returns whether a mounted canvas has it.
+	pub fn focus(&self, id: &str) -> bool {
+		self.with_context(|c| {
+			let target = c
+				.state
+				.node_index(id)
+				.and_then(|idx| c.state.focus_node_transform(idx));
+			let Some(target) = target else {
+				return false;
+			};
+			c.state.animate_to(target);
+			true
+		})
+		.unwrap_or(false)
+	}
+
+	/// Fit every visible node in the view.
+	pub fn fit_all(&self) {
+		self.with_context(|c| {
+			if let Some(target) = c.state.fit_all_transform() {
+				c.state.animate_to(target);
+			}
+		});
+	}
+
+	/// Graph position of every visible node by ID, e.g. for a minimap. A
+	/// snapshot: not reactive, so poll it or pair it with [`transform`](Self::transform).
+	pub fn positions(&self) -> Vec<(String, f64, f64)> {
+		self.with_context(|c| {
+			let mut positions = Vec::new();
+			c.state.graph.visit_nodes(|node| {
+				let info = &node.data.user_data;
+				if !info.hidden {
+					positions.push((info.id.clone(), node.x() as f64, node.y() as f64));
+				}
+			});
+			positions
+		})
+		.unwrap_or_default()
+	}
+
+	/// IDs of every node, hidden ones included, in the order
+	/// [`sample_positions`](Self::sample_positions) writes them. It changes only
+	/// when the data does; empty without a mounted canvas.
+	pub fn node_order(&self) -> Vec<String> {
+		self.with_context(|c| c.state.node_order())
+			.unwrap_or_default()
+	}
+
+	/// Overwrite `out` with every node's current graph position as interleaved
+	/// x, y pairs, in [`node_order`](Self::node_order). Reusing `out` keeps it
+	/// cheap enough to sample every frame, e.g. for companion views or tests.
+	/// Returns whether a canvas is mounted.
+	pub fn sample_positions(&self, out: &mut Vec<f32>) -> bool {
+		self.with_context(|c| c.state.write_positions(out))
+			.is_some()
+	}
+
+	/// Re-energize the layout without rebuilding it, e.g. after pinning,
+	/// filtering or patching data. `alpha` (0..1) sets how hard nodes are
+	/// nudged, at most 40 graph units.
+	pub fn reheat(&self, alpha: f64) {
+		self.with_context(|c| c.state.reheat(alpha));
+	}
+
+	/// Release every pinned node back to the layout. Undoable.
+	pub fn unpin_all(&self) {
+		self.with_context(|c| c.state.unpin_all());
+	}
+
+	/// Freeze or resume the layout; drawing, panning and zooming carry on.
+	pub fn set_paused(&self, paused: bool) {
+		self.with_context(|c| c.set_paused(paused));
+	}
+
+	/// Whether the layout is frozen.
+	pub fn is_paused(&self) -> bool {
+		self.with_context(|c| c.is_paused()).unwrap_or(false)
+	}
+
+	/// Draw `plugin`'s layers on the canvas, after any registered before it.
+	/// Returns whether a canvas is mounted.
+	pub fn add_plugin(&self, plugin: Rc<dyn RenderPlugin>) -> bool {
+		self.with_context(|c| c.add_plugin(plugin)).is_some()
+	}
+
+	/// Start recording pointer input on the canvas, from its current camera and
+	/// layout. Returns whether a canvas is mounted.
+	pub fn start_recording(&self) -> bool {
+		self.with_context(|c| c.recorder.start(&mut c.state))
+			.is_some()
+	}
+
+	/// Stop recording and return the session; `None` if nothing was recording.
+	pub fn stop_recording(&self) -> Option<Recording> {
+		self.with_context(|c| c.recorder.stop()).flatten()
+	}
+
+	/// Whether a recording is in progress.
+	pub fn is_recording(&self) -> bool {
+		self.with_context(|c| c.recorder.is_recording())
+			.unwrap_or(false)
+	}
+
+	/// Replay a recorded session on the canvas, from the camera and layout it
+	/// started with. Live pointer input is ignored meanwhile. Returns whether a
+	/// canvas is mounted.
+	pub fn replay(&self, recording: Recording) -> bool {
+		self.with_context(|c| c.recorder.replay(recording, &mut c.state))
+			.is_some()
+	}
+
+	/// Whether a replay is in progress.
+	pub fn is_replaying(&self) -> bool {
+		self.with_context(|c| c.recorder.is_replaying())
+			.unwrap_or(false)
+	}
+
+	/// Start capturing the canvas to video, e.g. before a [`replay`](Self::replay)
+	/// or while the layout settles. Fails without a mounted canvas or where the
+	/// browser can't record WebM.
+	pub fn start_capture(&self) -> Result<(), String> {
+		self.with_context(|c| c.clip.start())
+			.unwrap_or_else(|| Err("no graph canvas is mounted".to_string()))
+	}
+
+	/// Stop capturing and download the clip as `file_name` (e.g. "graph.webm").
+	/// Returns whether a capture was in progress.
+	pub fn stop_capture(&self, file_name: &str) -> bool {
+		self.with_context(|c| c.clip.stop(file_name))
+			.unwrap_or(false)
+	}
+
+	/// Whether a video capture is in progress.
+	pub fn is_capturing(&self) -> bool {
+		self.with_context(|c| c.clip.is_capturing())
+			.unwrap_or(false)
+	}
+
+	/// Render for print (light theme, every label, no animation, print
+	/// resolution) until switched off. Printing the page does this by itself;
+	/// this is for exporting or previewing the print look.
+	pub fn set_print_mode(&self, on: bool) {
+		self.with_context(|c| c.set_print_mode(on));
+	}
+
+	/// Run `f` on the attached canvas's context, if one is mounted.
+	fn with_context<R>(&self, f: impl FnOnce(&mut GraphContext) -> R) -> Option<R> {
+		let context = self.context.get_value()?;
+		let mut context = context.borrow_mut();
+		context.as_mut().map(f)
+	}
+}
+
+/// Provides a [`GraphHandle`] to its children: the canvas among them attaches
+/// to it, and the rest reach it with [`use_graph`].
+#[component]
+pub fn GraphProvider(children: Children) -> impl IntoView {
+	provide_context(GraphHandle::new());
+	children()
+}
+
+/// The handle of the enclosing [`GraphProvider`], if any.
+pub fn use_graph() -> Option<GraphHandle> {
+	use_context()
+}
+
+/// Attach a canvas's context to `handle` until the canvas unmounts.
+pub fn attach(handle: GraphHandle, context: SharedContext) {
+	handle.context.set_value(Some(context));
+	on_cleanup(move || {
+		handle.context.try_set_value(None);
+	});
+}
+
+/// Publish the data the canvas now shows.
+pub fn publish(handle: GraphHandle, data: &GraphData) {
+	handle.data.set(Some(data.clone()));
+}
@@ -0,0 +1,273 @@
+//! Record pointer input and replay it frame by frame.
+//!
+//! A [`Recording`] holds the camera and node positions at the start plus every
+//! canvas pointer event (press, move, release, double-click, leave, wheel),
+//! stamped with the animation frame it arrived in. The simulation advances a
+//! fixed step per frame, so replaying the events at the same frames from the
+//! same starting layout repeats the session: handy for demo sequences and for
+//! reproducing interaction bugs. Recordings serialize to JSON for saving.
+//!
+//! Node velocities are not recorded: recording and replay both stop the layout
+//! where it stands before the first frame, so a session started mid-motion
+//! replays from the same standstill rather than diverging.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{HtmlCanvasElement, MouseEvent, MouseEventInit, WheelEvent, WheelEventInit};
+
+use super::state::{ForceGraphState, ViewTransform};
+
+/// Kind of recorded pointer event, named after the DOM event it replays as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputKind {
+	/// `mousedown`
+	MouseDown,
+	/// `mousemove`
+	MouseMove,
+	/// `mouseup`
+	MouseUp,
+	/// `dblclick`
+	DblClick,
+	/// `mouseleave`
+	MouseLeave,
+	/// `wheel`
+	Wheel,
+}
+
+impl InputKind {
+	/// DOM event type.
+	fn event_type(self) -> &'static str {
+		match self {
+			InputKind::MouseDown => "mousedown",
+			InputKind::MouseMove => "mousemove",
+			InputKind::MouseUp => "mouseup",
+			InputKind::DblClick => "dblclick",
+			InputKind::MouseLeave => "mouseleave",
+			InputKind::Wheel => "wheel",
+		}
+	}
+}
+
+/// One recorded pointer event.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct RecordedInput {
+	/// Frames since the recording started.
+	pub frame: u64,
+	/// What happened.
+	pub kind: InputKind,
+	/// Pointer x relative to the canvas, in pixels.
+	pub x: f64,
+	/// Pointer y relative to the canvas, in pixels.
+	pub y: f64,
+	/// Button that changed (0 = primary, 1 = middle, 2 = secondary).
+	#[serde(default)]
+	pub button: i16,
+	/// Buttons held, as a bit mask.
+	#[serde(default)]
+	pub buttons: u16,
+	/// Shift held.
+	#[serde(default)]
+	pub shift: bool,
+	/// Alt held.
+	#[serde(default)]
+	pub alt: bool,
+	/// Ctrl held.
+	#[serde(default)]
+	pub ctrl: bool,
+	/// Meta held.
+	#[serde(default)]
+	pub meta: bool,
+	/// Horizontal wheel delta.
+	#[serde(default)]
+	pub delta_x: f64,
+	/// Vertical wheel delta.
+	#[serde(default)]
+	pub delta_y: f64,
+	/// Unit of the wheel deltas (0 = pixels, 1 = lines, 2 = pages).
+	#[serde(default)]
+	pub delta_mode: u32,
+}
+
+/// A recorded interaction session.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Recording {
+	/// Camera when recording started.
+	pub transform: ViewTransform,
+	/// Position of each node, by ID, when recording started.
+	pub positions: Vec<(String, f32, f32)>,
+	/// Pointer events in the order they arrived.
+	pub inputs: Vec<RecordedInput>,
+}
+
+/// What the recorder is doing.
+#[derive(Debug, Default)]
+enum Mode {
+	#[default]
+	Idle,
+	Recording(Recording),
+	Replaying(VecDeque<RecordedInput>),
+}
+
+/// Records pointer events as they arrive, or hands recorded ones back frame by
+/// frame for replay.
+#[derive(Debug, Default)]
+pub struct Recorder {
+	/// Animation frames seen so far
+	frame: u64,
+	/// Frame the current recording or replay started at
+	start: u64,
+	mode: Mode,
+}
+
+impl Recorder {
+	/// Start recording from the current camera and layout, stopped where it
+	/// stands, dropping any recording or replay in progress.
+	pub fn start(&mut self, state: &mut ForceGraphState) {
+		state.halt();
+		let mut positions = Vec::new();
+		state.graph.visit_nodes(|node| {
+			positions.push((node.data.user_data.id.clone(), node.x(), node.y()));
+		});
+		self.start = self.frame;
+		self.mode = Mode::Recording(Recording {
+			transform: state.transform.clone(),
+			positions,
+			inputs: Vec::new(),
+		});
+	}
+
+	/// Stop recording and return what was recorded; `None` if not recording.
+	pub fn stop(&mut self) -> Option<Recording> {
+		match std::mem::take(&mut self.mode) {
+			Mode::Recording(recording) => Some(recording),
+			mode => {
+				self.mode = mode;
+				None
+			}
+		}
+	}
+
+	/// Restore the recording's camera and layout, at a standstill, then replay
+	/// its events from the next frame on. Live pointer input is ignored until
+	/// the replay finishes.
+	pub fn replay(&mut self, recording: Recording, state: &mut ForceGraphState) {
+		state.mark_dirty();
+		state.wake();
+		state.camera = None;
+		state.transform = recording.transform;
+		for (id, x, y) in recording.positions {
+			if let Some(idx) = state.node_index(&id) {
+				state.graph.visit_nodes_mut(|node| {
+					if node.index() == idx {
+						node.data.x = x;
+						node.data.y = y;
+					}
+				});
+			}
+		}
+		state.halt();
+		self.start = self.frame;
+		self.mode = Mode::Replaying(recording.inputs.into());
+	}
+
+	/// Whether a recording is in progress.
+	pub fn is_recording(&self) -> bool {
+		matches!(self.mode, Mode::Recording(_))
+	}
+
+	/// Whether a replay is in progress.
+	pub fn is_replaying(&self) -> bool {
+		matches!(self.mode, Mode::Replaying(_))
+	}
+
+	/// Move on to the next frame, returning the recorded events due before it.
+	pub fn advance(&mut self) -> Vec<RecordedInput> {
+		self.frame += 1;
+		let Mode::Replaying(ref mut queue) = self.mode else {
+			return Vec::new();
+		};
+		let elapsed = self.frame - self.start;
+		let mut due = Vec::new();
+		while queue.front().is_some_and(|input| input.frame < elapsed) {
+			due.extend(queue.pop_front());
+		}
+		if queue.is_empty() {
+			self.mode = Mode::Idle;
+		}
+		due
+	}
+
+	/// Note a pointer event at canvas position (`x`, `y`). Returns whether the
+	/// canvas should handle it: live events are dropped during a replay.
+	pub fn capture(&mut self, kind: InputKind, x: f64, y: f64, ev: &MouseEvent) -> bool {
+		match self.mode {
+			Mode::Replaying(_) => !ev.is_trusted(),
+			Mode::Recording(ref mut recording) => {
+				let wheel = ev.dyn_ref::<WheelEvent>();
+				recording.inputs.push(RecordedInput {
+					frame: self.frame - self.start,
+					kind,
+					x,
+					y,
+					button: ev.button(),
+					buttons: ev.buttons(),
+					shift: ev.shift_key(),
+					alt: ev.alt_key(),
+					ctrl: ev.ctrl_key(),
+					meta: ev.meta_key(),
+					delta_x: wheel.map_or(0.0, WheelEvent::delta_x),
+					delta_y: wheel.map_or(0.0, WheelEvent::delta_y),
+					delta_mode: wheel.map_or(0, WheelEvent::delta_mode),
+				});
+				true
+			}
+			Mode::Idle => true,
+		}
+	}
+}
+
+/// Fire `input` at `canvas` as a synthetic DOM event, so it takes the same path
+/// through the canvas's handlers as the original.
+pub fn dispatch(canvas: &HtmlCanvasElement, input: &RecordedInput) {
+	let rect = canvas.get_bounding_client_rect();
+	let (client_x, client_y) = (
+		(input.x + rect.left()).round() as i32,
+		(input.y + rect.top()).round() as i32,
+	);
+	let kind = input.kind.event_type();
+	let event: Result<MouseEvent, JsValue> = if input.kind == InputKind::Wheel {
+		let init = WheelEventInit::new();
+		init.set_bubbles(true);
+		init.set_cancelable(true);
+		init.set_client_x(client_x);
+		init.set_client_y(client_y);
+		init.set_buttons(input.buttons);
+		init.set_shift_key(input.shift);
+		init.set_alt_key(input.alt);
+		init.set_ctrl_key(input.ctrl);
+		init.set_meta_key(input.meta);
+		init.set_delta_x(input.delta_x);
+		init.set_delta_y(input.delta_y);
+		init.set_delta_mode(input.delta_mode);
+		WheelEvent::new_with_event_init_dict(kind, &init).map(Into::into)
+	} else {
+		let init = MouseEventInit::new();
+		init.set_bubbles(input.kind != InputKind::MouseLeave);
+		init.set_cancelable(true);
+		init.set_client_x(client_x);
+		init.set_client_y(client_y);
+		init.set_button(input.button);
+		init.set_buttons(input.buttons);
+		init.set_shift_key(input.shift);
+		init.set_alt_key(input.alt);
+		init.set_ctrl_key(input.ctrl);
+		init.set_meta_key(input.meta);
+		MouseEvent::new_with_mouse_event_init_dict(kind, &init)
+	};
+	if let Ok(event) = event {
+		let _ = canvas.dispatch_event(&event);
+	}
+}
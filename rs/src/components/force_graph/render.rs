@@ -2,23 +2,190 @@
 //!
 //! Handles all drawing operations: background, edges, nodes, labels, and effects.
 //! Rendering uses multiple passes for correct z-ordering:
-//! 1. Background and particles (screen space)
+//! 1. Background and particles (screen space), then the grid, lanes and plugin
+//!    layers under the graph (world space)
 //! 2. Edge glows, then edge lines (world space)
-//! 3. Node glows, non-highlighted nodes, then highlighted nodes on top
+//! 3. Node glows, then each node layer in turn: non-highlighted nodes, then
+//!    highlighted nodes on top, then plugin layers over the graph
+//! 4. Interaction feedback and indicators (screen space)
+//!
+//! With stacked canvases (see [`Layers`]) each pass draws to a canvas of its
+//! own, and only passes whose canvas changed are repeated.
 
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::f64::consts::PI;
+use std::fmt::Write;
+use std::rc::Rc;
 
+use force_graph::DefaultNodeIdx;
 use wasm_bindgen::JsValue;
 use web_sys::CanvasRenderingContext2d;
 
+use super::hooks::{EdgeRenderInfo, NodeRenderInfo, RenderFrame, RenderHooks};
+use super::layers::Layers;
+use super::layout::TimeScale;
+#[cfg(feature = "particles")]
 use super::particles::ParticleSystem;
 use super::scale::{ScaleConfig, ScaledValues};
-use super::state::{ForceGraphState, NodeInfo};
-use super::theme::{Color, Theme};
+use super::state::{
+	EdgeInfo, ForceGraphState, INDICATOR_RADIUS, LinkDraft, NodeInfo, OffscreenIndicator,
+	badge_center,
+};
+use super::theme::{BackgroundPattern, Color, CssCache, Theme};
+
+/// Ease a highlight intensity with the configured curve, smoothing values that
+/// would otherwise cause abrupt visual changes.
+fn highlight_ease(state: &ForceGraphState, t: f64) -> f64 {
+	state.highlight.config.easing.apply(t)
+}
+
+/// Angular speed of data-driven node pulses (radians per second).
+const DATA_PULSE_SPEED: f64 = 4.0;
+
+/// Combined radius pulse for a node: the theme's global pulse plus its own data pulse.
+/// Data pulses only grow the node (never shrink it) so flagged nodes stay prominent.
+fn node_pulse(node: &force_graph::Node<NodeInfo>, theme_pulse: f64, time: f64) -> f64 {
+	let intensity = node.data.user_data.pulse;
+	if intensity <= 0.0 {
+		return theme_pulse;
+	}
+	theme_pulse + intensity * (0.5 + 0.5 * (time * DATA_PULSE_SPEED).sin())
+}
+
+/// Visits every node that isn't hidden.
+fn visit_visible_nodes(state: &ForceGraphState, mut cb: impl FnMut(&force_graph::Node<NodeInfo>)) {
+	// In 3D, far nodes go first so near ones cover them
+	if let Some(space) = &state.space {
+		space
+			.order
+			.iter()
+			.filter_map(|&idx| state.node(idx))
+			.for_each(cb);
+		return;
+	}
+	state.graph.visit_nodes(|node| {
+		if !node.data.user_data.hidden {
+			cb(node);
+		}
+	});
+}
+
+/// An edge is drawn only when both of its endpoints are visible and the data
+/// doesn't hide it (until hidden links are revealed).
+fn edge_visible(
+	state: &ForceGraphState,
+	n1: &force_graph::Node<NodeInfo>,
+	n2: &force_graph::Node<NodeInfo>,
+	edge: &EdgeInfo,
+) -> bool {
+	!n1.data.user_data.hidden
+		&& !n2.data.user_data.hidden
+		&& (!edge.hidden || state.show_hidden_links)
+}
+
+/// World-space position a node is drawn at this frame (see [`NodeInfo::display`]).
+fn display_pos(node: &force_graph::Node<NodeInfo>) -> (f64, f64) {
+	let display = &node.data.user_data.display;
+	(display.x, display.y)
+}
+
+/// Layers that change with time alone: twinkling particles, pulsing nodes,
+/// flowing dashes and traffic dots. Such layers are drawn even when
+/// [`ForceGraphState::redraw_layers`] says nothing changed.
+pub fn animated_layers(
+	state: &ForceGraphState,
+	config: &ScaleConfig,
+	theme: &Theme,
+	particles: bool,
+) -> Layers {
+	let mut layers = Layers {
+		background: particles,
+		..Layers::default()
+	};
+	layers.nodes = theme.node.pulse_intensity > 0.0;
+	if !layers.nodes {
+		visit_visible_nodes(state, |node| {
+			layers.nodes |= node.data.user_data.pulse > 0.0
+		});
+	}
+	let scale = ScaledValues::new(config, state.transform.k);
+	let dashes = scale.dash_pattern.1 * scale.dash_alpha > 0.1;
+	state.graph.visit_edges(|n1, n2, edge| {
+		layers.edges |= (dashes || (edge.user_data.traffic && !scale.cull_traffic))
+			&& edge_visible(state, n1, n2, &edge.user_data);
+	});
+	layers
+}
+
+/// Which edges a pass over them draws, and how.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EdgePass {
+	/// Every edge, highlighted or dimmed as hovering dictates
+	All,
+	/// Every edge, as if nothing were hovered (for the edge layer, which
+	/// hovering dims as a whole)
+	Resting,
+	/// Only highlighted edges (for the node layer, over the resting ones)
+	Highlighted,
+}
 
-/// Attempt to smooth values that would otherwise cause abrupt visual changes.
-fn smooth_step(t: f64) -> f64 {
-	t * t * (3.0 - 2.0 * t)
+/// Raw highlight intensities of an edge and of the most highlighted node, as
+/// `pass` draws them.
+fn edge_highlight(
+	state: &ForceGraphState,
+	pass: EdgePass,
+	n1: &force_graph::Node<NodeInfo>,
+	n2: &force_graph::Node<NodeInfo>,
+) -> (f64, f64) {
+	match pass {
+		EdgePass::Resting => (0.0, 0.0),
+		_ => (
+			state.highlight.edge_intensity(n1.index(), n2.index()),
+			state.highlight.max_intensity(),
+		),
+	}
+}
+
+/// Whether `pass` draws the edge between `n1` and `n2`.
+fn edge_in_pass(
+	state: &ForceGraphState,
+	pass: EdgePass,
+	n1: &force_graph::Node<NodeInfo>,
+	n2: &force_graph::Node<NodeInfo>,
+	edge: &EdgeInfo,
+) -> bool {
+	edge_visible(state, n1, n2, edge)
+		&& (pass != EdgePass::Highlighted
+			|| highlight_ease(
+				state,
+				state.highlight.edge_intensity(n1.index(), n2.index()),
+			) > 0.01)
+}
+
+/// Opacity of the edge layer, standing in for the dimming hovering gives
+/// edges drawn in one pass.
+pub fn edge_layer_opacity(state: &ForceGraphState) -> f64 {
+	1.0 - 0.5 / 0.7 * highlight_ease(state, state.highlight.max_intensity())
+}
+
+thread_local! {
+	/// CSS strings for the colors drawn recently, so frames don't format them anew.
+	static CSS: CssCache = CssCache::default();
+	/// Scratch buffer for text drawn every frame.
+	static TEXT: RefCell<String> = const { RefCell::new(String::new()) };
+	/// Empty dash list for solid lines.
+	static NO_DASH: js_sys::Array = js_sys::Array::new();
+}
+
+/// Interned CSS string for `color`.
+fn css(color: Color) -> Rc<str> {
+	CSS.with(|cache| cache.get(color))
+}
+
+/// Number of CSS strings currently interned by the renderer.
+pub fn css_cache_entries() -> usize {
+	CSS.with(CssCache::entries)
 }
 
 /// Renders the complete graph to the canvas.
@@ -27,30 +194,228 @@ pub fn render(
 	ctx: &CanvasRenderingContext2d,
 	config: &ScaleConfig,
 	theme: &Theme,
-	particles: Option<&ParticleSystem>,
+	hooks: &RenderHooks,
+	#[cfg(feature = "particles")] particles: Option<&ParticleSystem>,
 ) {
-	let scale = ScaledValues::new(config, state.transform.k);
+	let r = state.pixel_ratio;
+	let _ = ctx.set_transform(r, 0.0, 0.0, r, 0.0, 0.0);
+	draw_background_layer(
+		state,
+		ctx,
+		theme,
+		hooks,
+		#[cfg(feature = "particles")]
+		particles,
+	);
+	draw_edge_layer(state, ctx, config, theme, hooks, EdgePass::All);
+	draw_node_layer(state, ctx, config, theme, hooks, false);
+	draw_overlay_layer(state, ctx, theme);
+}
 
+/// Renders `layers` of the graph onto stacked canvases: `ctxs` holds the
+/// background, edge, node and overlay contexts, bottom to top. The other
+/// canvases keep what they show.
+#[allow(clippy::too_many_arguments)]
+pub fn render_layers(
+	state: &ForceGraphState,
+	ctxs: [&CanvasRenderingContext2d; 4],
+	layers: Layers,
+	config: &ScaleConfig,
+	theme: &Theme,
+	hooks: &RenderHooks,
+	#[cfg(feature = "particles")] particles: Option<&ParticleSystem>,
+) {
+	let r = state.pixel_ratio;
+	let [background, edges, nodes, overlay] = ctxs;
+	let clear = |ctx: &CanvasRenderingContext2d| {
+		let _ = ctx.set_transform(r, 0.0, 0.0, r, 0.0, 0.0);
+		ctx.clear_rect(0.0, 0.0, state.width, state.height);
+	};
+	if layers.background {
+		clear(background);
+		draw_background_layer(
+			state,
+			background,
+			theme,
+			hooks,
+			#[cfg(feature = "particles")]
+			particles,
+		);
+	}
+	if layers.edges {
+		clear(edges);
+		draw_edge_layer(state, edges, config, theme, hooks, EdgePass::Resting);
+	}
+	if layers.nodes {
+		clear(nodes);
+		draw_node_layer(state, nodes, config, theme, hooks, true);
+	}
+	if layers.overlay {
+		clear(overlay);
+		draw_overlay_layer(state, overlay, theme);
+	}
+}
+
+/// Position lookup for render plugins: where the visible node with an ID is
+/// drawn.
+fn plugin_position(state: &ForceGraphState) -> impl Fn(&str) -> Option<(f64, f64)> + '_ {
+	|id: &str| {
+		let node = state.node(state.node_index(id)?)?;
+		(!node.data.user_data.hidden).then(|| display_pos(node))
+	}
+}
+
+/// Screen-space background, then the world-space grid, lanes and plugin
+/// underlays.
+fn draw_background_layer(
+	state: &ForceGraphState,
+	ctx: &CanvasRenderingContext2d,
+	theme: &Theme,
+	hooks: &RenderHooks,
+	#[cfg(feature = "particles")] particles: Option<&ParticleSystem>,
+) {
 	draw_background(state, ctx, theme);
+	if theme.background.pattern != BackgroundPattern::None {
+		draw_pattern(state, ctx, theme);
+	}
 
+	#[cfg(feature = "particles")]
 	if let Some(ps) = particles {
 		draw_particles(state, ctx, theme, ps);
 	}
 
+	if let Some(time_scale) = &state.time_scale {
+		draw_time_axis(state, ctx, theme, time_scale);
+	}
+
 	ctx.save();
 	let _ = ctx.translate(state.transform.x, state.transform.y);
 	let _ = ctx.scale(state.transform.k, state.transform.k);
 
-	draw_edges(state, ctx, config, &scale, theme);
-	draw_nodes(state, ctx, config, &scale, theme);
+	if let Some(spacing) = state.grid {
+		draw_grid(state, ctx, theme, spacing);
+	}
+	if !state.lanes.is_empty() {
+		draw_lanes(state, ctx, theme);
+	}
+	if !hooks.plugins.is_empty() {
+		let position = plugin_position(state);
+		let frame = plugin_frame(state, &position);
+		for plugin in &hooks.plugins {
+			ctx.save();
+			plugin.before_edges(ctx, &frame);
+			ctx.restore();
+		}
+	}
 
 	ctx.restore();
+}
+
+/// World-space edges, drawn as `pass` says.
+fn draw_edge_layer(
+	state: &ForceGraphState,
+	ctx: &CanvasRenderingContext2d,
+	config: &ScaleConfig,
+	theme: &Theme,
+	hooks: &RenderHooks,
+	pass: EdgePass,
+) {
+	let scale = ScaledValues::new(config, state.transform.k);
+	ctx.save();
+	let _ = ctx.translate(state.transform.x, state.transform.y);
+	let _ = ctx.scale(state.transform.k, state.transform.k);
+	draw_edges(state, ctx, config, &scale, theme, hooks, pass);
+	ctx.restore();
+}
+
+/// World-space nodes and labels, then plugin overlays. With
+/// `highlighted_edges`, highlighted edges are drawn first, over the resting
+/// ones on the edge layer.
+fn draw_node_layer(
+	state: &ForceGraphState,
+	ctx: &CanvasRenderingContext2d,
+	config: &ScaleConfig,
+	theme: &Theme,
+	hooks: &RenderHooks,
+	highlighted_edges: bool,
+) {
+	let scale = ScaledValues::new(config, state.transform.k);
+	ctx.save();
+	let _ = ctx.translate(state.transform.x, state.transform.y);
+	let _ = ctx.scale(state.transform.k, state.transform.k);
+
+	if highlighted_edges && state.highlight.max_intensity() > 0.0 {
+		draw_edges(
+			state,
+			ctx,
+			config,
+			&scale,
+			theme,
+			hooks,
+			EdgePass::Highlighted,
+		);
+	}
+	draw_nodes(state, ctx, config, &scale, theme, hooks);
+	if !hooks.plugins.is_empty() {
+		let position = plugin_position(state);
+		let frame = plugin_frame(state, &position);
+		for plugin in &hooks.plugins {
+			ctx.save();
+			plugin.after_nodes(ctx, &frame);
+			ctx.restore();
+		}
+	}
+
+	ctx.restore();
+}
+
+/// Screen-space interaction feedback, indicators and the vignette.
+fn draw_overlay_layer(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+	if !state.lanes.is_empty() {
+		draw_lane_titles(state, ctx, theme);
+	}
+
+	if state.fisheye.is_active() {
+		draw_fisheye_outline(state, ctx);
+	}
+
+	if state.box_zoom.active {
+		draw_box_zoom(state, ctx);
+	}
+
+	if state.lasso.active {
+		draw_lasso(state, ctx);
+	}
+
+	if let Some(draft) = &state.link_draft {
+		draw_link_draft(state, ctx, draft);
+	}
+
+	let indicators = state.offscreen_indicators();
+	if !indicators.is_empty() {
+		draw_offscreen_indicators(state, ctx, theme, indicators);
+	}
 
 	if theme.background.vignette > 0.0 {
 		draw_vignette(state, ctx, theme);
 	}
 }
 
+/// The frame as render plugins see it.
+fn plugin_frame<'a>(
+	state: &ForceGraphState,
+	position: &'a dyn Fn(&str) -> Option<(f64, f64)>,
+) -> RenderFrame<'a> {
+	let (x0, y0) = state.screen_to_graph(0.0, 0.0);
+	let (x1, y1) = state.screen_to_graph(state.width, state.height);
+	RenderFrame {
+		zoom: state.transform.k,
+		time: state.flow_time,
+		view: (x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)),
+		position,
+	}
+}
+
 fn draw_background(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, theme: &Theme) {
 	if theme.background.use_gradient {
 		let gradient = ctx
@@ -65,21 +430,281 @@ fn draw_background(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, them
 			.unwrap();
 
 		gradient
-			.add_color_stop(0.0, &theme.background.color_secondary.to_css())
+			.add_color_stop(0.0, &css(theme.background.color_secondary))
 			.unwrap();
 		gradient
-			.add_color_stop(1.0, &theme.background.color.to_css())
+			.add_color_stop(1.0, &css(theme.background.color))
 			.unwrap();
 
 		#[allow(deprecated)]
 		ctx.set_fill_style(&gradient);
 	} else {
-		ctx.set_fill_style_str(&theme.background.color.to_css());
+		ctx.set_fill_style_str(&css(theme.background.color));
 	}
 
 	ctx.fill_rect(0.0, 0.0, state.width, state.height);
 }
 
+/// Draws the background pattern in screen space. It follows pan and zoom only by
+/// the theme's parallax fraction, so it reads as a distant layer that still shows
+/// movement when the view holds nothing but empty space.
+fn draw_pattern(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+	let style = &theme.background;
+	let t = &state.transform;
+	let zoom = t.k.powf(style.pattern_parallax);
+	let (ox, oy) = (t.x * style.pattern_parallax, t.y * style.pattern_parallax);
+	let (w, h) = (state.width, state.height);
+
+	ctx.begin_path();
+	match style.pattern {
+		BackgroundPattern::None => {}
+		BackgroundPattern::Dots { spacing, radius } => {
+			let step = spacing * zoom;
+			if step < 4.0 {
+				return;
+			}
+			let mut x = ox.rem_euclid(step);
+			while x < w {
+				let mut y = oy.rem_euclid(step);
+				while y < h {
+					ctx.rect(x - radius, y - radius, radius * 2.0, radius * 2.0);
+					y += step;
+				}
+				x += step;
+			}
+			ctx.set_fill_style_str(&css(style.pattern_color));
+			ctx.fill();
+		}
+		BackgroundPattern::Lines { spacing } => {
+			let step = spacing * zoom;
+			if step < 4.0 {
+				return;
+			}
+			let mut x = ox.rem_euclid(step);
+			while x < w {
+				ctx.move_to(x, 0.0);
+				ctx.line_to(x, h);
+				x += step;
+			}
+			let mut y = oy.rem_euclid(step);
+			while y < h {
+				ctx.move_to(0.0, y);
+				ctx.line_to(w, y);
+				y += step;
+			}
+			ctx.set_stroke_style_str(&css(style.pattern_color));
+			ctx.set_line_width(1.0);
+			ctx.stroke();
+		}
+		BackgroundPattern::Starfield { density } => {
+			draw_starfield(ctx, style.pattern_color, density, zoom, ox, oy, w, h)
+		}
+	}
+}
+
+/// Size of a starfield tile in pixels at 100% zoom.
+const STAR_TILE: f64 = 256.0;
+
+/// Scatters stars over the screen, tile by tile. Each tile's stars are derived
+/// from its coordinates, so the field stays put as it scrolls.
+#[allow(clippy::too_many_arguments)]
+fn draw_starfield(
+	ctx: &CanvasRenderingContext2d,
+	color: Color,
+	density: u32,
+	zoom: f64,
+	ox: f64,
+	oy: f64,
+	w: f64,
+	h: f64,
+) {
+	let tile = STAR_TILE * zoom;
+	if tile < 16.0 {
+		return;
+	}
+	let (i0, j0) = ((-ox / tile).floor() as i64, (-oy / tile).floor() as i64);
+	let (i1, j1) = (
+		((w - ox) / tile).ceil() as i64,
+		((h - oy) / tile).ceil() as i64,
+	);
+	// Two brightness tiers keep it to two fills per frame
+	for (tier, alpha) in [(0, 0.4), (1, 1.0)] {
+		ctx.begin_path();
+		for i in i0..i1 {
+			for j in j0..j1 {
+				let mut seed = (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (j as u64);
+				for _ in 0..density {
+					let (fx, fy, b) = (hash01(&mut seed), hash01(&mut seed), hash01(&mut seed));
+					if (b > 0.85) as usize != tier {
+						continue;
+					}
+					let r = if tier == 1 { 1.2 } else { 0.7 };
+					let (x, y) = (ox + (i as f64 + fx) * tile, oy + (j as f64 + fy) * tile);
+					ctx.rect(x - r / 2.0, y - r / 2.0, r, r);
+				}
+			}
+		}
+		ctx.set_fill_style_str(&css(color.with_alpha(color.a * alpha)));
+		ctx.fill();
+	}
+}
+
+/// Next pseudo-random number in 0..1 from a splitmix64 sequence.
+fn hash01(seed: &mut u64) -> f64 {
+	*seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+	let mut z = *seed;
+	z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	(z ^ (z >> 31)) as f64 / u64::MAX as f64
+}
+
+/// Draws the snap grid across the visible area (world coordinates).
+/// Lines are thinned out when zoomed far enough that they would crowd together.
+fn draw_grid(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, theme: &Theme, spacing: f64) {
+	const MIN_SCREEN_SPACING: f64 = 8.0;
+	if spacing <= 0.0 {
+		return;
+	}
+	let k = state.transform.k;
+	let mut step = spacing;
+	while step * k < MIN_SCREEN_SPACING {
+		step *= 2.0;
+	}
+	let (x0, y0) = state.screen_to_graph(0.0, 0.0);
+	let (x1, y1) = state.screen_to_graph(state.width, state.height);
+
+	ctx.begin_path();
+	let mut x = (x0 / step).floor() * step;
+	while x <= x1 {
+		ctx.move_to(x, y0);
+		ctx.line_to(x, y1);
+		x += step;
+	}
+	let mut y = (y0 / step).floor() * step;
+	while y <= y1 {
+		ctx.move_to(x0, y);
+		ctx.line_to(x1, y);
+		y += step;
+	}
+	ctx.set_stroke_style_str(&css(theme.background.grid_color));
+	ctx.set_line_width(1.0 / k);
+	ctx.stroke();
+}
+
+/// Draws the timeline layout's time axis along the bottom of the screen, beneath
+/// the graph, with calendar-aligned tick labels.
+fn draw_time_axis(
+	state: &ForceGraphState,
+	ctx: &CanvasRenderingContext2d,
+	theme: &Theme,
+	time_scale: &TimeScale,
+) {
+	const BOTTOM: f64 = 36.0;
+	const TICK: f64 = 5.0;
+	const MIN_TICK_GAP: f64 = 90.0;
+	let t = &state.transform;
+	let y = state.height - BOTTOM;
+	let to_screen = |x: f64| x * t.k + t.x;
+	let left = to_screen(time_scale.left).max(0.0);
+	let right = to_screen(time_scale.right).min(state.width);
+	if left > right {
+		return;
+	}
+	let color = theme.node.label_color;
+	ctx.save();
+	ctx.begin_path();
+	ctx.move_to(left, y);
+	ctx.line_to(right, y);
+	let from = time_scale.time((left - t.x) / t.k);
+	let to = time_scale.time((right - t.x) / t.k);
+	let ticks = time_scale.ticks(from, to, MIN_TICK_GAP / t.k);
+	for (time, _) in &ticks {
+		let x = to_screen(time_scale.x(*time));
+		ctx.move_to(x, y);
+		ctx.line_to(x, y + TICK);
+	}
+	ctx.set_stroke_style_str(&css(color.with_alpha(0.35)));
+	ctx.set_line_width(1.0);
+	ctx.stroke();
+
+	ctx.set_font("11px sans-serif");
+	ctx.set_text_align("center");
+	ctx.set_text_baseline("top");
+	ctx.set_fill_style_str(&css(color.with_alpha(0.55)));
+	for (time, label) in &ticks {
+		let _ = ctx.fill_text(label, to_screen(time_scale.x(*time)), y + TICK + 3.0);
+	}
+	ctx.restore();
+}
+
+/// Shades every other swimlane across the visible height, in world space.
+fn draw_lanes(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+	let (_, y0) = state.screen_to_graph(0.0, 0.0);
+	let (_, y1) = state.screen_to_graph(state.width, state.height);
+	ctx.set_fill_style_str(&css(theme.background.grid_color));
+	for lane in state.lanes.iter().skip(1).step_by(2) {
+		ctx.fill_rect(lane.min, y0, lane.max - lane.min, y1 - y0);
+	}
+}
+
+/// Writes each swimlane's title along the top of the screen.
+fn draw_lane_titles(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+	const TOP: f64 = 20.0;
+	ctx.save();
+	ctx.set_font("600 12px sans-serif");
+	ctx.set_text_align("center");
+	ctx.set_text_baseline("middle");
+	ctx.set_fill_style_str(&css(theme.node.label_color.with_alpha(0.55)));
+	for lane in &state.lanes {
+		let t = &state.transform;
+		let (left, right) = (lane.min * t.k + t.x, lane.max * t.k + t.x);
+		if right < 0.0 || left > state.width {
+			continue;
+		}
+		let _ = ctx.fill_text_with_max_width(&lane.title, (left + right) / 2.0, TOP, right - left);
+	}
+	ctx.restore();
+}
+
+/// Draws arrow chips at the screen edge pointing toward off-screen nodes, each
+/// filled with its node's color.
+fn draw_offscreen_indicators(
+	state: &ForceGraphState,
+	ctx: &CanvasRenderingContext2d,
+	theme: &Theme,
+	indicators: &[OffscreenIndicator],
+) {
+	let ring = css(theme.selection.ring_color);
+	for chip in indicators {
+		let Some(node) = state.node(chip.node) else {
+			continue;
+		};
+		let selected = state.selection.contains(chip.node);
+		let (cos, sin) = (chip.angle.cos(), chip.angle.sin());
+		let r = INDICATOR_RADIUS;
+
+		// Arrowhead just outside the chip, pointing at the node
+		ctx.begin_path();
+		ctx.move_to(chip.x + cos * (r + 6.0), chip.y + sin * (r + 6.0));
+		ctx.line_to(chip.x + cos * r - sin * 5.0, chip.y + sin * r + cos * 5.0);
+		ctx.line_to(chip.x + cos * r + sin * 5.0, chip.y + sin * r - cos * 5.0);
+		ctx.close_path();
+		ctx.set_fill_style_str(&css(node.data.user_data.fill));
+		ctx.fill();
+
+		ctx.begin_path();
+		let _ = ctx.arc(chip.x, chip.y, r, 0.0, 2.0 * PI);
+		ctx.fill();
+		if selected {
+			ctx.set_stroke_style_str(&ring);
+		} else {
+			ctx.set_stroke_style_str("rgba(255, 255, 255, 0.6)");
+		}
+		ctx.set_line_width(1.5);
+		ctx.stroke();
+	}
+}
+
 fn draw_vignette(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, theme: &Theme) {
 	let gradient = ctx
 		.create_radial_gradient(
@@ -94,10 +719,7 @@ fn draw_vignette(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, theme:
 
 	gradient.add_color_stop(0.0, "rgba(0, 0, 0, 0)").unwrap();
 	gradient
-		.add_color_stop(
-			1.0,
-			&format!("rgba(0, 0, 0, {})", theme.background.vignette),
-		)
+		.add_color_stop(1.0, &css(Color::rgba(0, 0, 0, theme.background.vignette)))
 		.unwrap();
 
 	#[allow(deprecated)]
@@ -105,6 +727,77 @@ fn draw_vignette(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, theme:
 	ctx.fill_rect(0.0, 0.0, state.width, state.height);
 }
 
+/// Rubber-band rectangle for an in-progress box zoom (screen space).
+fn draw_box_zoom(state: &ForceGraphState, ctx: &CanvasRenderingContext2d) {
+	let (left, top, w, h) = state.box_zoom.rect();
+	ctx.set_fill_style_str("rgba(140, 180, 255, 0.08)");
+	ctx.fill_rect(left, top, w, h);
+	let _ = ctx.set_line_dash(&js_sys::Array::of2(
+		&JsValue::from_f64(4.0),
+		&JsValue::from_f64(3.0),
+	));
+	ctx.set_stroke_style_str("rgba(140, 180, 255, 0.7)");
+	ctx.set_line_width(1.0);
+	ctx.stroke_rect(left, top, w, h);
+	let _ = NO_DASH.with(|dash| ctx.set_line_dash(dash));
+}
+
+/// Freeform lasso path for an in-progress selection (screen space).
+fn draw_lasso(state: &ForceGraphState, ctx: &CanvasRenderingContext2d) {
+	let mut points = state.lasso.points.iter();
+	let Some(&(x0, y0)) = points.next() else {
+		return;
+	};
+	ctx.begin_path();
+	ctx.move_to(x0, y0);
+	for &(x, y) in points {
+		ctx.line_to(x, y);
+	}
+	ctx.close_path();
+	ctx.set_fill_style_str("rgba(140, 180, 255, 0.08)");
+	ctx.fill();
+	let _ = ctx.set_line_dash(&js_sys::Array::of2(
+		&JsValue::from_f64(4.0),
+		&JsValue::from_f64(3.0),
+	));
+	ctx.set_stroke_style_str("rgba(140, 180, 255, 0.7)");
+	ctx.set_line_width(1.0);
+	ctx.stroke();
+	let _ = NO_DASH.with(|dash| ctx.set_line_dash(dash));
+}
+
+/// Rubber-band line from a node to the pointer while dragging out a new link (screen space).
+fn draw_link_draft(state: &ForceGraphState, ctx: &CanvasRenderingContext2d, draft: &LinkDraft) {
+	let Some(node) = state.node(draft.source) else {
+		return;
+	};
+	let (t, d) = (&state.transform, &node.data.user_data.display);
+	ctx.begin_path();
+	ctx.move_to(d.x * t.k + t.x, d.y * t.k + t.y);
+	ctx.line_to(draft.x, draft.y);
+	let _ = ctx.set_line_dash(&js_sys::Array::of2(
+		&JsValue::from_f64(4.0),
+		&JsValue::from_f64(3.0),
+	));
+	ctx.set_stroke_style_str("rgba(140, 180, 255, 0.8)");
+	ctx.set_line_width(1.5);
+	ctx.stroke();
+	let _ = NO_DASH.with(|dash| ctx.set_line_dash(dash));
+}
+
+/// Faint circle marking the fisheye lens boundary (screen space).
+fn draw_fisheye_outline(state: &ForceGraphState, ctx: &CanvasRenderingContext2d) {
+	let Some((fx, fy)) = state.fisheye.focus else {
+		return;
+	};
+	ctx.begin_path();
+	let _ = ctx.arc(fx, fy, state.fisheye.radius, 0.0, 2.0 * PI);
+	ctx.set_stroke_style_str("rgba(255, 255, 255, 0.12)");
+	ctx.set_line_width(1.0);
+	ctx.stroke();
+}
+
+#[cfg(feature = "particles")]
 fn draw_particles(
 	state: &ForceGraphState,
 	ctx: &CanvasRenderingContext2d,
@@ -115,10 +808,7 @@ fn draw_particles(
 
 	for p in &particles.particles {
 		let alpha = particles.twinkle_alpha(p, state.flow_time);
-		ctx.set_fill_style_str(&format!(
-			"rgba({}, {}, {}, {})",
-			color.r, color.g, color.b, alpha
-		));
+		ctx.set_fill_style_str(&css(color.with_alpha(alpha)));
 
 		ctx.begin_path();
 		let _ = ctx.arc(p.x, p.y, p.size, 0.0, PI * 2.0);
@@ -132,45 +822,254 @@ fn draw_edges(
 	config: &ScaleConfig,
 	scale: &ScaledValues,
 	theme: &Theme,
+	hooks: &RenderHooks,
+	pass: EdgePass,
 ) {
-	let dash_offset = scale.dash_offset(state.flow_time, config.edge.flow_speed);
-	let k = scale.k;
-
+	#[cfg(feature = "glow")]
 	if theme.edge.glow_intensity > 0.0 {
-		state.graph.visit_edges(|n1, n2, _| {
-			draw_edge_glow(state, ctx, scale, theme, n1, n2);
+		state.graph.visit_edges(|n1, n2, edge| {
+			if !edge_in_pass(state, pass, n1, n2, &edge.user_data) {
+				return;
+			}
+			ctx.set_global_alpha(edge_alpha(state, n1, n2));
+			draw_edge_glow(state, ctx, scale, theme, pass, n1, n2, &edge.user_data);
+		});
+		ctx.set_global_alpha(1.0);
+	}
+
+	state.graph.visit_edges(|n1, n2, edge| {
+		if !edge_in_pass(state, pass, n1, n2, &edge.user_data) {
+			return;
+		}
+		let alpha = edge_alpha(state, n1, n2);
+		ctx.set_global_alpha(alpha);
+		let e = &edge.user_data;
+		let draw_default = || draw_edge_main(state, ctx, config, scale, theme, pass, n1, n2, e);
+		let Some(hook) = &hooks.edge else {
+			draw_default();
+			return;
+		};
+		let ((x1, y1), (x2, y2)) = (display_pos(n1), display_pos(n2));
+		let (dx, dy) = (x2 - x1, y2 - y1);
+		let dist = (dx * dx + dy * dy).sqrt();
+		let control = edge_tension(state, theme, scale, e, dist)
+			.filter(|_| dist > 0.001)
+			.map(|tension| curve_control_point(x1, y1, x2, y2, dx / dist, dy / dist, tension));
+		ctx.save();
+		hook(
+			ctx,
+			&EdgeRenderInfo {
+				source: &n1.data.user_data.spec,
+				target: &n2.data.user_data.spec,
+				from: (x1, y1),
+				to: (x2, y2),
+				control,
+				kind: e.kind.as_deref(),
+				weight: e.weight,
+				count: e.count,
+				alpha,
+				highlight: edge_highlight(state, pass, n1, n2).0,
+				zoom: scale.k,
+				time: state.flow_time,
+				draw_default: &draw_default,
+			},
+		);
+		ctx.restore();
+	});
+	ctx.set_global_alpha(1.0);
+
+	let _ = NO_DASH.with(|dash| ctx.set_line_dash(dash));
+	if pass == EdgePass::Highlighted {
+		return;
+	}
+
+	if state.show_labels {
+		state.graph.visit_edges(|n1, n2, edge| {
+			if edge.user_data.count > 1 && edge_visible(state, n1, n2, &edge.user_data) {
+				ctx.set_global_alpha(edge_alpha(state, n1, n2));
+				draw_edge_count(state, ctx, scale, theme, n1, n2, &edge.user_data);
+			}
 		});
+		ctx.set_global_alpha(1.0);
+	}
+
+	if !scale.cull_traffic {
+		state.graph.visit_edges(|n1, n2, edge| {
+			if edge.user_data.traffic && edge_visible(state, n1, n2, &edge.user_data) {
+				ctx.set_global_alpha(edge_alpha(state, n1, n2));
+				draw_edge_traffic(state, ctx, config, scale, theme, n1, n2, &edge.user_data);
+			}
+		});
+		ctx.set_global_alpha(1.0);
+	}
+}
+
+/// Draws the "×N" count of an aggregated edge at the middle of its path.
+fn draw_edge_count(
+	state: &ForceGraphState,
+	ctx: &CanvasRenderingContext2d,
+	scale: &ScaledValues,
+	theme: &Theme,
+	n1: &force_graph::Node<NodeInfo>,
+	n2: &force_graph::Node<NodeInfo>,
+	edge: &EdgeInfo,
+) {
+	let ((x1, y1), (x2, y2)) = (display_pos(n1), display_pos(n2));
+	let (dx, dy) = (x2 - x1, y2 - y1);
+	let dist = (dx * dx + dy * dy).sqrt();
+	let (mut x, mut y) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+	if let Some(tension) = edge_tension(state, theme, scale, edge, dist) {
+		// A quadratic curve passes halfway between the chord midpoint and its control point
+		let (cx, cy) = curve_control_point(x1, y1, x2, y2, dx / dist, dy / dist, tension);
+		(x, y) = ((x + cx) / 2.0, (y + cy) / 2.0);
 	}
 
-	state.graph.visit_edges(|n1, n2, _| {
-		draw_edge_main(state, ctx, config, scale, theme, n1, n2, dash_offset, k);
+	ctx.set_font(&scale.label_font);
+	ctx.set_text_align("center");
+	ctx.set_text_baseline("middle");
+	ctx.set_fill_style_str("rgba(255, 255, 255, 0.85)");
+	TEXT.with_borrow_mut(|text| {
+		text.clear();
+		let _ = write!(text, "\u{d7}{}", edge.count);
+		let _ = ctx.fill_text(text, x, y);
 	});
+	ctx.set_text_align("start");
+	ctx.set_text_baseline("alphabetic");
+}
 
-	let _ = ctx.set_line_dash(&js_sys::Array::new());
+/// Draws dots traveling from source to target (or back, for reversed edges), following the
+/// same path as the edge line. Heavier edges move dots faster and pack them closer together.
+#[allow(clippy::too_many_arguments)]
+fn draw_edge_traffic(
+	state: &ForceGraphState,
+	ctx: &CanvasRenderingContext2d,
+	config: &ScaleConfig,
+	scale: &ScaledValues,
+	theme: &Theme,
+	n1: &force_graph::Node<NodeInfo>,
+	n2: &force_graph::Node<NodeInfo>,
+	edge: &EdgeInfo,
+) {
+	if edge.weight <= 0.0 {
+		return;
+	}
+	let ((x1, y1), (x2, y2)) = (display_pos(n1), display_pos(n2));
+	let (dx, dy) = (x2 - x1, y2 - y1);
+	let dist = (dx * dx + dy * dy).sqrt();
+	let offset = scale.node_radius + scale.arrow_size;
+	if dist < offset * 2.0 {
+		return;
+	}
+	let (ux, uy) = (dx / dist, dy / dist);
+	let (sx, sy) = (x1 + ux * scale.node_radius, y1 + uy * scale.node_radius);
+	let (ex, ey) = (x2 - ux * offset, y2 - uy * offset);
+	let control = edge_tension(state, theme, scale, edge, dist)
+		.map(|tension| curve_control_point(x1, y1, x2, y2, ux, uy, tension));
+
+	let speed = config.edge.traffic_speed * edge.weight.sqrt();
+	let spacing = config.edge.traffic_spacing / edge.weight.max(0.25);
+	let path_len = dist - scale.node_radius - offset;
+	let count = (path_len / spacing).ceil().max(1.0) as usize;
+	let head = (state.flow_time * speed / path_len).fract();
+
+	let edge_t = highlight_ease(
+		state,
+		state.highlight.edge_intensity(n1.index(), n2.index()),
+	);
+	let max_t = highlight_ease(state, state.highlight.max_intensity());
+	let alpha = if edge_t > 0.01 {
+		0.9
+	} else {
+		0.9 - 0.6 * max_t
+	};
+	let color = theme.edge.color.lighten(0.4);
+	ctx.set_fill_style_str(&css(color.with_alpha(alpha)));
+
+	for i in 0..count {
+		let t = (head + i as f64 / count as f64).fract();
+		let t = if edge.flow_reverse { 1.0 - t } else { t };
+		let (px, py) = match control {
+			Some((cx, cy)) => {
+				let u = 1.0 - t;
+				(
+					u * u * sx + 2.0 * u * t * cx + t * t * ex,
+					u * u * sy + 2.0 * u * t * cy + t * t * ey,
+				)
+			}
+			None => (sx + (ex - sx) * t, sy + (ey - sy) * t),
+		};
+		ctx.begin_path();
+		let _ = ctx.arc(px, py, scale.traffic_dot_radius, 0.0, 2.0 * PI);
+		ctx.fill();
+	}
 }
 
+/// An edge is only as prominent as the dimmer of its endpoints under focus mode,
+/// further faded by both endpoints' data-supplied opacity.
+fn edge_alpha(
+	state: &ForceGraphState,
+	n1: &force_graph::Node<NodeInfo>,
+	n2: &force_graph::Node<NodeInfo>,
+) -> f64 {
+	state
+		.focus_alpha(n1.index())
+		.min(state.focus_alpha(n2.index()))
+		* n1.data.user_data.opacity
+		* n2.data.user_data.opacity
+}
+
+/// Opacity of a node from the focus dimming and its data-supplied `opacity`.
+fn node_alpha(state: &ForceGraphState, node: &force_graph::Node<NodeInfo>) -> f64 {
+	state.focus_alpha(node.index()) * node.data.user_data.opacity
+}
+
+/// Curve tension of edges in the timeline layout, so links between nodes on the
+/// same stretch of the axis arc instead of overlapping.
+const TIMELINE_ARC_TENSION: f64 = 0.3;
+
+/// Curve tension for an edge of length `dist`, or `None` if it should be drawn straight.
+/// A per-edge curvature overrides the layout and theme; very short edges are always straight.
+fn edge_tension(
+	state: &ForceGraphState,
+	theme: &Theme,
+	scale: &ScaledValues,
+	edge: &EdgeInfo,
+	dist: f64,
+) -> Option<f64> {
+	let tension = match edge.curvature {
+		Some(c) => c,
+		None if state.time_scale.is_some() => TIMELINE_ARC_TENSION,
+		None if theme.edge.curved => theme.edge.curve_tension,
+		None => return None,
+	};
+	(tension != 0.0 && dist > scale.node_radius * 4.0).then_some(tension)
+}
+
+#[cfg(feature = "glow")]
+#[allow(clippy::too_many_arguments)]
 fn draw_edge_glow(
 	state: &ForceGraphState,
 	ctx: &CanvasRenderingContext2d,
 	scale: &ScaledValues,
 	theme: &Theme,
+	pass: EdgePass,
 	n1: &force_graph::Node<NodeInfo>,
 	n2: &force_graph::Node<NodeInfo>,
+	edge: &EdgeInfo,
 ) {
-	let (x1, y1, x2, y2) = (n1.x() as f64, n1.y() as f64, n2.x() as f64, n2.y() as f64);
+	let ((x1, y1), (x2, y2)) = (display_pos(n1), display_pos(n2));
 	let (dx, dy) = (x2 - x1, y2 - y1);
 	let dist = (dx * dx + dy * dy).sqrt();
 	if dist < 0.001 {
 		return;
 	}
 
-	let edge_t = state.highlight.edge_intensity(n1.index(), n2.index());
-	let max_t = state.highlight.max_intensity();
+	let (edge_t, max_t) = edge_highlight(state, pass, n1, n2);
 
 	let glow_alpha = if edge_t > 0.01 {
-		theme.edge.glow_intensity * (0.6 + 0.4 * smooth_step(edge_t))
+		theme.edge.glow_intensity * (0.6 + 0.4 * highlight_ease(state, edge_t))
 	} else if max_t > 0.01 {
-		theme.edge.glow_intensity * (0.6 - 0.4 * smooth_step(max_t))
+		theme.edge.glow_intensity * (0.6 - 0.4 * highlight_ease(state, max_t))
 	} else {
 		theme.edge.glow_intensity * 0.6
 	};
@@ -179,33 +1078,17 @@ fn draw_edge_glow(
 		return;
 	}
 
-	let glow_width = scale.edge_line_width * 4.0;
+	let glow_width = scale.edge_line_width * 4.0 * edge.width_factor();
 	let glow_color = &theme.edge.glow_color;
 
-	ctx.set_stroke_style_str(&format!(
-		"rgba({}, {}, {}, {})",
-		glow_color.r,
-		glow_color.g,
-		glow_color.b,
-		glow_alpha * glow_color.a
-	));
+	ctx.set_stroke_style_str(&css(glow_color.with_alpha(glow_alpha * glow_color.a)));
 	ctx.set_line_width(glow_width);
-	let _ = ctx.set_line_dash(&js_sys::Array::new());
+	let _ = NO_DASH.with(|dash| ctx.set_line_dash(dash));
 
 	let (ux, uy) = (dx / dist, dy / dist);
 
-	if theme.edge.curved && dist > scale.node_radius * 4.0 {
-		draw_curved_edge(
-			ctx,
-			x1,
-			y1,
-			x2,
-			y2,
-			ux,
-			uy,
-			scale.node_radius,
-			theme.edge.curve_tension,
-		);
+	if let Some(tension) = edge_tension(state, theme, scale, edge, dist) {
+		draw_curved_edge(ctx, x1, y1, x2, y2, ux, uy, scale.node_radius, tension);
 	} else {
 		ctx.begin_path();
 		ctx.move_to(x1 + ux * scale.node_radius, y1 + uy * scale.node_radius);
@@ -218,23 +1101,23 @@ fn draw_edge_glow(
 fn draw_edge_main(
 	state: &ForceGraphState,
 	ctx: &CanvasRenderingContext2d,
-	_config: &ScaleConfig,
+	config: &ScaleConfig,
 	scale: &ScaledValues,
 	theme: &Theme,
+	pass: EdgePass,
 	n1: &force_graph::Node<NodeInfo>,
 	n2: &force_graph::Node<NodeInfo>,
-	dash_offset: f64,
-	_k: f64,
+	edge: &EdgeInfo,
 ) {
-	let (x1, y1, x2, y2) = (n1.x() as f64, n1.y() as f64, n2.x() as f64, n2.y() as f64);
+	let ((x1, y1), (x2, y2)) = (display_pos(n1), display_pos(n2));
 	let (dx, dy) = (x2 - x1, y2 - y1);
 	let dist = (dx * dx + dy * dy).sqrt();
 	if dist < 0.001 {
 		return;
 	}
 
-	let edge_t = smooth_step(state.highlight.edge_intensity(n1.index(), n2.index()));
-	let max_t = smooth_step(state.highlight.max_intensity());
+	let (edge_t, max_t) = edge_highlight(state, pass, n1, n2);
+	let (edge_t, max_t) = (highlight_ease(state, edge_t), highlight_ease(state, max_t));
 
 	let (edge_alpha, base_arrow_alpha, base_width) = if edge_t > 0.01 {
 		(
@@ -253,17 +1136,13 @@ fn draw_edge_main(
 	};
 
 	// Compensate for dash pattern fading to solid
-	let width = base_width * (1.0 + 0.3 * (1.0 - scale.dash_alpha));
+	let width = base_width * (1.0 + 0.3 * (1.0 - scale.dash_alpha)) * edge.width_factor();
 	let arrow_alpha = base_arrow_alpha * scale.arrow_alpha;
 
-	let edge_color = &theme.edge.color;
-	ctx.set_stroke_style_str(&format!(
-		"rgba({}, {}, {}, {})",
-		edge_color.r,
-		edge_color.g,
-		edge_color.b,
-		edge_alpha * edge_color.a
-	));
+	let edge_color = theme
+		.edge
+		.color_for(edge.kind.as_deref(), n1.data.user_data.fill);
+	ctx.set_stroke_style_str(&css(edge_color.with_alpha(edge_alpha * edge_color.a)));
 	ctx.set_line_width(width);
 
 	// Fade dash pattern to solid when zoomed out
@@ -273,14 +1152,16 @@ fn draw_edge_main(
 			&JsValue::from_f64(scale.dash_pattern.0),
 			&JsValue::from_f64(effective_gap),
 		));
-		ctx.set_line_dash_offset(dash_offset);
+		let flow_speed = edge.flow_speed.unwrap_or(config.edge.flow_speed);
+		let direction = if edge.flow_reverse { -1.0 } else { 1.0 };
+		ctx.set_line_dash_offset(scale.dash_offset(state.flow_time, flow_speed) * direction);
 	} else {
-		let _ = ctx.set_line_dash(&js_sys::Array::new());
+		let _ = NO_DASH.with(|dash| ctx.set_line_dash(dash));
 	}
 
 	let (ux, uy) = (dx / dist, dy / dist);
 
-	if theme.edge.curved && dist > scale.node_radius * 4.0 {
+	if let Some(tension) = edge_tension(state, theme, scale, edge, dist) {
 		draw_curved_edge(
 			ctx,
 			x1,
@@ -290,7 +1171,7 @@ fn draw_edge_main(
 			ux,
 			uy,
 			scale.node_radius + scale.arrow_size,
-			theme.edge.curve_tension,
+			tension,
 		);
 	} else {
 		ctx.begin_path();
@@ -303,14 +1184,8 @@ fn draw_edge_main(
 	}
 
 	if !scale.cull_arrows && arrow_alpha > 0.0 {
-		let _ = ctx.set_line_dash(&js_sys::Array::new());
-		ctx.set_fill_style_str(&format!(
-			"rgba({}, {}, {}, {})",
-			edge_color.r,
-			edge_color.g,
-			edge_color.b,
-			arrow_alpha * edge_color.a
-		));
+		let _ = NO_DASH.with(|dash| ctx.set_line_dash(dash));
+		ctx.set_fill_style_str(&css(edge_color.with_alpha(arrow_alpha * edge_color.a)));
 
 		let (tip_x, tip_y) = (x2 - ux * scale.node_radius, y2 - uy * scale.node_radius);
 		let (back_x, back_y) = (tip_x - ux * scale.arrow_size, tip_y - uy * scale.arrow_size);
@@ -337,43 +1212,77 @@ fn draw_curved_edge(
 	offset: f64,
 	tension: f64,
 ) {
-	let (dx, dy) = (x2 - x1, y2 - y1);
-	let dist = (dx * dx + dy * dy).sqrt();
-
-	let curve_offset = dist * tension * 0.3;
-	let (px, py) = (-uy * curve_offset, ux * curve_offset);
-
 	let (start_x, start_y) = (x1 + ux * offset, y1 + uy * offset);
 	let (end_x, end_y) = (x2 - ux * offset, y2 - uy * offset);
-	let (mid_x, mid_y) = ((x1 + x2) / 2.0 + px, (y1 + y2) / 2.0 + py);
+	let (mid_x, mid_y) = curve_control_point(x1, y1, x2, y2, ux, uy, tension);
 
 	ctx.begin_path();
 	ctx.move_to(start_x, start_y);
-	let _ = ctx.quadratic_curve_to(mid_x, mid_y, end_x, end_y);
+	ctx.quadratic_curve_to(mid_x, mid_y, end_x, end_y);
 	ctx.stroke();
 }
 
+/// Control point of the quadratic curve used for curved edges: the midpoint pushed
+/// perpendicular to the edge by an amount proportional to its length.
+fn curve_control_point(
+	x1: f64,
+	y1: f64,
+	x2: f64,
+	y2: f64,
+	ux: f64,
+	uy: f64,
+	tension: f64,
+) -> (f64, f64) {
+	let (dx, dy) = (x2 - x1, y2 - y1);
+	let dist = (dx * dx + dy * dy).sqrt();
+	let curve_offset = dist * tension * 0.3;
+	let (px, py) = (-uy * curve_offset, ux * curve_offset);
+	((x1 + x2) / 2.0 + px, (y1 + y2) / 2.0 + py)
+}
+
 fn draw_nodes(
 	state: &ForceGraphState,
 	ctx: &CanvasRenderingContext2d,
 	_config: &ScaleConfig,
 	scale: &ScaledValues,
 	theme: &Theme,
+	hooks: &RenderHooks,
 ) {
-	let max_t = smooth_step(state.highlight.max_intensity());
+	let max_t = highlight_ease(state, state.highlight.max_intensity());
 	let has_highlight = max_t > 0.01;
-	let pulse = if theme.node.pulse_intensity > 0.0 {
+	let theme_pulse = if theme.node.pulse_intensity > 0.0 {
 		(state.flow_time * theme.node.pulse_speed).sin() * theme.node.pulse_intensity
 	} else {
 		0.0
 	};
+	let time = state.flow_time;
+
+	// Pass 0: expanding halos behind data-pulsed nodes
+	visit_visible_nodes(state, |node| {
+		if node.data.user_data.pulse > 0.0 {
+			ctx.set_global_alpha(node_alpha(state, node));
+			draw_pulse_halo(ctx, node, scale, time);
+		}
+	});
+	ctx.set_global_alpha(1.0);
 
-	// Pass 1: node glows
+	// Pass 1: node glows, selected nodes glowing in the selection color
+	#[cfg(feature = "glow")]
+	if theme.selection.glow > 0.0 && !state.selection.is_empty() {
+		visit_visible_nodes(state, |node| {
+			if state.selection.contains(node.index()) {
+				ctx.set_global_alpha(node_alpha(state, node));
+				draw_selection_glow(ctx, node, scale, theme);
+			}
+		});
+		ctx.set_global_alpha(1.0);
+	}
+	#[cfg(feature = "glow")]
 	if theme.node.glow_intensity > 0.0 {
-		state.graph.visit_nodes(|node| {
+		visit_visible_nodes(state, |node| {
 			let idx = node.index();
-			let node_t = smooth_step(state.highlight.node_intensity(idx));
-			let hover_t = smooth_step(state.highlight.hover_ring_intensity(idx));
+			let node_t = highlight_ease(state, state.highlight.node_intensity(idx));
+			let hover_t = highlight_ease(state, state.highlight.hover_ring_intensity(idx));
 
 			let glow_mult = if node_t > 0.001 {
 				let neighbor_glow = 1.0 + 0.3 * node_t;
@@ -385,36 +1294,45 @@ fn draw_nodes(
 				1.0
 			};
 
+			let pulse = node_pulse(node, theme_pulse, time);
+			ctx.set_global_alpha(node_alpha(state, node));
 			draw_node_glow(ctx, node, scale, theme, glow_mult, pulse);
 		});
+		ctx.set_global_alpha(1.0);
 	}
 
 	// Pass 2: non-highlighted nodes
-	state.graph.visit_nodes(|node| {
+	let draw_resting = |node: &force_graph::Node<NodeInfo>| {
 		let idx = node.index();
-		let node_t = state.highlight.node_intensity(idx);
-		if node_t > 0.001 {
-			return;
-		}
 		let (alpha, radius_mult) = if has_highlight {
 			(1.0 - 0.7 * max_t, 1.0 - 0.15 * max_t)
 		} else {
 			(1.0, 1.0)
 		};
-		draw_node(ctx, node, scale, theme, alpha, radius_mult, pulse);
-	});
+		let alpha = alpha * node_alpha(state, node);
+		let pulse = node_pulse(node, theme_pulse, time);
+		let show_label = state.show_labels && state.placed_labels.contains(idx);
+		draw_node(
+			state,
+			ctx,
+			node,
+			scale,
+			theme,
+			hooks,
+			alpha,
+			radius_mult,
+			pulse,
+			show_label,
+		);
+	};
 
 	// Pass 3: highlighted/transitioning nodes on top
-	state.graph.visit_nodes(|node| {
+	let draw_highlighted = |node: &force_graph::Node<NodeInfo>| {
 		let idx = node.index();
 		let node_t = state.highlight.node_intensity(idx);
-		if node_t <= 0.001 {
-			return;
-		}
-
-		let eased_t = smooth_step(node_t);
-		let hover_t = smooth_step(state.highlight.hover_ring_intensity(idx));
-		let (x, y) = (node.x() as f64, node.y() as f64);
+		let eased_t = highlight_ease(state, node_t);
+		let hover_t = highlight_ease(state, state.highlight.hover_ring_intensity(idx));
+		let (x, y) = display_pos(node);
 
 		let dim_alpha = if has_highlight {
 			1.0 - 0.7 * max_t
@@ -431,38 +1349,132 @@ fn draw_nodes(
 		let hovered_radius = 1.0 + 0.4 * eased_t;
 		let highlight_radius = neighbor_radius + (hovered_radius - neighbor_radius) * hover_t;
 
-		let alpha = dim_alpha + (1.0 - dim_alpha) * eased_t;
+		let focus_alpha = node_alpha(state, node);
+		let alpha = (dim_alpha + (1.0 - dim_alpha) * eased_t) * focus_alpha;
 		let radius_mult = dim_radius + (highlight_radius - dim_radius) * eased_t;
+		let pulse = node_pulse(node, theme_pulse, time);
 
-		draw_node(ctx, node, scale, theme, alpha, radius_mult, pulse);
+		let show_label = state.show_labels;
+		draw_node(
+			state,
+			ctx,
+			node,
+			scale,
+			theme,
+			hooks,
+			alpha,
+			radius_mult,
+			pulse,
+			show_label,
+		);
+		ctx.set_global_alpha(focus_alpha);
 
-		let ring_t = smooth_step(state.highlight.hover_ring_intensity(idx));
+		let ring_t = highlight_ease(state, state.highlight.hover_ring_intensity(idx));
 		if ring_t > 0.01 {
-			let node_size = node.data.user_data.size;
+			let node_size = node.data.user_data.display_size();
 			let radius = scale.node_radius * radius_mult * node_size * (1.0 + pulse);
 			ctx.begin_path();
 			let _ = ctx.arc(x, y, radius + scale.ring_offset, 0.0, 2.0 * PI);
-			ctx.set_stroke_style_str(&format!("rgba(255, 255, 255, {})", 0.8 * ring_t));
+			ctx.set_stroke_style_str(&css(Color::rgba(255, 255, 255, 0.8 * ring_t)));
 			ctx.set_line_width(scale.ring_width);
 			ctx.stroke();
 
 			ctx.begin_path();
 			let _ = ctx.arc(x, y, radius + scale.ring_offset * 2.5, 0.0, 2.0 * PI);
-			ctx.set_stroke_style_str(&format!("rgba(255, 255, 255, {})", 0.3 * ring_t));
+			ctx.set_stroke_style_str(&css(Color::rgba(255, 255, 255, 0.3 * ring_t)));
 			ctx.set_line_width(scale.ring_width * 0.5);
 			ctx.stroke();
 		}
 
-		if let Some(label) = &node.data.user_data.label {
-			let node_size = node.data.user_data.size;
+		if let Some(label) = &node.data.user_data.label
+			&& state.show_labels
+		{
+			let node_size = node.data.user_data.display_size();
 			let radius = scale.node_radius * radius_mult * node_size * (1.0 + pulse);
-			ctx.set_fill_style_str(&format!("rgba(255, 255, 255, {})", 0.95 * alpha));
-			ctx.set_font(&scale.label_font);
-			let _ = ctx.fill_text(label, x + radius + 4.0, y + 3.0);
+			let magnification = node.data.user_data.display.magnification;
+			let opacity = alpha * focus_alpha;
+			draw_label(
+				state,
+				ctx,
+				scale,
+				theme,
+				label,
+				(x, y),
+				radius,
+				magnification,
+				opacity,
+			);
 		}
+		ctx.set_global_alpha(1.0);
+	};
+
+	// Layers stack bottom to top, whatever is highlighted; within one layer
+	// highlighted nodes go on top
+	let mut stack = Vec::new();
+	visit_visible_nodes(state, |node| {
+		let highlighted = state.highlight.node_intensity(node.index()) > 0.001;
+		stack.push((node.data.user_data.layer, highlighted, node.index()));
 	});
+	// Stable, so nodes keep their drawing order within a layer
+	stack.sort_by_key(|&(layer, highlighted, _)| (layer, highlighted));
+	for (_, highlighted, idx) in stack {
+		let Some(node) = state.node(idx) else {
+			continue;
+		};
+		if highlighted {
+			draw_highlighted(node);
+		} else {
+			draw_resting(node);
+		}
+	}
+
+	// Pass 4: selection tint and rings
+	if !state.selection.is_empty() {
+		let style = &theme.selection;
+		visit_visible_nodes(state, |node| {
+			if !state.selection.contains(node.index()) {
+				return;
+			}
+			let (x, y) = display_pos(node);
+			let radius = scale.node_radius * node.data.user_data.display_size();
+			ctx.set_global_alpha(node_alpha(state, node));
+			if style.fill_tint.a > 0.0 {
+				ctx.begin_path();
+				let _ = ctx.arc(x, y, radius, 0.0, 2.0 * PI);
+				ctx.set_fill_style_str(&css(style.fill_tint));
+				ctx.fill();
+			}
+			ctx.begin_path();
+			let _ = ctx.arc(x, y, radius + scale.ring_offset * 1.5, 0.0, 2.0 * PI);
+			ctx.set_stroke_style_str(&css(style.ring_color));
+			ctx.set_line_width(scale.ring_width);
+			ctx.stroke();
+		});
+		ctx.set_global_alpha(1.0);
+	}
+}
+
+/// Draws a ring expanding outward from a pulsing node and fading as it grows.
+fn draw_pulse_halo(
+	ctx: &CanvasRenderingContext2d,
+	node: &force_graph::Node<NodeInfo>,
+	scale: &ScaledValues,
+	time: f64,
+) {
+	let info = &node.data.user_data;
+	let phase = (time * DATA_PULSE_SPEED / (2.0 * PI)).fract();
+	let radius = scale.node_radius * info.display_size() * (1.0 + info.pulse * 4.0 * phase);
+	let alpha = (1.0 - phase) * 0.6;
+	let (x, y) = display_pos(node);
+
+	ctx.begin_path();
+	let _ = ctx.arc(x, y, radius, 0.0, 2.0 * PI);
+	ctx.set_stroke_style_str(&css(info.fill.with_alpha(alpha)));
+	ctx.set_line_width(scale.ring_width);
+	ctx.stroke();
 }
 
+#[cfg(feature = "glow")]
 fn draw_node_glow(
 	ctx: &CanvasRenderingContext2d,
 	node: &force_graph::Node<NodeInfo>,
@@ -471,8 +1483,8 @@ fn draw_node_glow(
 	intensity_mult: f64,
 	pulse: f64,
 ) {
-	let (x, y) = (node.x() as f64, node.y() as f64);
-	let node_size = node.data.user_data.size;
+	let (x, y) = display_pos(node);
+	let node_size = node.data.user_data.display_size();
 	let radius = scale.node_radius * node_size * (1.0 + pulse);
 	let glow_radius = radius * 3.0 * intensity_mult;
 	let alpha = theme.node.glow_intensity * intensity_mult * 0.4;
@@ -481,7 +1493,7 @@ fn draw_node_glow(
 		return;
 	}
 
-	let node_color = parse_color(&node.data.user_data.color);
+	let node_color = node.data.user_data.fill;
 
 	let gradient = ctx
 		.create_radial_gradient(x, y, radius * 0.5, x, y, glow_radius)
@@ -491,10 +1503,37 @@ fn draw_node_glow(
 	let white_glow = Color::rgba(255, 255, 255, alpha * 0.3);
 
 	gradient
-		.add_color_stop(0.0, &white_glow.lerp(glow_color, 0.5).to_css())
+		.add_color_stop(0.0, &css(white_glow.lerp(glow_color, 0.5)))
+		.unwrap();
+	gradient
+		.add_color_stop(0.4, &css(glow_color.with_alpha(alpha * 0.5)))
+		.unwrap();
+	gradient.add_color_stop(1.0, "rgba(0, 0, 0, 0)").unwrap();
+
+	ctx.begin_path();
+	let _ = ctx.arc(x, y, glow_radius, 0.0, 2.0 * PI);
+	#[allow(deprecated)]
+	ctx.set_fill_style(&gradient);
+	ctx.fill();
+}
+
+/// Draws a soft halo in the selection ring color behind a selected node.
+#[cfg(feature = "glow")]
+fn draw_selection_glow(
+	ctx: &CanvasRenderingContext2d,
+	node: &force_graph::Node<NodeInfo>,
+	scale: &ScaledValues,
+	theme: &Theme,
+) {
+	let (x, y) = display_pos(node);
+	let radius = scale.node_radius * node.data.user_data.display_size();
+	let glow_radius = radius * 3.0;
+	let color = theme.selection.ring_color;
+	let gradient = ctx
+		.create_radial_gradient(x, y, radius, x, y, glow_radius)
 		.unwrap();
 	gradient
-		.add_color_stop(0.4, &glow_color.with_alpha(alpha * 0.5).to_css())
+		.add_color_stop(0.0, &css(color.with_alpha(0.5 * theme.selection.glow)))
 		.unwrap();
 	gradient.add_color_stop(1.0, "rgba(0, 0, 0, 0)").unwrap();
 
@@ -505,34 +1544,95 @@ fn draw_node_glow(
 	ctx.fill();
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_node(
+	state: &ForceGraphState,
 	ctx: &CanvasRenderingContext2d,
 	node: &force_graph::Node<NodeInfo>,
 	scale: &ScaledValues,
 	theme: &Theme,
+	hooks: &RenderHooks,
 	alpha: f64,
 	radius_mult: f64,
 	pulse: f64,
+	show_label: bool,
 ) {
-	let (x, y) = (node.x() as f64, node.y() as f64);
-	let node_size = node.data.user_data.size;
+	let (x, y) = display_pos(node);
+	let node_size = node.data.user_data.display_size();
 	let radius = scale.node_radius * radius_mult * node_size * (1.0 + pulse);
-	let color = &node.data.user_data.color;
 
 	ctx.set_global_alpha(alpha);
+	let draw_default = || draw_node_body(ctx, node, scale, theme, radius);
+	match &hooks.node {
+		Some(hook) => {
+			let info = &node.data.user_data;
+			ctx.save();
+			hook(
+				ctx,
+				&NodeRenderInfo {
+					node: &info.spec,
+					x,
+					y,
+					radius,
+					color: &css(info.fill),
+					alpha,
+					highlight: state.highlight.node_intensity(node.index()),
+					selected: state.selection.contains(node.index()),
+					zoom: scale.k,
+					draw_default: &draw_default,
+				},
+			);
+			ctx.restore();
+		}
+		None => draw_default(),
+	}
+	ctx.set_global_alpha(1.0);
+
+	if let Some(label) = &node.data.user_data.label
+		&& alpha > 0.5
+		&& show_label
+	{
+		let magnification = node.data.user_data.display.magnification;
+		// Resting labels are a little fainter than highlighted ones
+		let opacity = alpha * 0.7;
+		draw_label(
+			state,
+			ctx,
+			scale,
+			theme,
+			label,
+			(x, y),
+			radius,
+			magnification,
+			opacity,
+		);
+		ctx.set_global_alpha(1.0);
+	}
+}
+
+/// The standard node: disc, border, segment ring and badge.
+fn draw_node_body(
+	ctx: &CanvasRenderingContext2d,
+	node: &force_graph::Node<NodeInfo>,
+	scale: &ScaledValues,
+	theme: &Theme,
+	radius: f64,
+) {
+	let (x, y) = display_pos(node);
+	let color = node.data.user_data.fill;
 
 	if theme.node.use_gradient {
 		let gradient = ctx
 			.create_radial_gradient(x - radius * 0.3, y - radius * 0.3, 0.0, x, y, radius)
 			.unwrap();
 
-		let base_color = parse_color(color);
+		let base_color = color;
 		let highlight = base_color.lighten(0.4);
 		let shadow = base_color.darken(0.2);
 
-		gradient.add_color_stop(0.0, &highlight.to_css()).unwrap();
-		gradient.add_color_stop(0.7, &base_color.to_css()).unwrap();
-		gradient.add_color_stop(1.0, &shadow.to_css()).unwrap();
+		gradient.add_color_stop(0.0, &css(highlight)).unwrap();
+		gradient.add_color_stop(0.7, &css(base_color)).unwrap();
+		gradient.add_color_stop(1.0, &css(shadow)).unwrap();
 
 		ctx.begin_path();
 		let _ = ctx.arc(x, y, radius, 0.0, 2.0 * PI);
@@ -542,64 +1642,243 @@ fn draw_node(
 	} else {
 		ctx.begin_path();
 		let _ = ctx.arc(x, y, radius, 0.0, 2.0 * PI);
-		ctx.set_fill_style_str(color);
+		ctx.set_fill_style_str(&css(color));
 		ctx.fill();
 	}
 
-	if theme.node.border_width > 0.0 {
+	let (border_color, border_width) = node
+		.data
+		.user_data
+		.border
+		.unwrap_or((theme.node.border_color, theme.node.border_width));
+	if border_width > 0.0 {
 		ctx.begin_path();
 		let _ = ctx.arc(x, y, radius, 0.0, 2.0 * PI);
-		ctx.set_stroke_style_str(&theme.node.border_color.to_css());
-		ctx.set_line_width(theme.node.border_width / scale.k);
+		ctx.set_stroke_style_str(&css(border_color));
+		ctx.set_line_width(border_width / scale.k);
 		ctx.stroke();
 	}
 
-	ctx.set_global_alpha(1.0);
+	if !node.data.user_data.ring.is_empty() {
+		draw_segment_ring(ctx, node, scale, radius);
+	}
 
-	if let Some(label) = &node.data.user_data.label {
-		if alpha > 0.5 {
-			ctx.set_global_alpha(alpha * 0.8);
-			ctx.set_fill_style_str("rgba(255, 255, 255, 0.85)");
-			ctx.set_font(&scale.label_font);
-			let _ = ctx.fill_text(label, x + radius + 4.0, y + 3.0);
-			ctx.set_global_alpha(1.0);
+	if let Some(badge) = &node.data.user_data.badge {
+		draw_badge(ctx, node, scale, theme, badge, radius);
+	}
+}
+
+/// Smallest on-screen label size, in pixels, at which labels beyond the top
+/// ones are shown.
+const MIN_LABEL_PX: f64 = 7.0;
+
+/// Side of the grid cells used to find overlapping labels, in pixels.
+const LABEL_CELL: f64 = 64.0;
+
+/// Clear space kept around each placed label, in pixels.
+const LABEL_GAP: f64 = 2.0;
+
+/// Nodes whose resting labels to draw in the current view, for
+/// [`ForceGraphState::placed_labels`]. Labels are placed in priority order
+/// (see [`ForceGraphState::rank_labels`]): the top `state.top_labels` always,
+/// the rest only where they overlap no label placed before them and the text
+/// is large enough to read. Zooming in spreads nodes apart, so more labels fit.
+pub fn place_labels(state: &ForceGraphState, scale: &ScaledValues) -> HashSet<DefaultNodeIdx> {
+	let t = &state.transform;
+	let readable = scale.label_font_size * t.k >= MIN_LABEL_PX;
+	let mut placed = HashSet::new();
+	let mut cells: HashMap<(i64, i64), Vec<[f64; 4]>> = HashMap::new();
+	for (rank, &idx) in state.label_order.iter().enumerate() {
+		let top = rank < state.top_labels;
+		if !top && !readable {
+			break;
+		}
+		let Some(node) = state.node(idx) else {
+			continue;
+		};
+		let info = &node.data.user_data;
+		let Some(label) = info.label.as_deref().filter(|_| !info.hidden) else {
+			continue;
+		};
+		let (x, y) = display_pos(node);
+		let magnification = info.display.magnification;
+		let radius = scale.node_radius * info.display_size();
+		if !label_on_screen(state, scale, label, x, y, radius, magnification) {
+			continue;
+		}
+		// Unmeasured labels get a rough width until they are measured
+		let width = state
+			.labels
+			.width(label, scale.label_font_size)
+			.unwrap_or(label.chars().count() as f64 * scale.label_font_size * 0.6);
+		let (left, baseline) = (x + radius + 4.0 * magnification, y + 3.0 * magnification);
+		let height = scale.label_font_size * magnification;
+		let rect = [
+			(left * t.k + t.x) - LABEL_GAP,
+			((baseline - height) * t.k + t.y) - LABEL_GAP,
+			((left + width * magnification) * t.k + t.x) + LABEL_GAP,
+			((baseline + height * 0.25) * t.k + t.y) + LABEL_GAP,
+		];
+		let (cx0, cy0) = (
+			(rect[0] / LABEL_CELL).floor() as i64,
+			(rect[1] / LABEL_CELL).floor() as i64,
+		);
+		let (cx1, cy1) = (
+			(rect[2] / LABEL_CELL).floor() as i64,
+			(rect[3] / LABEL_CELL).floor() as i64,
+		);
+		let overlaps = |other: &[f64; 4]| {
+			rect[0] < other[2] && other[0] < rect[2] && rect[1] < other[3] && other[1] < rect[3]
+		};
+		if !top
+			&& (cx0..=cx1)
+				.flat_map(|cx| (cy0..=cy1).map(move |cy| (cx, cy)))
+				.any(|cell| {
+					cells
+						.get(&cell)
+						.is_some_and(|rects| rects.iter().any(overlaps))
+				}) {
+			continue;
+		}
+		for cx in cx0..=cx1 {
+			for cy in cy0..=cy1 {
+				cells.entry((cx, cy)).or_default().push(rect);
+			}
 		}
+		placed.insert(idx);
 	}
+	placed
 }
 
-/// Parses a CSS color string into a [`Color`].
-/// Supports hex (`#RRGGBB`) and `rgb()`/`rgba()` functional notation.
-fn parse_color(color_str: &str) -> Color {
-	if color_str.starts_with('#') && color_str.len() == 7 {
-		let r = u8::from_str_radix(&color_str[1..3], 16).unwrap_or(128);
-		let g = u8::from_str_radix(&color_str[3..5], 16).unwrap_or(128);
-		let b = u8::from_str_radix(&color_str[5..7], 16).unwrap_or(128);
-		Color::rgb(r, g, b)
-	} else if color_str.starts_with("rgb") {
-		let nums: Vec<&str> = color_str
-			.trim_start_matches("rgba(")
-			.trim_start_matches("rgb(")
-			.trim_end_matches(')')
-			.split(',')
-			.collect();
-		let r = nums
-			.first()
-			.and_then(|s| s.trim().parse().ok())
-			.unwrap_or(128);
-		let g = nums
-			.get(1)
-			.and_then(|s| s.trim().parse().ok())
-			.unwrap_or(128);
-		let b = nums
-			.get(2)
-			.and_then(|s| s.trim().parse().ok())
-			.unwrap_or(128);
-		let a = nums
-			.get(3)
-			.and_then(|s| s.trim().parse().ok())
-			.unwrap_or(1.0);
-		Color::rgba(r, g, b, a)
-	} else {
-		Color::rgb(128, 128, 128)
+/// Whether any part of a label could be on screen. Labels whose width hasn't
+/// been measured yet count as visible (and get queued for measurement).
+fn label_on_screen(
+	state: &ForceGraphState,
+	scale: &ScaledValues,
+	label: &str,
+	x: f64,
+	y: f64,
+	radius: f64,
+	magnification: f64,
+) -> bool {
+	let (x0, y0) = state.screen_to_graph(0.0, 0.0);
+	let (x1, y1) = state.screen_to_graph(state.width, state.height);
+	let left = x + radius + 4.0 * magnification;
+	let height = scale.label_font_size * magnification;
+	if left > x1 || y + height < y0 || y - height > y1 {
+		return false;
+	}
+	state
+		.labels
+		.width(label, scale.label_font_size)
+		.is_none_or(|width| left + width * magnification >= x0)
+}
+
+/// Draws a node label to the right of the node in the theme's label color, at
+/// `opacity`. Uses the label's pre-rendered sprite when there is a current one
+/// and falls back to drawing the text. Magnified nodes (fisheye) get
+/// proportionally larger text. Sets `global_alpha`.
+#[allow(clippy::too_many_arguments)]
+fn draw_label(
+	state: &ForceGraphState,
+	ctx: &CanvasRenderingContext2d,
+	scale: &ScaledValues,
+	theme: &Theme,
+	label: &str,
+	(x, y): (f64, f64),
+	radius: f64,
+	magnification: f64,
+	opacity: f64,
+) {
+	if !label_on_screen(state, scale, label, x, y, radius, magnification) {
+		return;
+	}
+	ctx.set_global_alpha(opacity);
+	let color = theme.node.label_color;
+	let (left, baseline) = (x + radius + 4.0 * magnification, y + 3.0 * magnification);
+	// Sprites are rasterized for the screen; print resolution draws plain text
+	if state.pixel_ratio == 1.0
+		&& let Some(sprite) = state
+			.sprites
+			.get(label, scale.label_font_size * scale.k, color)
+	{
+		// World units per sprite pixel
+		let s = magnification * scale.label_font_size / sprite.font_px;
+		let (w, h) = (sprite.canvas.width() as f64, sprite.canvas.height() as f64);
+		let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(
+			&sprite.canvas,
+			left,
+			baseline - sprite.baseline * s,
+			w * s,
+			h * s,
+		);
+		return;
+	}
+
+	ctx.set_font(&scale.label_font);
+	ctx.set_fill_style_str(&css(color));
+	if (magnification - 1.0).abs() < 0.01 {
+		let _ = ctx.fill_text(label, left, baseline);
+		return;
+	}
+	ctx.save();
+	let _ = ctx.translate(left, baseline);
+	let _ = ctx.scale(magnification, magnification);
+	let _ = ctx.fill_text(label, 0.0, 0.0);
+	ctx.restore();
+}
+
+/// Draws a node's segmented ring: one arc per data segment, just outside the node.
+fn draw_segment_ring(
+	ctx: &CanvasRenderingContext2d,
+	node: &force_graph::Node<NodeInfo>,
+	scale: &ScaledValues,
+	node_radius: f64,
+) {
+	let (x, y) = display_pos(node);
+	// Half the stroke width to sit outside the fill, plus a small gap
+	let ring_radius = node_radius + scale.segment_ring_width * 0.8;
+	ctx.set_line_width(scale.segment_ring_width);
+	for (start, end, color) in &node.data.user_data.ring {
+		ctx.begin_path();
+		let _ = ctx.arc(x, y, ring_radius, *start, *end);
+		ctx.set_stroke_style_str(color);
+		ctx.stroke();
+	}
+}
+
+/// Draws a node's status badge: a filled circle on the rim with centered text.
+/// Expects the node's alpha to already be applied via `global_alpha`.
+fn draw_badge(
+	ctx: &CanvasRenderingContext2d,
+	node: &force_graph::Node<NodeInfo>,
+	scale: &ScaledValues,
+	theme: &Theme,
+	text: &str,
+	node_radius: f64,
+) {
+	let (x, y) = display_pos(node);
+	let (bx, by) = badge_center(x, y, node_radius);
+	let fill = node
+		.data
+		.user_data
+		.badge_color
+		.as_deref()
+		.map(Color::parse)
+		.unwrap_or(theme.badge.color);
+
+	ctx.begin_path();
+	let _ = ctx.arc(bx, by, scale.badge_radius, 0.0, 2.0 * PI);
+	ctx.set_fill_style_str(&css(fill));
+	ctx.fill();
+
+	if !text.is_empty() {
+		ctx.set_fill_style_str(&css(theme.badge.text_color));
+		ctx.set_font(&scale.badge_font);
+		ctx.set_text_align("center");
+		ctx.set_text_baseline("middle");
+		let _ = ctx.fill_text(text, bx, by);
+		ctx.set_text_align("start");
+		ctx.set_text_baseline("alphabetic");
 	}
 }
@@ -21,6 +21,8 @@
 //! - [`ScaleBehavior::Clamped`]: World-space scaling with min/max screen-size bounds.
 //!   Useful for elements that should scale but not become too small or too large.
 
+use super::labels::LABEL_FAMILY;
+
 /// Defines how a visual property scales with zoom level.
 #[derive(Clone, Debug)]
 #[allow(
@@ -34,7 +36,12 @@ pub enum ScaleBehavior {
 	Screen,
 	/// World-space scaling, clamped to min/max screen-space bounds.
 	/// `(min_screen_px, max_screen_px)` - use `f64::NEG_INFINITY` or `f64::INFINITY` for unbounded.
-	Clamped { min_screen: f64, max_screen: f64 },
+	Clamped {
+		/// Minimum on-screen size in pixels.
+		min_screen: f64,
+		/// Maximum on-screen size in pixels.
+		max_screen: f64,
+	},
 }
 
 impl ScaleBehavior {
@@ -76,7 +83,9 @@ pub enum AlphaBehavior {
 	/// Alpha fades based on zoom thresholds.
 	/// Fully visible at `full_alpha_k`, fades to zero at `zero_alpha_k`.
 	Fade {
+		/// Zoom level at (or below) which alpha reaches zero.
 		zero_alpha_k: f64,
+		/// Zoom level at (or above) which alpha is fully opaque.
 		full_alpha_k: f64,
 	},
 }
@@ -116,6 +125,12 @@ pub struct NodeScaleConfig {
 	pub label_size: f64,
 	/// Minimum zoom level for label font scaling.
 	pub label_min_k: f64,
+	/// Badge radius in screen pixels.
+	pub badge_radius: f64,
+	/// Segmented ring stroke width in world units.
+	pub segment_ring_width: f64,
+	/// How the segmented ring width scales with zoom.
+	pub segment_ring_behavior: ScaleBehavior,
 }
 
 /// Configuration for edge visual scaling.
@@ -130,6 +145,15 @@ pub struct EdgeScaleConfig {
 	/// How dash pattern alpha/visibility scales with zoom.
 	/// When faded out, edges become solid lines.
 	pub dash_alpha_behavior: AlphaBehavior,
+	/// Traffic dot speed for a weight-1 edge (world units per second).
+	pub traffic_speed: f64,
+	/// Spacing between traffic dots on a weight-1 edge (world units).
+	/// Heavier edges pack dots proportionally closer.
+	pub traffic_spacing: f64,
+	/// Traffic dot radius in screen pixels.
+	pub traffic_dot_radius: f64,
+	/// Zoom level below which traffic dots are not drawn.
+	pub traffic_min_k: f64,
 }
 
 /// Configuration for arrow visual scaling.
@@ -161,9 +185,13 @@ pub struct GlowScaleConfig {
 /// Complete scale configuration for all graph elements.
 #[derive(Clone, Debug)]
 pub struct ScaleConfig {
+	/// Node radius, hit area, and label sizing.
 	pub node: NodeScaleConfig,
+	/// Edge width and dash animation.
 	pub edge: EdgeScaleConfig,
+	/// Arrowhead sizing and fading.
 	pub arrow: ArrowScaleConfig,
+	/// Hover glow and ring sizing.
 	pub glow: GlowScaleConfig,
 }
 
@@ -183,6 +211,12 @@ impl Default for ScaleConfig {
 				},
 				label_size: 10.0,
 				label_min_k: 0.5,
+				badge_radius: 6.0,
+				segment_ring_width: 2.0,
+				segment_ring_behavior: ScaleBehavior::Clamped {
+					min_screen: 1.5,
+					max_screen: 6.0,
+				},
 			},
 			edge: EdgeScaleConfig {
 				line_width: 1.5,
@@ -192,6 +226,10 @@ impl Default for ScaleConfig {
 					zero_alpha_k: 0.4,
 					full_alpha_k: 0.9,
 				},
+				traffic_speed: 40.0,
+				traffic_spacing: 60.0,
+				traffic_dot_radius: 2.0,
+				traffic_min_k: 0.3,
 			},
 			arrow: ArrowScaleConfig {
 				size: 5.0,
@@ -212,6 +250,45 @@ impl Default for ScaleConfig {
 	}
 }
 
+/// Scale settings a theme imposes on top of the configured [`ScaleConfig`].
+/// Fields left `None` keep the configured value.
+#[derive(Clone, Debug, Default)]
+pub struct ScaleOverrides {
+	/// Base node radius in world units.
+	pub node_radius: Option<f64>,
+	/// Label font size in screen pixels.
+	pub label_size: Option<f64>,
+	/// Base edge line width in screen pixels.
+	pub edge_line_width: Option<f64>,
+	/// Edge dash pattern (dash, gap) in world units. A zero gap draws solid edges.
+	pub dash_pattern: Option<(f64, f64)>,
+	/// Edge flow animation speed (world units per second).
+	pub flow_speed: Option<f64>,
+	/// Base arrow size in world units.
+	pub arrow_size: Option<f64>,
+}
+
+impl ScaleConfig {
+	/// This config with a theme's overrides applied.
+	pub fn with_overrides(&self, overrides: &ScaleOverrides) -> Self {
+		let mut config = self.clone();
+		let set = |field: &mut f64, value: Option<f64>| {
+			if let Some(value) = value {
+				*field = value;
+			}
+		};
+		set(&mut config.node.radius, overrides.node_radius);
+		set(&mut config.node.label_size, overrides.label_size);
+		set(&mut config.edge.line_width, overrides.edge_line_width);
+		set(&mut config.edge.flow_speed, overrides.flow_speed);
+		set(&mut config.arrow.size, overrides.arrow_size);
+		if let Some(pattern) = overrides.dash_pattern {
+			config.edge.dash_pattern = pattern;
+		}
+		config
+	}
+}
+
 /// Pre-computed scale values for a specific zoom level.
 ///
 /// Create this once per frame and pass it to rendering functions.
@@ -230,12 +307,24 @@ pub struct ScaledValues {
 	pub hit_radius: f64,
 	/// Label font size string (e.g., "10px sans-serif").
 	pub label_font: String,
+	/// Label font size in world-space.
+	pub label_font_size: f64,
+	/// Badge radius in world-space.
+	pub badge_radius: f64,
+	/// Badge font string, sized to fit inside the badge.
+	pub badge_font: String,
+	/// Segmented ring stroke width in world-space.
+	pub segment_ring_width: f64,
 	/// Edge line width in world-space.
 	pub edge_line_width: f64,
 	/// Dash pattern in world-space.
 	pub dash_pattern: (f64, f64),
 	/// Dash pattern visibility [0, 1]. At 0, edges are solid lines.
 	pub dash_alpha: f64,
+	/// Traffic dot radius in world-space.
+	pub traffic_dot_radius: f64,
+	/// Whether to skip drawing traffic dots (zoomed too far out).
+	pub cull_traffic: bool,
 	/// Arrow size in world-space.
 	pub arrow_size: f64,
 	/// Arrow alpha multiplier [0, 1].
@@ -261,10 +350,19 @@ impl ScaledValues {
 			k,
 			node_radius,
 			hit_radius,
-			label_font: format!("{}px sans-serif", label_font_size),
+			label_font: format!("{}px {LABEL_FAMILY}", label_font_size),
+			label_font_size,
+			badge_radius: config.node.badge_radius / k,
+			badge_font: format!("bold {}px sans-serif", config.node.badge_radius * 1.3 / k),
+			segment_ring_width: config
+				.node
+				.segment_ring_behavior
+				.apply(config.node.segment_ring_width, k),
 			edge_line_width: config.edge.line_width / k,
 			dash_pattern: config.edge.dash_pattern,
 			dash_alpha,
+			traffic_dot_radius: config.edge.traffic_dot_radius / k,
+			cull_traffic: k < config.edge.traffic_min_k,
 			arrow_size: config.arrow.size_behavior.apply(config.arrow.size, k),
 			arrow_alpha,
 			cull_arrows: arrow_alpha < config.arrow.cull_alpha,
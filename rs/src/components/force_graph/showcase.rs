@@ -0,0 +1,101 @@
+//! Idle showcase mode for unattended displays.
+//!
+//! After a stretch without input the camera glides to the fitted view and starts
+//! a slow drift around it, while the hover highlight steps through the
+//! best-connected nodes. Any input ends the show and leaves the view where it is.
+
+use std::f64::consts::TAU;
+
+use force_graph::DefaultNodeIdx;
+
+use super::state::ViewTransform;
+
+/// Seconds for one full drift loop.
+const DRIFT_PERIOD: f64 = 60.0;
+
+/// Drift radius as a fraction of the smaller viewport side.
+const DRIFT_RADIUS: f64 = 0.06;
+
+/// Peak zoom change while drifting (0.08 = ±8%).
+const DRIFT_ZOOM: f64 = 0.08;
+
+/// Seconds each spotlighted node stays highlighted.
+const SPOTLIGHT_DWELL: f64 = 4.0;
+
+/// Idle timer and progress of the show.
+#[derive(Clone, Debug, Default)]
+pub struct Showcase {
+	/// Seconds without input before the show starts; `None` disables it.
+	pub delay: Option<f64>,
+	idle: f64,
+	/// View the camera drifts around, set once the show starts.
+	pub anchor: Option<ViewTransform>,
+	/// Nodes to spotlight in turn, highest degree first.
+	pub spotlights: Vec<DefaultNodeIdx>,
+	/// Seconds spent drifting.
+	drift: f64,
+	/// Seconds the current spotlight has been shown, and its position in the cycle.
+	dwell: f64,
+	spotlight: usize,
+}
+
+impl Showcase {
+	/// Whether the show is on.
+	pub fn is_running(&self) -> bool {
+		self.anchor.is_some()
+	}
+
+	/// Advance the idle timer. Returns whether the show should be running.
+	pub fn advance(&mut self, dt: f64) -> bool {
+		let Some(delay) = self.delay else {
+			return false;
+		};
+		self.idle += dt;
+		self.idle >= delay
+	}
+
+	/// Begin the show around `anchor`.
+	pub fn start(&mut self, anchor: ViewTransform) {
+		self.anchor = Some(anchor);
+		self.drift = 0.0;
+		self.dwell = SPOTLIGHT_DWELL;
+		self.spotlight = 0;
+	}
+
+	/// Register user input: restart the idle timer and stop any show. Returns
+	/// whether a show was running.
+	pub fn wake(&mut self) -> bool {
+		self.idle = 0.0;
+		self.spotlights.clear();
+		self.anchor.take().is_some()
+	}
+
+	/// Camera for the next frame of the drift. The path is a figure eight around
+	/// the anchor with a gentle zoom swell, starting exactly at the anchor.
+	pub fn camera(&mut self, dt: f64, width: f64, height: f64) -> Option<ViewTransform> {
+		let anchor = self.anchor.as_ref()?;
+		self.drift += dt;
+		let phase = self.drift / DRIFT_PERIOD * TAU;
+		let radius = width.min(height) * DRIFT_RADIUS;
+		let zoom = 1.0 + DRIFT_ZOOM * (phase * 0.5).sin();
+		// Zoom about the viewport center, then offset along the path
+		let (cx, cy) = (width / 2.0, height / 2.0);
+		Some(ViewTransform {
+			x: cx + (anchor.x - cx) * zoom + radius * phase.sin(),
+			y: cy + (anchor.y - cy) * zoom + radius * 0.5 * (phase * 2.0).sin(),
+			k: anchor.k * zoom,
+		})
+	}
+
+	/// The node to spotlight next, when it is time to move on.
+	pub fn next_spotlight(&mut self, dt: f64) -> Option<DefaultNodeIdx> {
+		self.dwell += dt;
+		if self.dwell < SPOTLIGHT_DWELL || self.spotlights.is_empty() {
+			return None;
+		}
+		self.dwell = 0.0;
+		let idx = self.spotlights[self.spotlight % self.spotlights.len()];
+		self.spotlight += 1;
+		Some(idx)
+	}
+}
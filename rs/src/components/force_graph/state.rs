@@ -7,11 +7,25 @@
 use std::collections::{HashMap, HashSet};
 use std::f64::consts::PI;
 
-use force_graph::{DefaultNodeIdx, EdgeData, ForceGraph, NodeData, SimulationParameters};
+use force_graph::{DefaultNodeIdx, EdgeData, ForceGraph, Node, NodeData, SimulationParameters};
+use serde::{Deserialize, Serialize};
 
+use super::adjacency::Adjacency;
+use super::depth::Space;
+use super::easing::Easing;
+use super::fisheye::FisheyeLens;
+use super::history::{History, NodeSnapshot};
+use super::labels::{LabelMetrics, LabelSprites, PlacedLabels, PlacementKey};
+use super::layers::Layers;
+use super::layout::{
+	self, Axis, AxisConstraint, AxisField, Lane, Layout, NodeConstraint, TimeScale,
+};
+use super::physics::PhysicsConfig;
 use super::scale::{ScaleConfig, ScaledValues};
-use super::theme::Theme;
-use super::types::GraphData;
+use super::showcase::Showcase;
+use super::theme::{Color, ColorRamp, NodePalette, Theme};
+use super::types::{GraphData, GraphLink, GraphNode, RingSegment};
+use super::view_model;
 
 /// Default cluster colors matching imp.lib conventions.
 pub fn default_cluster_colors() -> HashMap<String, String> {
@@ -33,19 +47,162 @@ pub fn default_cluster_colors() -> HashMap<String, String> {
 	.collect()
 }
 
+/// Group colors in effect for `data`: the host's map with the payload's own
+/// `cluster_colors` layered on top.
+fn group_colors(base: &HashMap<String, String>, data: &GraphData) -> HashMap<String, String> {
+	let mut colors = base.clone();
+	colors.extend(data.cluster_colors.clone());
+	colors
+}
+
+/// Palette colors for groups that have no assigned color, in order of first
+/// appearance. Beyond the theme palette's size, a palette with one distinct hue
+/// per group is generated so colors don't repeat.
+fn auto_group_colors(
+	data: &GraphData,
+	colors: &HashMap<String, String>,
+	theme: &Theme,
+) -> HashMap<String, String> {
+	let mut groups: Vec<&String> = Vec::new();
+	for group in data.nodes.iter().filter_map(|n| n.group.as_ref()) {
+		if !colors.contains_key(group) && !groups.contains(&group) {
+			groups.push(group);
+		}
+	}
+	let generated;
+	let palette = if groups.len() <= theme.palette.colors.len() {
+		&theme.palette
+	} else {
+		generated = NodePalette::generate(groups.len(), &theme.palette);
+		&generated
+	};
+	groups
+		.into_iter()
+		.enumerate()
+		.map(|(i, group)| (group.clone(), palette.get(i).to_css_rgb()))
+		.collect()
+}
+
 /// Per-node display metadata attached to each node in the simulation.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct NodeInfo {
+	/// The node's ID from the input data
+	pub id: String,
 	pub label: Option<String>,
 	pub color: String,
+	/// Fill actually drawn: `color` parsed, or a blend toward it while recoloring
+	pub fill: Color,
+	/// Fill a recolor transition started from
+	pub fade_from: Option<Color>,
 	/// Size multiplier (1.0 = normal, >1.0 = larger/more important)
 	pub size: f64,
+	/// Badge text, if the node carries a status marker
+	pub badge: Option<String>,
+	/// Badge color override (CSS)
+	pub badge_color: Option<String>,
+	/// Segmented ring as (start angle, end angle, CSS color), precomputed from data
+	pub ring: Vec<(f64, f64, String)>,
+	/// Data-driven pulse intensity (0.0 = no pulse)
+	pub pulse: f64,
+	/// Where the node is drawn this frame, refreshed by [`ForceGraphState::prepare_frame`]
+	pub display: DisplayGeometry,
+	/// Hidden nodes (and their edges) are not drawn or hit-tested
+	pub hidden: bool,
+	/// Data-supplied opacity multiplier for the node and its edges
+	pub opacity: f64,
+	/// Border from the theme's per-group rules: color and screen width
+	pub border: Option<(Color, f64)>,
+	/// Where the active layout lets the node go, set by [`ForceGraphState::apply_layout`]
+	pub constraint: NodeConstraint,
+	/// Inertia relative to a default node; each step's movement is divided by it
+	pub mass: f64,
+	/// Depth in 3D mode (see [`Space`]), 0.0 otherwise
+	pub z: f32,
+	/// Draw layer; higher layers are drawn and hit-tested above lower ones
+	pub layer: i32,
+	/// The input node this was built from, kept for export
+	pub spec: GraphNode,
 }
 
-/// Pan and zoom transform applied to the entire graph view.
+impl NodeInfo {
+	/// Size multiplier including view-only magnification (e.g. fisheye).
+	pub fn display_size(&self) -> f64 {
+		self.size * self.display.magnification
+	}
+}
+
+/// World-space position and magnification a node is drawn with, after view-only
+/// distortions such as the fisheye lens. Equals the simulated position when no
+/// distortion is active.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DisplayGeometry {
+	pub x: f64,
+	pub y: f64,
+	/// Size multiplier on top of [`NodeInfo::size`]
+	pub magnification: f64,
+}
+
+/// Convert ring fractions into arc angles starting at 12 o'clock, clockwise.
+/// Fractions are normalized when they sum past 1.0; negative fractions are dropped.
+fn ring_arcs(segments: &[RingSegment]) -> Vec<(f64, f64, String)> {
+	let total: f64 = segments.iter().map(|s| s.fraction.max(0.0)).sum();
+	let norm = if total > 1.0 { total } else { 1.0 };
+	let mut start = -PI / 2.0;
+	segments
+		.iter()
+		.filter(|s| s.fraction > 0.0)
+		.map(|s| {
+			let end = start + s.fraction / norm * 2.0 * PI;
+			let arc = (start, end, s.color.clone());
+			start = end;
+			arc
+		})
+		.collect()
+}
+
+/// Center of a node's badge in world-space: on the node's rim at the top-right.
+pub fn badge_center(x: f64, y: f64, node_radius: f64) -> (f64, f64) {
+	let offset = node_radius * std::f64::consts::FRAC_1_SQRT_2;
+	(x + offset, y - offset)
+}
+
+/// Per-edge display metadata attached to each edge in the simulation.
 #[derive(Clone, Debug, Default)]
+pub struct EdgeInfo {
+	/// Edge weight (1.0 = normal)
+	pub weight: f64,
+	/// Whether traffic dots animate along this edge
+	pub traffic: bool,
+	/// Dash-flow speed override (world units per second)
+	pub flow_speed: Option<f64>,
+	/// Whether flow animations run target → source
+	pub flow_reverse: bool,
+	/// Curvature override (0.0 = straight)
+	pub curvature: Option<f64>,
+	/// Number of identical (source, target) links folded into this edge
+	pub count: u32,
+	/// Edge category for per-kind coloring
+	pub kind: Option<String>,
+	/// Hidden by the input data
+	pub hidden: bool,
+	/// Preferred length in graph units
+	pub length: Option<f64>,
+}
+
+impl EdgeInfo {
+	/// Line width multiplier for aggregated edges: grows with the link count, capped
+	/// so heavily duplicated edges stay readable.
+	pub fn width_factor(&self) -> f64 {
+		(1.0 + 0.5 * self.count.saturating_sub(1) as f64).min(4.0)
+	}
+}
+
+/// Pan and zoom transform applied to the entire graph view.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct ViewTransform {
+	/// Screen x of the graph origin, in pixels.
 	pub x: f64,
+	/// Screen y of the graph origin, in pixels.
 	pub y: f64,
 	/// Zoom factor (1.0 = 100%, clamped to 0.1..10.0).
 	pub k: f64,
@@ -60,6 +217,52 @@ pub struct DragState {
 	pub start_y: f64,
 	pub node_start_x: f32,
 	pub node_start_y: f32,
+	/// State of the dragged node at press time, for undo.
+	pub before: Vec<NodeSnapshot>,
+}
+
+/// Furthest a node is nudged by a full [`ForceGraphState::reheat`], in graph units.
+pub const REHEAT_KICK: f64 = 40.0;
+
+/// Reheat after releasing pinned nodes: enough to let them drift back into the
+/// layout without shaking the rest of it.
+const UNPIN_REHEAT: f64 = 0.1;
+
+/// Angle between successive spawned nodes or reheat nudges; spreads them
+/// evenly around the circle.
+const GOLDEN_ANGLE: f32 = 2.399_963;
+
+/// Simulation mass of a node, which only scales its repulsion.
+const BASE_CHARGE: f32 = 10.0;
+
+/// Lightest node mass accepted from data; lighter nodes would overshoot wildly.
+const MIN_MASS: f64 = 0.25;
+
+/// Pointer movement (screen pixels) below which a press-release still counts as a click.
+const CLICK_SLOP: f64 = 4.0;
+
+/// Tracks the current pointer gesture so clicks can be told apart from drags.
+#[derive(Clone, Debug, Default)]
+pub struct GestureState {
+	pub down_x: f64,
+	pub down_y: f64,
+	/// Whether the pointer has moved past [`CLICK_SLOP`] since the button went down.
+	pub moved: bool,
+}
+
+impl GestureState {
+	pub fn begin(&mut self, x: f64, y: f64) {
+		self.down_x = x;
+		self.down_y = y;
+		self.moved = false;
+	}
+
+	pub fn update(&mut self, x: f64, y: f64) {
+		let (dx, dy) = (x - self.down_x, y - self.down_y);
+		if (dx * dx + dy * dy).sqrt() > CLICK_SLOP {
+			self.moved = true;
+		}
+	}
 }
 
 /// Tracks an in-progress canvas pan operation.
@@ -70,6 +273,356 @@ pub struct PanState {
 	pub start_y: f64,
 	pub transform_start_x: f64,
 	pub transform_start_y: f64,
+	/// Spacebar is held: left-drag pans even when it starts on a node.
+	pub space_held: bool,
+}
+
+/// Tracks an in-progress shift-drag box zoom (screen coordinates).
+#[derive(Clone, Debug, Default)]
+pub struct BoxZoomState {
+	pub active: bool,
+	pub start_x: f64,
+	pub start_y: f64,
+	pub current_x: f64,
+	pub current_y: f64,
+}
+
+impl BoxZoomState {
+	/// The dragged rectangle as (left, top, width, height) in screen pixels.
+	pub fn rect(&self) -> (f64, f64, f64, f64) {
+		(
+			self.start_x.min(self.current_x),
+			self.start_y.min(self.current_y),
+			(self.current_x - self.start_x).abs(),
+			(self.current_y - self.start_y).abs(),
+		)
+	}
+}
+
+/// A link being dragged out of a node in edit mode.
+#[derive(Clone, Debug)]
+pub struct LinkDraft {
+	pub source: DefaultNodeIdx,
+	/// Current pointer position (screen coordinates).
+	pub x: f64,
+	pub y: f64,
+}
+
+/// Tracks an in-progress alt-drag lasso as a freeform path (screen coordinates).
+#[derive(Clone, Debug, Default)]
+pub struct LassoState {
+	pub active: bool,
+	pub points: Vec<(f64, f64)>,
+}
+
+impl LassoState {
+	/// Whether a screen point lies inside the lasso path (even-odd rule, path implicitly closed).
+	pub fn contains(&self, x: f64, y: f64) -> bool {
+		let n = self.points.len();
+		if n < 3 {
+			return false;
+		}
+		let mut inside = false;
+		let mut j = n - 1;
+		for i in 0..n {
+			let (xi, yi) = self.points[i];
+			let (xj, yj) = self.points[j];
+			if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+				inside = !inside;
+			}
+			j = i;
+		}
+		inside
+	}
+}
+
+/// The set of currently selected nodes, target of bulk operations like pinning.
+#[derive(Clone, Debug, Default)]
+pub struct SelectionState {
+	nodes: HashSet<DefaultNodeIdx>,
+	/// Whether the selection changed since the last frame was drawn
+	changed: bool,
+}
+
+impl SelectionState {
+	pub fn contains(&self, idx: DefaultNodeIdx) -> bool {
+		self.nodes.contains(&idx)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = DefaultNodeIdx> + '_ {
+		self.nodes.iter().copied()
+	}
+
+	/// Replace the selection with `nodes`.
+	pub fn set(&mut self, nodes: impl IntoIterator<Item = DefaultNodeIdx>) {
+		let nodes: HashSet<_> = nodes.into_iter().collect();
+		self.changed |= nodes != self.nodes;
+		self.nodes = nodes;
+	}
+}
+
+/// A persistent focus on one node's dependency subtree. Unlike hover highlighting
+/// it survives pointer movement and panning until explicitly cleared.
+#[derive(Clone, Debug)]
+pub struct FocusState {
+	pub root: DefaultNodeIdx,
+	/// The root plus every node reachable from it along link direction.
+	pub nodes: HashSet<DefaultNodeIdx>,
+}
+
+/// What a drill-down changed, so a breadcrumb can return to the view before it.
+#[derive(Clone, Debug)]
+pub struct ViewSnapshot {
+	hidden: HashSet<DefaultNodeIdx>,
+	focus: Option<FocusState>,
+	transform: ViewTransform,
+}
+
+/// One step of drill-down navigation (isolate, focus), named after the node drilled into.
+#[derive(Clone, Debug)]
+pub struct Breadcrumb {
+	pub label: String,
+	/// The view as it was before this step.
+	before: ViewSnapshot,
+}
+
+/// Alpha multiplier for nodes and edges outside the focused subtree.
+const FOCUS_DIM_ALPHA: f64 = 0.12;
+
+/// An in-flight animated camera move between two transforms.
+#[derive(Clone, Debug)]
+pub struct CameraTween {
+	pub from: ViewTransform,
+	pub to: ViewTransform,
+	pub elapsed: f64,
+	pub duration: f64,
+	pub easing: Easing,
+}
+
+/// How animated camera moves (fit, focus, zoom buttons and keys) feel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CameraConfig {
+	/// Length of a camera move in seconds (default 0.4). Zero jumps instantly.
+	pub duration: f64,
+	/// Curve applied to camera moves.
+	pub easing: Easing,
+}
+
+impl Default for CameraConfig {
+	fn default() -> Self {
+		Self {
+			duration: 0.4,
+			easing: Easing::EaseInOut,
+		}
+	}
+}
+
+/// How the other animated transitions feel: switching layouts, theme
+/// cross-fades and node recoloring. Zero durations jump straight to the end.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionConfig {
+	/// Seconds nodes take to move into a new layout (default 0.6).
+	pub layout_duration: f64,
+	/// Curve nodes follow into a new layout.
+	pub layout_easing: Easing,
+	/// Seconds a theme change cross-fades for (default 0.3).
+	pub theme_duration: f64,
+	/// Curve applied to theme cross-fades.
+	pub theme_easing: Easing,
+	/// Seconds nodes take to fade to new colors (default 0.6).
+	pub color_duration: f64,
+	/// Curve applied to node color fades.
+	pub color_easing: Easing,
+}
+
+impl Default for TransitionConfig {
+	fn default() -> Self {
+		Self {
+			layout_duration: 0.6,
+			layout_easing: Easing::EaseInOut,
+			theme_duration: 0.3,
+			theme_easing: Easing::EaseInOut,
+			color_duration: 0.6,
+			color_easing: Easing::EaseInOut,
+		}
+	}
+}
+
+/// Advance a transition's `progress` (0..1) by `dt` seconds of one lasting
+/// `duration`.
+pub fn advance_transition(progress: f64, dt: f64, duration: f64) -> f64 {
+	if duration <= 0.0 {
+		1.0
+	} else {
+		(progress + dt / duration).min(1.0)
+	}
+}
+
+/// Colors nodes by a numeric metric instead of by group.
+///
+/// Nodes carrying the metric in their `metrics` map are colored along the ramp;
+/// nodes without it keep their usual color.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeatmapConfig {
+	/// Key into each node's `metrics` map.
+	pub metric: String,
+	/// Color scale, defaulting to the theme's `heat_ramp`.
+	pub ramp: Option<ColorRamp>,
+	/// Value range mapped onto the ramp, defaulting to the metric's min and max.
+	pub domain: Option<(f64, f64)>,
+}
+
+impl HeatmapConfig {
+	/// Heatmap over `metric` with the theme's ramp and an automatic domain.
+	pub fn new(metric: impl Into<String>) -> Self {
+		Self {
+			metric: metric.into(),
+			ramp: None,
+			domain: None,
+		}
+	}
+}
+
+/// What the colorbar legend shows for the active heatmap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeatLegend {
+	/// Metric name shown as the legend title
+	pub metric: String,
+	/// CSS gradient of the ramp
+	pub gradient: String,
+	/// Value at the low end of the ramp
+	pub min: f64,
+	/// Value at the high end of the ramp
+	pub max: f64,
+}
+
+/// What a node's size encodes, on top of labeled nodes being drawn larger.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum NodeSizing {
+	/// Number of links touching the node (default).
+	#[default]
+	Degree,
+	/// A key into each node's `metrics` map. Nodes without it get the middle size.
+	Metric(String),
+	/// Every node the same size.
+	Uniform,
+}
+
+/// What the size legend shows: a title and example (value, screen radius) pairs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SizeLegend {
+	/// Name of the encoded variable
+	pub title: String,
+	/// Representative values with their radius in pixels at 100% zoom
+	pub entries: Vec<(f64, f64)>,
+}
+
+/// A connected component as listed by the component navigator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComponentSummary {
+	/// Label of the component's best-connected node
+	pub label: String,
+	/// Number of nodes
+	pub size: usize,
+}
+
+/// Entity and cache sizes shown in the debug panel, for spotting leaks over
+/// long sessions of live updates.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DebugStats {
+	pub nodes: usize,
+	pub hidden_nodes: usize,
+	pub edges: usize,
+	pub selected: usize,
+	/// Entries across the highlight maps (targets, intensities, rings, hold timers)
+	pub highlight_entries: usize,
+	pub label_widths: usize,
+	pub sprites: usize,
+	pub sprite_bytes: usize,
+	pub css_strings: usize,
+	pub particles: usize,
+	/// Size of the WASM linear memory in bytes
+	pub heap_bytes: usize,
+	/// Frames drawn and skipped as unchanged since the last readout
+	pub frames_drawn: u32,
+	pub frames_skipped: u32,
+}
+
+/// An arrow chip at the edge of the screen pointing toward a selected or
+/// highlighted node that is out of view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OffscreenIndicator {
+	pub node: DefaultNodeIdx,
+	/// Screen position of the chip's center
+	pub x: f64,
+	pub y: f64,
+	/// Direction from the chip toward the node, in radians
+	pub angle: f64,
+}
+
+/// Radius of an off-screen indicator chip in pixels.
+pub const INDICATOR_RADIUS: f64 = 9.0;
+
+/// Distance (pixels) between off-screen indicator chips and the screen edge.
+const INDICATOR_MARGIN: f64 = 16.0;
+
+/// Seconds an indicator for a highlighted node outlasts the highlight, so the
+/// pointer can travel from the hovered node to the chip.
+const INDICATOR_LINGER: f64 = 2.0;
+
+/// Zoom level used when focusing a single node (never zooms out to reach it).
+const FOCUS_ZOOM: f64 = 2.0;
+
+/// Screen padding (pixels) kept around the graph when zooming to fit.
+const FIT_PADDING: f64 = 40.0;
+
+/// How quickly the camera closes in on a followed node (per second).
+const FOLLOW_RATE: f64 = 6.0;
+
+/// On-screen node movement (pixels) below which a frame counts as unchanged.
+const REDRAW_MOTION: f64 = 0.05;
+
+/// Average node movement per simulation step (graph units) below which the
+/// layout counts as settled.
+const SETTLED_ENERGY: f64 = 0.05;
+
+/// Factor over [`SETTLED_ENERGY`] a settled layout has to move by to count as
+/// settling again, so jitter around the threshold doesn't flicker.
+const UNSETTLE_FACTOR: f64 = 4.0;
+
+/// How many of the best-connected nodes the idle showcase cycles through.
+const SHOWCASE_SPOTLIGHTS: usize = 8;
+
+/// Timing of hover highlight transitions.
+///
+/// Speeds are exponential smoothing rates: at speed `s` a transition covers about
+/// 95% of the way in `3 / s` seconds, so higher is snappier.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighlightConfig {
+	/// Rate at which highlights fade in (default 6.0, ~0.5s to 95%).
+	pub fade_in_speed: f64,
+	/// Rate at which highlights fade out (default 4.0, ~0.75s to 95%).
+	pub fade_out_speed: f64,
+	/// Minimum time (seconds) a highlight is held before it may fade out. Prevents
+	/// flashing when the pointer briefly skirts a node's hover zone.
+	pub min_hold_time: f64,
+	/// Curve applied to highlight intensities when drawing.
+	pub easing: Easing,
+}
+
+impl Default for HighlightConfig {
+	fn default() -> Self {
+		Self {
+			fade_in_speed: 6.0,
+			fade_out_speed: 4.0,
+			min_hold_time: 0.12,
+			easing: Easing::EaseInOut,
+		}
+	}
 }
 
 /// Manages smooth highlight transitions with per-node intensity tracking.
@@ -82,9 +635,10 @@ pub struct PanState {
 /// as they approach their target (like spring physics).
 ///
 /// Includes a minimum hold time to prevent flashing when the mouse briefly
-/// skirts the edge of a node's hover zone.
+/// skirts the edge of a node's hover zone. Timing comes from [`HighlightConfig`].
 #[derive(Clone, Debug, Default)]
 pub struct HighlightState {
+	pub config: HighlightConfig,
 	/// Currently hovered node (if any)
 	pub hovered_node: Option<DefaultNodeIdx>,
 	/// Set of nodes that should be highlighted (hovered + neighbors)
@@ -100,17 +654,9 @@ pub struct HighlightState {
 	cached_max: f64,
 }
 
-/// Minimum time (seconds) a highlight must be held before it can fade out.
-/// This prevents flashing when the mouse briefly touches a hover zone.
-const MIN_HOLD_TIME: f64 = 0.12;
-
 impl HighlightState {
 	/// Update the hovered node and recompute the target highlight set.
-	pub fn set_hover(
-		&mut self,
-		node: Option<DefaultNodeIdx>,
-		edges: &[(DefaultNodeIdx, DefaultNodeIdx)],
-	) {
+	pub fn set_hover(&mut self, node: Option<DefaultNodeIdx>, adjacency: &Adjacency) {
 		if self.hovered_node == node {
 			return;
 		}
@@ -122,17 +668,11 @@ impl HighlightState {
 			// Add hovered node
 			self.target_set.insert(idx);
 			// Add neighbors
-			for &(src, tgt) in edges {
-				if src == idx {
-					self.target_set.insert(tgt);
-				} else if tgt == idx {
-					self.target_set.insert(src);
-				}
-			}
+			self.target_set.extend(adjacency.neighbors(idx));
 
 			// Reset hold timers for newly highlighted nodes
 			for &idx in &self.target_set {
-				self.hold_timer.insert(idx, MIN_HOLD_TIME);
+				self.hold_timer.insert(idx, self.config.min_hold_time);
 			}
 		}
 	}
@@ -142,14 +682,9 @@ impl HighlightState {
 	/// Exponential smoothing: value += (target - value) * (1 - e^(-speed * dt))
 	/// This creates natural ease-out behavior where animation slows as it approaches target.
 	pub fn tick(&mut self, dt: f64) {
-		// Smoothing factors - higher = faster response
-		// At 60fps with speed=8: reaches ~63% in ~2 frames, ~95% in ~6 frames (~100ms)
-		// At 60fps with speed=5: reaches ~63% in ~4 frames, ~95% in ~12 frames (~200ms)
-		const FADE_IN_SPEED: f64 = 6.0; // ~150ms to 95%
-		const FADE_OUT_SPEED: f64 = 4.0; // ~250ms to 95%
-
-		let fade_in_factor = 1.0 - (-FADE_IN_SPEED * dt).exp();
-		let fade_out_decay = (-FADE_OUT_SPEED * dt).exp();
+		// Smoothing factors - higher speed = faster response
+		let fade_in_factor = 1.0 - (-self.config.fade_in_speed * dt).exp();
+		let fade_out_decay = (-self.config.fade_out_speed * dt).exp();
 
 		// Animate nodes in target set (fade in)
 		for &idx in &self.target_set {
@@ -236,6 +771,28 @@ impl HighlightState {
 	pub fn max_intensity(&self) -> f64 {
 		self.cached_max
 	}
+
+	/// Whether any intensity is still moving towards its target (or held before fading).
+	pub fn is_animating(&self) -> bool {
+		let settled = |intensity: Option<&f64>, target: bool| match intensity {
+			Some(&v) => target && v > 0.995,
+			None => !target,
+		};
+		self.target_set
+			.iter()
+			.any(|idx| !settled(self.node_intensity.get(idx), true))
+			|| self
+				.node_intensity
+				.keys()
+				.any(|idx| !self.target_set.contains(idx))
+			|| self
+				.hovered_node
+				.is_some_and(|idx| !settled(self.hover_ring_intensity.get(&idx), true))
+			|| self
+				.hover_ring_intensity
+				.keys()
+				.any(|&idx| self.hovered_node != Some(idx))
+	}
 }
 
 /// Core graph state combining physics simulation with interaction and highlight tracking.
@@ -244,16 +801,261 @@ impl HighlightState {
 /// animation loop. The `tick` method advances the physics simulation and
 /// animates highlight intensities.
 pub struct ForceGraphState {
-	pub graph: ForceGraph<NodeInfo, ()>,
+	pub graph: ForceGraph<NodeInfo, EdgeInfo>,
 	pub transform: ViewTransform,
 	pub drag: DragState,
 	pub pan: PanState,
+	pub gesture: GestureState,
+	pub box_zoom: BoxZoomState,
+	pub lasso: LassoState,
+	pub selection: SelectionState,
+	pub focus: Option<FocusState>,
+	/// Drill-down trail, outermost first.
+	pub breadcrumbs: Vec<Breadcrumb>,
+	pub camera: Option<CameraTween>,
+	pub camera_config: CameraConfig,
+	/// How layout switches, theme fades and recoloring animate
+	pub transitions: TransitionConfig,
+	/// Node the camera keeps centered while it moves; panning disengages it.
+	pub follow: Option<DefaultNodeIdx>,
+	/// Idle showcase: camera drift and spotlights after a period without input.
+	pub showcase: Showcase,
 	pub highlight: HighlightState,
 	pub width: f64,
 	pub height: f64,
 	pub animation_running: bool,
 	pub flow_time: f64,
+	pub fisheye: FisheyeLens,
+	/// Last known pointer position over the canvas (screen coordinates).
+	pub pointer: Option<(f64, f64)>,
+	/// Draw node labels; toggled by the `L` shortcut.
+	pub show_labels: bool,
+	/// Labeled nodes, most important first, set by [`rank_labels`](Self::rank_labels)
+	pub label_order: Vec<DefaultNodeIdx>,
+	/// Labels shown at any zoom level, counted from the top of `label_order`
+	pub top_labels: usize,
+	/// Snap-to-grid spacing in graph units; `None` disables snapping.
+	pub grid: Option<f64>,
+	/// Draw nodes on half-pixel boundaries once the layout is still, so static
+	/// edges don't shimmer.
+	pub crisp: bool,
+	/// Extra forces on top of the simulation
+	pub physics: PhysicsConfig,
+	/// Whether any link has a preferred length, so steps pull on links at all
+	sized_links: bool,
+	/// Simulation and camera of the experimental 3D mode, while it is on
+	pub space: Option<Space>,
+	/// Edit mode: clicks create nodes, node drags create links.
+	pub editable: bool,
+	/// Draw links the input data marks as hidden (set by "Show all")
+	pub show_hidden_links: bool,
+	pub link_draft: Option<LinkDraft>,
+	/// Label widths, measured in idle time
+	pub labels: LabelMetrics,
+	/// Pre-rendered label sprites, drawn in idle time
+	pub sprites: LabelSprites,
+	/// Nodes whose resting labels are shown, placed in idle time
+	pub placed_labels: PlacedLabels,
+	/// Bumped whenever labels may need placing anew: nodes came, went, were
+	/// hidden or revealed, or moved on screen
+	label_generation: u64,
+	/// Canvas pixels per CSS pixel; raised while rendering for print
+	pub pixel_ratio: f64,
+	/// Something visible changed outside the animation loop; cleared once drawn.
+	pub dirty: bool,
+	/// Layers with changes confined to them, on top of `dirty`
+	dirty_layers: Layers,
+	/// Largest on-screen node movement in the last prepared frame, in pixels
+	motion: f64,
+	/// Transform the last drawn frame used
+	drawn_transform: Option<ViewTransform>,
 	edges: Vec<(DefaultNodeIdx, DefaultNodeIdx)>,
+	/// Neighbor index over `edges`, kept in step with it
+	adjacency: Adjacency,
+	history: History,
+	/// Host-supplied group colors, before the data payload's own
+	cluster_colors: HashMap<String, String>,
+	/// Group colors in effect for the current data
+	group_colors: HashMap<String, String>,
+	/// Generated colors for the remaining groups
+	auto_colors: HashMap<String, String>,
+	/// The input data as last applied, before collapsing groups
+	source: GraphData,
+	/// Groups shown as a single meta-node
+	pub collapsed: HashSet<String>,
+	/// Progress of the current recolor transition (1.0 = settled)
+	color_fade: f64,
+	/// Progress of the switch into the current layout, 0.0 to 1.0
+	layout_fade: f64,
+	/// Active metric heatmap, applied by [`recolor`](Self::recolor)
+	pub heatmap: Option<HeatmapConfig>,
+	/// What node size encodes, applied by [`apply_sizing`](Self::apply_sizing)
+	pub sizing: NodeSizing,
+	/// Positional constraints, applied by [`apply_layout`](Self::apply_layout)
+	pub layout: Layout,
+	/// Swimlanes of the active layout, left to right
+	pub lanes: Vec<Lane>,
+	/// Time axis of the timeline layout
+	pub time_scale: Option<TimeScale>,
+	/// Highlighted nodes that get off-screen indicators, with seconds left
+	indicator_holds: HashMap<DefaultNodeIdx, f64>,
+	/// Off-screen indicator chips as of the last prepared frame
+	indicators: Vec<OffscreenIndicator>,
+	/// Average node movement in the last simulation step, in graph units
+	energy: f64,
+	/// Highest energy since the layout last started settling
+	energy_peak: f64,
+	/// The layout has come to rest
+	settled: bool,
+	/// Nodes the simulation moves while a local disturbance settles; `None`
+	/// moves them all
+	hot_nodes: Option<HashSet<DefaultNodeIdx>>,
+	/// Node positions (x, y, z) before the current step, reused between steps
+	step_positions: Vec<(f32, f32, f32)>,
+}
+
+/// Normalized sizing value in 0..=1; square-rooted for softer scaling.
+fn size_factor(value: f64, lo: f64, hi: f64) -> f64 {
+	if hi > lo {
+		((value - lo) / (hi - lo)).clamp(0.0, 1.0).sqrt()
+	} else {
+		0.5
+	}
+}
+
+/// Size multiplier for a node. Labeled nodes are more important and drawn larger.
+fn node_size(labeled: bool, factor: f64) -> f64 {
+	if labeled {
+		1.4 + 0.6 * factor // labeled: 1.4x to 2.0x
+	} else {
+		0.7 + 0.5 * factor // unlabeled: 0.7x to 1.2x
+	}
+}
+
+/// A node's color before any heatmap: explicit color > cluster color > palette
+/// color for its group > palette fallback by index.
+fn base_color(
+	node: &GraphNode,
+	index: usize,
+	theme: &Theme,
+	cluster_colors: &HashMap<String, String>,
+	auto_colors: &HashMap<String, String>,
+) -> String {
+	node.color.clone().unwrap_or_else(|| {
+		node.group
+			.as_ref()
+			.and_then(|g| {
+				cluster_colors
+					.get(g)
+					.or_else(|| auto_colors.get(g))
+					.cloned()
+			})
+			.unwrap_or_else(|| theme.palette.get(index).to_css_rgb())
+	})
+}
+
+/// Build the simulation graph for `data`, laying nodes out on a circle around the
+/// viewport center. Returns the graph and its edges as (source, target) pairs.
+fn build_graph(
+	data: &GraphData,
+	width: f64,
+	height: f64,
+	theme: &Theme,
+	cluster_colors: &HashMap<String, String>,
+	auto_colors: &HashMap<String, String>,
+) -> (
+	ForceGraph<NodeInfo, EdgeInfo>,
+	Vec<(DefaultNodeIdx, DefaultNodeIdx)>,
+) {
+	let mut graph = ForceGraph::new(SimulationParameters {
+		force_charge: 150.0,
+		force_spring: 0.05,
+		force_max: 100.0,
+		node_speed: 3000.0,
+		damping_factor: 0.9,
+	});
+	let mut id_to_idx = HashMap::new();
+	let mut edges = Vec::new();
+
+	for (i, node) in data.nodes.iter().enumerate() {
+		let color = base_color(node, i, theme, cluster_colors, auto_colors);
+		let angle = (i as f64) * 2.0 * PI / data.nodes.len() as f64;
+		let (x, y) = (
+			(width / 2.0 + 100.0 * angle.cos()) as f32,
+			(height / 2.0 + 100.0 * angle.sin()) as f32,
+		);
+
+		let idx = graph.add_node(NodeData {
+			x,
+			y,
+			mass: BASE_CHARGE,
+			is_anchor: false,
+			user_data: NodeInfo {
+				id: node.id.clone(),
+				label: node.label.clone(),
+				fill: Color::parse(&color),
+				fade_from: None,
+				color,
+				// Set by ForceGraphState::apply_sizing once edges are in place
+				size: 1.0,
+				badge: node.badge.as_ref().map(|b| b.display_text()),
+				badge_color: node.badge_color.clone(),
+				ring: ring_arcs(&node.ring),
+				pulse: node.pulse.map(|p| p.intensity()).unwrap_or(0.0),
+				display: DisplayGeometry {
+					x: x as f64,
+					y: y as f64,
+					magnification: 1.0,
+				},
+				hidden: node.hidden,
+				opacity: node.opacity.unwrap_or(1.0).clamp(0.0, 1.0),
+				border: theme
+					.node
+					.border_for(node.group.as_deref())
+					.map(|b| (b.color, b.width)),
+				constraint: NodeConstraint::default(),
+				mass: node.mass.map_or(1.0, |m| m.max(MIN_MASS)),
+				z: 0.0,
+				layer: node.layer.unwrap_or(0),
+				spec: node.clone(),
+			},
+		});
+		id_to_idx.insert(node.id.clone(), idx);
+	}
+
+	// Repeated (source, target) pairs collapse into one edge carrying a count; the
+	// first occurrence's attributes win
+	let mut links: Vec<((DefaultNodeIdx, DefaultNodeIdx), EdgeInfo)> = Vec::new();
+	let mut link_index: HashMap<(DefaultNodeIdx, DefaultNodeIdx), usize> = HashMap::new();
+	for link in &data.links {
+		if let (Some(&src), Some(&tgt)) = (id_to_idx.get(&link.source), id_to_idx.get(&link.target))
+		{
+			if let Some(&i) = link_index.get(&(src, tgt)) {
+				links[i].1.count += 1;
+				continue;
+			}
+			link_index.insert((src, tgt), links.len());
+			links.push((
+				(src, tgt),
+				EdgeInfo {
+					weight: link.weight.unwrap_or(1.0).max(0.0),
+					traffic: link.traffic,
+					flow_speed: link.flow_speed,
+					flow_reverse: link.flow_reverse,
+					curvature: link.curvature,
+					kind: link.kind.clone(),
+					hidden: link.hidden,
+					length: link.length.filter(|l| *l > 0.0),
+					count: 1,
+				},
+			));
+		}
+	}
+	for ((src, tgt), info) in links {
+		graph.add_edge(src, tgt, EdgeData { user_data: info });
+		edges.push((src, tgt));
+	}
+	(graph, edges)
 }
 
 impl ForceGraphState {
@@ -268,77 +1070,23 @@ impl ForceGraphState {
 		theme: &Theme,
 		cluster_colors: &HashMap<String, String>,
 	) -> Self {
-		let mut graph = ForceGraph::new(SimulationParameters {
-			force_charge: 150.0,
-			force_spring: 0.05,
-			force_max: 100.0,
-			node_speed: 3000.0,
-			damping_factor: 0.9,
-		});
-		let mut id_to_idx = HashMap::new();
-		let mut edges = Vec::new();
-
-		// Count edges per node for importance calculation
-		let mut edge_counts: HashMap<&String, usize> = HashMap::new();
-		for link in &data.links {
-			*edge_counts.entry(&link.source).or_insert(0) += 1;
-			*edge_counts.entry(&link.target).or_insert(0) += 1;
-		}
-		let max_edges = edge_counts.values().copied().max().unwrap_or(1).max(1);
-
-		for (i, node) in data.nodes.iter().enumerate() {
-			// Get color from: explicit color > cluster color > palette fallback
-			let color = node.color.clone().unwrap_or_else(|| {
-				node.group
-					.as_ref()
-					.and_then(|g| cluster_colors.get(g).cloned())
-					.unwrap_or_else(|| theme.palette.get(i).to_css_rgb())
-			});
-			let angle = (i as f64) * 2.0 * PI / data.nodes.len() as f64;
-			let (x, y) = (
-				(width / 2.0 + 100.0 * angle.cos()) as f32,
-				(height / 2.0 + 100.0 * angle.sin()) as f32,
-			);
-
-			// Calculate node importance/size based on:
-			// - Having a label (more important)
-			// - Number of connections (more connected = larger)
-			let has_label = node.label.is_some();
-			let node_edges = edge_counts.get(&node.id).copied().unwrap_or(0);
-			let edge_factor = (node_edges as f64 / max_edges as f64).sqrt(); // sqrt for softer scaling
-
-			let size = if has_label {
-				1.4 + 0.6 * edge_factor // labeled: 1.4x to 2.0x
-			} else {
-				0.7 + 0.5 * edge_factor // unlabeled: 0.7x to 1.2x
-			};
-
-			let idx = graph.add_node(NodeData {
-				x,
-				y,
-				mass: 10.0,
-				is_anchor: false,
-				user_data: NodeInfo {
-					label: node.label.clone(),
-					color,
-					size,
-				},
-			});
-			id_to_idx.insert(node.id.clone(), idx);
-		}
-
-		for link in &data.links {
-			if let (Some(&src), Some(&tgt)) =
-				(id_to_idx.get(&link.source), id_to_idx.get(&link.target))
-			{
-				graph.add_edge(src, tgt, EdgeData::default());
-				edges.push((src, tgt));
-			}
-		}
+		let colors = group_colors(cluster_colors, data);
+		let auto_colors = auto_group_colors(data, &colors, theme);
+		let (graph, edges) = build_graph(data, width, height, theme, &colors, &auto_colors);
 
-		Self {
+		let mut state = Self {
 			graph,
+			adjacency: Adjacency::new(&edges),
 			edges,
+			sized_links: data
+				.links
+				.iter()
+				.any(|link| link.length.is_some_and(|l| l > 0.0)),
+			history: History::default(),
+			cluster_colors: cluster_colors.clone(),
+			group_colors: colors,
+			auto_colors,
+			heatmap: None,
 			transform: ViewTransform {
 				x: width / 2.0,
 				y: height / 2.0,
@@ -346,52 +1094,2159 @@ impl ForceGraphState {
 			},
 			drag: DragState::default(),
 			pan: PanState::default(),
+			gesture: GestureState::default(),
+			box_zoom: BoxZoomState::default(),
+			lasso: LassoState::default(),
+			selection: SelectionState::default(),
+			focus: None,
+			breadcrumbs: Vec::new(),
+			camera: None,
+			follow: None,
+			showcase: Showcase::default(),
+			labels: LabelMetrics::default(),
+			sprites: LabelSprites::default(),
+			placed_labels: PlacedLabels::default(),
+			label_generation: 0,
+			pixel_ratio: 1.0,
+			dirty: true,
+			dirty_layers: Layers::default(),
+			motion: 0.0,
+			drawn_transform: None,
+			camera_config: CameraConfig::default(),
+			transitions: TransitionConfig::default(),
 			highlight: HighlightState::default(),
 			width,
 			height,
 			animation_running: true,
 			flow_time: 0.0,
+			fisheye: FisheyeLens::default(),
+			pointer: None,
+			show_labels: true,
+			label_order: Vec::new(),
+			top_labels: 10,
+			grid: None,
+			crisp: false,
+			physics: PhysicsConfig::default(),
+			space: None,
+			editable: false,
+			show_hidden_links: false,
+			color_fade: 1.0,
+			layout_fade: 1.0,
+			source: data.clone(),
+			collapsed: HashSet::new(),
+			link_draft: None,
+			sizing: NodeSizing::default(),
+			layout: Layout::default(),
+			lanes: Vec::new(),
+			time_scale: None,
+			indicator_holds: HashMap::new(),
+			indicators: Vec::new(),
+			energy: 0.0,
+			energy_peak: 0.0,
+			settled: false,
+			hot_nodes: None,
+			step_positions: Vec::new(),
+		};
+		state.apply_sizing();
+		state.rank_labels();
+		state
+	}
+
+	/// Replace the graph with `data` in place. Nodes that survive (by ID) keep their
+	/// position, pin and visibility; new nodes appear next to an existing neighbor
+	/// so the graph visibly grows rather than re-laying out from scratch.
+	pub fn apply_data(&mut self, data: &GraphData, theme: &Theme) {
+		self.source = data.clone();
+		let view = view_model::collapse(&self.source, &self.collapsed);
+		self.rebuild(&view, theme);
+	}
+
+	/// Collapse `group` into a single meta-node, or expand it again.
+	pub fn set_collapsed(&mut self, group: &str, collapsed: bool, theme: &Theme) {
+		let changed = if collapsed {
+			self.collapsed.insert(group.to_string())
+		} else {
+			self.collapsed.remove(group)
+		};
+		if changed {
+			let view = view_model::collapse(&self.source, &self.collapsed);
+			self.rebuild(&view, theme);
 		}
 	}
 
-	pub fn screen_to_graph(&self, sx: f64, sy: f64) -> (f64, f64) {
-		(
-			(sx - self.transform.x) / self.transform.k,
-			(sy - self.transform.y) / self.transform.k,
-		)
+	/// Swap in a freshly built simulation for `data`, carrying over what survives.
+	/// Collapsing spawns a meta-node where its members were; expanding spawns the
+	/// members where the meta-node was.
+	fn rebuild(&mut self, data: &GraphData, theme: &Theme) {
+		self.dirty = true;
+		self.group_colors = group_colors(&self.cluster_colors, data);
+		self.auto_colors = auto_group_colors(data, &self.group_colors, theme);
+		let (mut graph, edges) = build_graph(
+			data,
+			self.width,
+			self.height,
+			theme,
+			&self.group_colors,
+			&self.auto_colors,
+		);
+
+		let mut old: HashMap<String, (f32, f32, bool, bool, Color)> = HashMap::new();
+		let mut old_depth: HashMap<String, f32> = HashMap::new();
+		self.graph.visit_nodes(|node| {
+			let info = &node.data.user_data;
+			old.insert(
+				info.id.clone(),
+				(
+					node.x(),
+					node.y(),
+					node.data.is_anchor,
+					info.hidden,
+					info.fill,
+				),
+			);
+			old_depth.insert(info.id.clone(), info.z);
+		});
+		let mut placed: HashMap<DefaultNodeIdx, (f32, f32)> = HashMap::new();
+		let mut new_ids: HashMap<String, DefaultNodeIdx> = HashMap::new();
+		graph.visit_nodes_mut(|node| {
+			let idx = node.index();
+			let id = node.data.user_data.id.clone();
+			if let Some(&(x, y, anchor, hidden, fill)) = old.get(&id) {
+				node.data.x = x;
+				node.data.y = y;
+				node.data.is_anchor = anchor;
+				node.data.user_data.hidden = hidden;
+				// Survivors recolor from what they showed, not from scratch
+				node.data.user_data.fill = fill;
+				node.data.user_data.z = old_depth[&id];
+				placed.insert(idx, (x, y));
+			}
+			new_ids.insert(id, idx);
+		});
+		let mut centroids: HashMap<String, (f32, f32, f32)> = HashMap::new();
+		self.graph.visit_nodes(|node| {
+			if let Some(group) = &node.data.user_data.spec.group {
+				let c = centroids.entry(group.clone()).or_default();
+				*c = (c.0 + node.x(), c.1 + node.y(), c.2 + 1.0);
+			}
+		});
+		let mut spawn: HashMap<DefaultNodeIdx, (f32, f32)> = HashMap::new();
+		graph.visit_nodes(|node| {
+			if placed.contains_key(&node.index()) {
+				return;
+			}
+			let info = &node.data.user_data;
+			let hint = match view_model::meta_group(&info.id) {
+				Some(group) => centroids.get(group).map(|&(x, y, n)| (x / n, y / n)),
+				None => info.spec.group.as_ref().and_then(|group| {
+					old.get(&view_model::meta_id(group))
+						.map(|&(x, y, ..)| (x, y))
+				}),
+			};
+			if let Some(pos) = hint {
+				spawn.insert(node.index(), pos);
+			}
+		});
+		for &(a, b) in &edges {
+			for (from, to) in [(a, b), (b, a)] {
+				if let Some(&pos) = placed.get(&from)
+					&& !placed.contains_key(&to)
+				{
+					spawn.entry(to).or_insert(pos);
+				}
+			}
+		}
+		graph.visit_nodes_mut(|node| {
+			if let Some(&(x, y)) = spawn.get(&node.index()) {
+				// Golden-angle offsets keep siblings spawned from one parent apart
+				let angle = node.index().index() as f32 * GOLDEN_ANGLE;
+				node.data.x = x + 30.0 * angle.cos();
+				node.data.y = y + 30.0 * angle.sin();
+			}
+		});
+
+		let remap = |idx: DefaultNodeIdx, graph: &ForceGraph<NodeInfo, EdgeInfo>| {
+			graph
+				.get_graph()
+				.node_weight(idx)
+				.and_then(|n| new_ids.get(&n.data.user_data.id).copied())
+		};
+		let selection: Vec<_> = self
+			.selection
+			.iter()
+			.filter_map(|idx| remap(idx, &self.graph))
+			.collect();
+		let focus_root = self.focus.as_ref().and_then(|f| remap(f.root, &self.graph));
+		self.follow = self.follow.and_then(|idx| remap(idx, &self.graph));
+		// Indices are stale; the showcase picks its spotlights again
+		self.showcase.spotlights.clear();
+		self.indicator_holds.clear();
+		self.indicators.clear();
+		// New and moved nodes start the layout settling again
+		(self.settled, self.energy_peak, self.hot_nodes) = (false, 0.0, None);
+
+		if let Some(space) = &mut self.space {
+			space.reset_motion();
+		}
+		self.graph = graph;
+		self.sized_links = data
+			.links
+			.iter()
+			.any(|link| link.length.is_some_and(|l| l > 0.0));
+		self.adjacency = Adjacency::new(&edges);
+		self.edges = edges;
+		self.selection.set(selection);
+		self.focus = None;
+		if let Some(root) = focus_root {
+			self.focus_subtree(root);
+		}
+		self.highlight = HighlightState {
+			config: self.highlight.config.clone(),
+			..HighlightState::default()
+		};
+		self.breadcrumbs.clear();
+		self.history.clear();
+		self.drag = DragState::default();
+		self.link_draft = None;
+		self.apply_sizing();
+		self.apply_charge();
+		self.rank_labels();
+		self.apply_layout();
+		self.recolor(theme);
 	}
 
-	pub fn node_at_position(
-		&self,
-		sx: f64,
-		sy: f64,
-		config: &ScaleConfig,
-	) -> Option<DefaultNodeIdx> {
-		let (gx, gy) = self.screen_to_graph(sx, sy);
-		let scale = ScaledValues::new(config, self.transform.k);
-		let mut found = None;
+	/// Recompute each node's positional constraint from the current [`Layout`]
+	/// and move nodes onto them.
+	pub fn apply_layout(&mut self) {
+		self.dirty = true;
+		self.lanes = match &self.layout {
+			Layout::Swimlanes { order } => {
+				let mut groups = Vec::new();
+				self.graph
+					.visit_nodes(|node| groups.push(node.data.user_data.spec.group.clone()));
+				layout::swimlanes(order, groups.iter().map(Option::as_deref), self.width / 2.0)
+			}
+			_ => Vec::new(),
+		};
+		let mut constraints: HashMap<DefaultNodeIdx, NodeConstraint> = HashMap::new();
 		self.graph.visit_nodes(|node| {
-			let (dx, dy) = (node.x() as f64 - gx, node.y() as f64 - gy);
-			let node_hit_radius = scale.hit_radius * node.data.user_data.size;
-			if (dx * dx + dy * dy).sqrt() < node_hit_radius {
-				found = Some(node.index());
+			let group = node.data.user_data.spec.group.as_ref();
+			if let Some(lane) = self.lanes.iter().find(|lane| lane.group.as_ref() == group) {
+				constraints.insert(node.index(), lane.constraint());
 			}
 		});
-		found
+		let axis_layout = match &self.layout {
+			Layout::Axis {
+				axis,
+				field,
+				length,
+			} => Some((*axis, field.clone(), *length)),
+			Layout::Timeline { length } => Some((Axis::X, AxisField::Timestamp, *length)),
+			_ => None,
+		};
+		self.time_scale = None;
+		if let Some((axis, field, length)) = axis_layout {
+			let values = self.axis_values(&field);
+			let lo = values.values().copied().fold(f64::INFINITY, f64::min);
+			let hi = values.values().copied().fold(f64::NEG_INFINITY, f64::max);
+			let center = match axis {
+				Axis::X => self.width / 2.0,
+				Axis::Y => self.height / 2.0,
+			};
+			if matches!(self.layout, Layout::Timeline { .. }) && lo <= hi {
+				self.time_scale = Some(TimeScale {
+					start: lo,
+					end: hi,
+					left: center - length / 2.0,
+					right: center + length / 2.0,
+				});
+			}
+			for (idx, v) in values {
+				let t = if hi > lo {
+					(v - lo) / (hi - lo) - 0.5
+				} else {
+					0.0
+				};
+				let target = Some(AxisConstraint::target(center + t * length));
+				let constraint = match axis {
+					Axis::X => NodeConstraint { x: target, y: None },
+					Axis::Y => NodeConstraint { x: None, y: target },
+				};
+				constraints.insert(idx, constraint);
+			}
+		}
+		self.graph.visit_nodes_mut(|node| {
+			node.data.user_data.constraint =
+				constraints.get(&node.index()).copied().unwrap_or_default();
+		});
+		self.constrain();
 	}
 
-	pub fn set_hover(&mut self, node: Option<DefaultNodeIdx>) {
-		self.highlight.set_hover(node, &self.edges);
+	/// Each node's value for an axis layout's `field`, where it has one.
+	fn axis_values(&self, field: &AxisField) -> HashMap<DefaultNodeIdx, f64> {
+		match field {
+			AxisField::Depth => {
+				let mut nodes = Vec::new();
+				self.graph.visit_nodes(|node| nodes.push(node.index()));
+				layout::depths(nodes, self.adjacency())
+					.into_iter()
+					.map(|(idx, depth)| (idx, depth as f64))
+					.collect()
+			}
+			AxisField::Timestamp => {
+				let mut values = HashMap::new();
+				self.graph.visit_nodes(|node| {
+					if let Some(t) = node.data.user_data.spec.timestamp
+						&& t.is_finite()
+					{
+						values.insert(node.index(), t);
+					}
+				});
+				values
+			}
+			AxisField::Metric(metric) => {
+				let mut values = HashMap::new();
+				self.graph.visit_nodes(|node| {
+					if let Some(&v) = node.data.user_data.spec.metrics.get(metric)
+						&& v.is_finite()
+					{
+						values.insert(node.index(), v);
+					}
+				});
+				values
+			}
+		}
 	}
 
-	pub fn tick(&mut self, dt: f32) {
-		self.graph.update(dt);
-		self.flow_time += dt as f64;
-		self.highlight.tick(dt as f64);
+	/// Move every node back within its layout constraint: all the way, or
+	/// while switching layouts, as far as the switch has eased.
+	fn constrain(&mut self) {
+		let w = self.transitions.layout_easing.apply(self.layout_fade) as f32;
+		self.graph.visit_nodes_mut(|node| {
+			let constraint = node.data.user_data.constraint;
+			if let Some(x) = constraint.x {
+				node.data.x += (x.apply(node.data.x) - node.data.x) * w;
+			}
+			if let Some(y) = constraint.y {
+				node.data.y += (y.apply(node.data.y) - node.data.y) * w;
+			}
+		});
+	}
+
+	/// Advance a layout switch by `dt` seconds. The simulation step applies the
+	/// constraints; a paused layout still moves into its new shape.
+	pub fn tick_layout(&mut self, dt: f64) {
+		if self.layout_fade >= 1.0 {
+			return;
+		}
+		self.layout_fade =
+			advance_transition(self.layout_fade, dt, self.transitions.layout_duration);
+		if !self.animation_running {
+			self.constrain();
+		}
+	}
+
+	/// Scale back this step's movement of heavy nodes, and boost light ones, given
+	/// every node's position before the step in visiting order. The simulation's
+	/// own mass only scales repulsion.
+	fn apply_inertia(&mut self, before: &[(f32, f32, f32)]) {
+		let mut i = 0;
+		self.graph.visit_nodes_mut(|node| {
+			let mass = node.data.user_data.mass as f32;
+			if let Some(&(x, y, z)) = before.get(i)
+				&& mass != 1.0
+				&& !node.data.is_anchor
+			{
+				let d = &mut node.data;
+				d.x = x + (d.x - x) / mass;
+				d.y = y + (d.y - y) / mass;
+				d.user_data.z = z + (d.user_data.z - z) / mass;
+			}
+			i += 1;
+		});
+	}
+
+	/// Put nodes outside [`hot_nodes`](Self::heat_around) back where they were
+	/// before the step, given every node's position in visiting order. Runs
+	/// before the extra forces, which leave held nodes alone.
+	fn hold_cold(&mut self, before: &[(f32, f32, f32)]) {
+		let Some(hot) = &self.hot_nodes else {
+			return;
+		};
+		let mut i = 0;
+		self.graph.visit_nodes_mut(|node| {
+			if let Some(&(x, y, z)) = before.get(i)
+				&& !hot.contains(&node.index())
+			{
+				(node.data.x, node.data.y, node.data.user_data.z) = (x, y, z);
+			}
+			i += 1;
+		});
+	}
+
+	/// Let the simulation move only the nodes near `idx` until the layout
+	/// settles again, per [`PhysicsConfig::drag_hops`]. Applies when the layout
+	/// is at rest, or extends an earlier local disturbance.
+	pub fn heat_around(&mut self, idx: DefaultNodeIdx) {
+		let Some(hops) = self.physics.drag_hops else {
+			return;
+		};
+		match &self.hot_nodes {
+			Some(hot) if hot.contains(&idx) => return,
+			None if !self.settled => return,
+			_ => {}
+		}
+		let near = self.adjacency.within(idx, hops);
+		self.hot_nodes.get_or_insert_default().extend(near);
+		// Held until the disturbance has settled
+		if self.settled {
+			(self.settled, self.energy_peak) = (false, 0.0);
+		}
+	}
+
+	/// Apply the extra forces configured in `physics` to every free node not
+	/// held by a local disturbance.
+	fn apply_forces(&mut self) {
+		if !self.sized_links && !self.physics.is_active() {
+			return;
+		}
+		// Edges with a preferred length pull or push their ends toward it, in
+		// depth too while 3D mode is on
+		let mut stretch: HashMap<DefaultNodeIdx, (f64, f64, f64)> = HashMap::new();
+		let stiffness = self.physics.length_stiffness.clamp(0.0, 1.0);
+		if self.sized_links {
+			self.graph.visit_edges(|n1, n2, edge| {
+				let Some(length) = edge.user_data.length else {
+					return;
+				};
+				let (dx, dy, dz) = (
+					(n2.x() - n1.x()) as f64,
+					(n2.y() - n1.y()) as f64,
+					(n2.data.user_data.z - n1.data.user_data.z) as f64,
+				);
+				let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+				if distance < 1e-6 {
+					return;
+				}
+				let f = (distance - length) / distance * stiffness / 2.0;
+				let a = stretch.entry(n1.index()).or_default();
+				(a.0, a.1, a.2) = (a.0 + dx * f, a.1 + dy * f, a.2 + dz * f);
+				let b = stretch.entry(n2.index()).or_default();
+				(b.0, b.1, b.2) = (b.0 - dx * f, b.1 - dy * f, b.2 - dz * f);
+			});
+		}
+		if stretch.is_empty() && !self.physics.is_active() {
+			return;
+		}
+		let (mut sum_x, mut sum_y, mut free) = (0.0, 0.0, 0);
+		self.graph.visit_nodes(|node| {
+			if !node.data.is_anchor {
+				sum_x += node.x() as f64;
+				sum_y += node.y() as f64;
+				free += 1;
+			}
+		});
+		if free == 0 {
+			return;
+		}
+		let physics = &self.physics;
+		let hot = self.hot_nodes.as_ref();
+		// Centering waits out a local disturbance, which would shift only the
+		// nodes let move; held nodes take no force until it has settled
+		let (shift_x, shift_y) = match hot {
+			Some(_) => (0.0, 0.0),
+			None => physics.centering_shift((sum_x / free as f64, sum_y / free as f64)),
+		};
+		self.graph.visit_nodes_mut(|node| {
+			if node.data.is_anchor || hot.is_some_and(|hot| !hot.contains(&node.index())) {
+				return;
+			}
+			let (dx, dy, dz) = stretch.get(&node.index()).copied().unwrap_or_default();
+			let (x, y) = physics.bound(
+				node.data.x as f64 + dx + shift_x,
+				node.data.y as f64 + dy + shift_y,
+			);
+			(node.data.x, node.data.y) = (x as f32, y as f32);
+			node.data.user_data.z += dz as f32;
+		});
+	}
+
+	/// Number of links touching each node, counting aggregated duplicates.
+	fn degrees(&self) -> HashMap<DefaultNodeIdx, f64> {
+		let mut degrees: HashMap<DefaultNodeIdx, f64> = HashMap::new();
+		self.graph.visit_edges(|n1, n2, edge| {
+			let count = edge.user_data.count.max(1) as f64;
+			*degrees.entry(n1.index()).or_default() += count;
+			*degrees.entry(n2.index()).or_default() += count;
+		});
+		degrees
+	}
+
+	/// Order labeled nodes by label priority: the input's `label_priority`, or
+	/// else the node's degree. Ties keep input order. Run whenever nodes come
+	/// or go, so it also drops cached metrics and sprites of labels now gone.
+	pub fn rank_labels(&mut self) {
+		let degrees = self.degrees();
+		let mut ranked = Vec::new();
+		self.graph.visit_nodes(|node| {
+			let info = &node.data.user_data;
+			if info.label.is_some() {
+				let degree = degrees.get(&node.index()).copied().unwrap_or(0.0);
+				ranked.push((node.index(), info.spec.label_priority.unwrap_or(degree)));
+			}
+		});
+		let graph = self.graph.get_graph();
+		let live = ranked
+			.iter()
+			.filter_map(|&(idx, _)| graph.node_weight(idx)?.data.user_data.label.as_deref())
+			.collect();
+		self.labels.retain(&live);
+		self.sprites.retain(&live);
+		self.label_generation += 1;
+		ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+		self.label_order = ranked.into_iter().map(|(idx, _)| idx).collect();
+	}
+
+	/// Spread of the sizing value across nodes: highest degree, or the metric's range.
+	fn sizing_domain(&self) -> Option<(f64, f64)> {
+		match &self.sizing {
+			NodeSizing::Uniform => None,
+			NodeSizing::Degree => {
+				let max = self.degrees().values().copied().fold(0.0, f64::max);
+				Some((0.0, max.max(1.0)))
+			}
+			NodeSizing::Metric(metric) => {
+				let mut range: Option<(f64, f64)> = None;
+				self.graph.visit_nodes(|node| {
+					if let Some(&v) = node.data.user_data.spec.metrics.get(metric)
+						&& v.is_finite()
+					{
+						range = Some(range.map_or((v, v), |(lo, hi)| (lo.min(v), hi.max(v))));
+					}
+				});
+				range
+			}
+		}
+	}
+
+	/// Recompute every node's size multiplier from the current [`NodeSizing`].
+	pub fn apply_sizing(&mut self) {
+		self.dirty = true;
+		let domain = self.sizing_domain();
+		let degrees = self.degrees();
+		let sizing = &self.sizing;
+		self.graph.visit_nodes_mut(|node| {
+			let degree = degrees.get(&node.index()).copied().unwrap_or(0.0);
+			let info = &mut node.data.user_data;
+			let value = match sizing {
+				NodeSizing::Uniform => None,
+				NodeSizing::Degree => Some(degree),
+				NodeSizing::Metric(metric) => info.spec.metrics.get(metric).copied(),
+			};
+			let factor = value
+				.zip(domain)
+				.map_or(0.5, |(v, (lo, hi))| size_factor(v, lo, hi));
+			info.size = node_size(info.label.is_some(), factor);
+		});
+	}
+
+	/// Recompute every node's repulsion, scaled by its number of links when
+	/// [`PhysicsConfig::degree_charge`] is set.
+	pub fn apply_charge(&mut self) {
+		let degrees = if self.physics.degree_charge {
+			self.degrees()
+		} else {
+			HashMap::new()
+		};
+		self.graph.visit_nodes_mut(|node| {
+			let degree = degrees.get(&node.index()).copied().unwrap_or(0.0);
+			node.data.mass = BASE_CHARGE * (1.0 + degree as f32).sqrt();
+		});
+	}
+
+	/// Example circles for the size legend: representative values of the sizing
+	/// variable with their radius at 100% zoom, for unlabeled nodes.
+	pub fn size_legend(&self, base_radius: f64) -> Option<SizeLegend> {
+		let (lo, hi) = self.sizing_domain()?;
+		let title = match &self.sizing {
+			NodeSizing::Degree => "Connections".to_string(),
+			NodeSizing::Metric(metric) => metric.clone(),
+			NodeSizing::Uniform => return None,
+		};
+		let mut values = vec![lo, (lo + hi) / 2.0, hi];
+		if self.sizing == NodeSizing::Degree {
+			values = vec![1.0, (hi / 2.0).round(), hi];
+		}
+		values.dedup();
+		let entries = values
+			.into_iter()
+			.map(|v| (v, base_radius * node_size(false, size_factor(v, lo, hi))))
+			.collect();
+		Some(SizeLegend { title, entries })
+	}
+
+	/// Value range the heatmap maps onto its ramp: the configured domain, or the
+	/// spread of the metric across all nodes.
+	#[cfg(feature = "analysis")]
+	fn heat_domain(&self, config: &HeatmapConfig) -> Option<(f64, f64)> {
+		if config.domain.is_some() {
+			return config.domain;
+		}
+		let mut range: Option<(f64, f64)> = None;
+		self.graph.visit_nodes(|node| {
+			if let Some(&v) = node.data.user_data.spec.metrics.get(&config.metric)
+				&& v.is_finite()
+			{
+				range = Some(range.map_or((v, v), |(lo, hi)| (lo.min(v), hi.max(v))));
+			}
+		});
+		range
+	}
+
+	/// Reassign node colors, applying the heatmap (if any) over the base colors.
+	pub fn recolor(&mut self, theme: &Theme) {
+		self.dirty = true;
+		#[cfg(not(feature = "analysis"))]
+		let heat: Option<(String, ColorRamp, f64, f64)> = None;
+		#[cfg(feature = "analysis")]
+		let heat = self.heatmap.as_ref().and_then(|config| {
+			let (lo, hi) = self.heat_domain(config)?;
+			let ramp = config
+				.ramp
+				.clone()
+				.unwrap_or_else(|| theme.heat_ramp.clone());
+			Some((config.metric.clone(), ramp, lo, hi))
+		});
+		let (group_colors, auto_colors) = (&self.group_colors, &self.auto_colors);
+		let mut fading = false;
+		self.graph.visit_nodes_mut(|node| {
+			let index = node.index().index();
+			let info = &mut node.data.user_data;
+			let heat_color = heat.as_ref().and_then(|(metric, ramp, lo, hi)| {
+				let v = *info.spec.metrics.get(metric)?;
+				let t = if hi > lo { (v - lo) / (hi - lo) } else { 0.5 };
+				Some(ramp.sample(t).to_css_rgb())
+			});
+			info.color = heat_color
+				.unwrap_or_else(|| base_color(&info.spec, index, theme, group_colors, auto_colors));
+			if Color::parse(&info.color) != info.fill {
+				info.fade_from = Some(info.fill);
+				fading = true;
+			}
+		});
+		if fading {
+			self.color_fade = 0.0;
+		}
+	}
+
+	/// Advance the animation clock and highlight fades by `dt` seconds. Runs
+	/// every frame, whether or not the simulation is paused.
+	pub fn tick_animation(&mut self, dt: f64) {
+		self.flow_time += dt;
+		self.highlight.tick(dt);
+	}
+
+	/// Advance a recolor transition, blending each changed node toward its new color.
+	pub fn tick_colors(&mut self, dt: f64) {
+		if self.color_fade >= 1.0 {
+			return;
+		}
+		let transitions = &self.transitions;
+		self.color_fade = advance_transition(self.color_fade, dt, transitions.color_duration);
+		let (t, done) = (
+			transitions.color_easing.apply(self.color_fade),
+			self.color_fade >= 1.0,
+		);
+		self.graph.visit_nodes_mut(|node| {
+			let info = &mut node.data.user_data;
+			if let Some(from) = info.fade_from {
+				info.fill = from.lerp(Color::parse(&info.color), t);
+				if done {
+					info.fade_from = None;
+				}
+			}
+		});
+	}
+
+	/// Colorbar contents for the active heatmap, if any node carries its metric.
+	#[cfg(not(feature = "analysis"))]
+	pub fn heat_legend(&self, _theme: &Theme) -> Option<HeatLegend> {
+		None
+	}
+
+	/// Colorbar contents for the active heatmap, if any node carries its metric.
+	#[cfg(feature = "analysis")]
+	pub fn heat_legend(&self, theme: &Theme) -> Option<HeatLegend> {
+		let config = self.heatmap.as_ref()?;
+		let (min, max) = self.heat_domain(config)?;
+		let ramp = config.ramp.as_ref().unwrap_or(&theme.heat_ramp);
+		Some(HeatLegend {
+			metric: config.metric.clone(),
+			gradient: ramp.to_css_gradient(),
+			min,
+			max,
+		})
+	}
+
+	pub fn screen_to_graph(&self, sx: f64, sy: f64) -> (f64, f64) {
+		(
+			(sx - self.transform.x) / self.transform.k,
+			(sy - self.transform.y) / self.transform.k,
+		)
+	}
+
+	/// Whether node `a`, met after node `b` in visiting order, is drawn over it
+	/// when they share a layer and highlighting: in 3D the nearer one is,
+	/// otherwise the later one.
+	fn drawn_over(&self, a: DefaultNodeIdx, b: DefaultNodeIdx) -> bool {
+		let Some(space) = &self.space else {
+			return true;
+		};
+		// Far nodes are drawn first
+		let position = |idx| space.order.iter().position(|&i| i == idx);
+		position(a) > position(b)
+	}
+
+	/// The node under screen point `(sx, sy)`, as drawn: a point on a node's disc
+	/// picks the topmost such node (highlighted nodes draw over the rest, then
+	/// nearer nodes in 3D, or else later nodes, over the others). Otherwise the
+	/// hit areas around nodes count, nearest center first relative to the hit
+	/// radius, then the smaller node.
+	pub fn node_at_position(
+		&self,
+		sx: f64,
+		sy: f64,
+		config: &ScaleConfig,
+	) -> Option<DefaultNodeIdx> {
+		let (gx, gy) = self.screen_to_graph(sx, sy);
+		let scale = ScaledValues::new(config, self.transform.k);
+		// Topmost node whose disc contains the point, by layer and then whether it
+		// is highlighted
+		let mut top: Option<(i32, bool, DefaultNodeIdx)> = None;
+		// Nearest node whose hit area contains the point: (distance / hit radius, radius)
+		let mut nearest: Option<(f64, f64, DefaultNodeIdx)> = None;
+		self.graph.visit_nodes(|node| {
+			let info = &node.data.user_data;
+			if info.hidden {
+				return;
+			}
+			let (x, y) = (info.display.x, info.display.y);
+			let radius = scale.node_radius * info.display_size();
+			// Badges are decorative: a point over a badge doesn't count as hitting its node
+			if info.badge.is_some() {
+				let (bx, by) = badge_center(x, y, radius);
+				let (bdx, bdy) = (bx - gx, by - gy);
+				if (bdx * bdx + bdy * bdy).sqrt() < scale.badge_radius {
+					return;
+				}
+			}
+			let (dx, dy) = (x - gx, y - gy);
+			let dist = (dx * dx + dy * dy).sqrt();
+			let idx = node.index();
+			if dist < radius {
+				// Same stacking as the renderer's
+				let highlighted = self.highlight.node_intensity(idx) > 0.001;
+				let rank = (info.layer, highlighted);
+				if top.is_none_or(|(layer, top_highlighted, top_idx)| {
+					let top_rank = (layer, top_highlighted);
+					rank > top_rank || rank == top_rank && self.drawn_over(idx, top_idx)
+				}) {
+					top = Some((info.layer, highlighted, idx));
+				}
+				return;
+			}
+			let node_hit_radius = scale.hit_radius * info.display_size();
+			if dist < node_hit_radius {
+				let closeness = dist / node_hit_radius;
+				let better = nearest.is_none_or(|(best, best_radius, _)| {
+					closeness
+						.total_cmp(&best)
+						.then(radius.total_cmp(&best_radius))
+						.is_lt()
+				});
+				if better {
+					nearest = Some((closeness, radius, idx));
+				}
+			}
+		});
+		top.map(|(_, _, idx)| idx)
+			.or(nearest.map(|(_, _, idx)| idx))
+	}
+
+	/// Transform that fits the screen-space rectangle (left, top, width, height)
+	/// to the canvas, preserving aspect ratio and centering it.
+	pub fn fit_screen_rect(&self, rect: (f64, f64, f64, f64)) -> ViewTransform {
+		let (left, top, w, h) = rect;
+		let (cx, cy) = self.screen_to_graph(left + w / 2.0, top + h / 2.0);
+		let factor = (self.width / w.max(1.0)).min(self.height / h.max(1.0));
+		let k = (self.transform.k * factor).clamp(0.1, 10.0);
+		ViewTransform {
+			x: self.width / 2.0 - cx * k,
+			y: self.height / 2.0 - cy * k,
+			k,
+		}
+	}
+
+	/// Transform that fits every node on screen, or `None` for an empty graph.
+	pub fn fit_all_transform(&self) -> Option<ViewTransform> {
+		let mut visible = Vec::new();
+		self.graph.visit_nodes(|node| {
+			if !node.data.user_data.hidden {
+				visible.push(node.index());
+			}
+		});
+		self.fit_nodes_transform(&visible)
+	}
+
+	/// Transform fitting `nodes` in the viewport with some padding.
+	pub fn fit_nodes_transform(&self, nodes: &[DefaultNodeIdx]) -> Option<ViewTransform> {
+		let mut bounds: Option<(f64, f64, f64, f64)> = None;
+		for &idx in nodes {
+			let Some(node) = self.node(idx) else {
+				continue;
+			};
+			let (x, y) = self.camera_position(node);
+			bounds = Some(match bounds {
+				Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+				None => (x, y, x, y),
+			});
+		}
+		let (x0, y0, x1, y1) = bounds?;
+		let avail_w = (self.width - 2.0 * FIT_PADDING).max(1.0);
+		let avail_h = (self.height - 2.0 * FIT_PADDING).max(1.0);
+		let k = (avail_w / (x1 - x0).max(1.0))
+			.min(avail_h / (y1 - y0).max(1.0))
+			.clamp(0.1, 10.0);
+		let (cx, cy) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+		Some(ViewTransform {
+			x: self.width / 2.0 - cx * k,
+			y: self.height / 2.0 - cy * k,
+			k,
+		})
+	}
+
+	/// Connected components of the visible graph, ignoring link direction,
+	/// largest first.
+	pub fn connected_components(&self) -> Vec<Vec<DefaultNodeIdx>> {
+		let mut nodes = Vec::new();
+		self.graph.visit_nodes(|node| {
+			if !node.data.user_data.hidden {
+				nodes.push(node.index());
+			}
+		});
+		nodes.sort();
+		let mut components = self.adjacency.components(&nodes);
+		// Stable: equal sizes keep the order of their lowest node index
+		components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+		components
+	}
+
+	/// Navigator entries for the connected components, largest first: each is
+	/// named after its best-connected node.
+	pub fn component_summaries(&self) -> Vec<ComponentSummary> {
+		let degrees = self.degrees();
+		self.connected_components()
+			.into_iter()
+			.map(|component| {
+				let hub = component.iter().copied().max_by(|a, b| {
+					let (da, db) = (
+						degrees.get(a).unwrap_or(&0.0),
+						degrees.get(b).unwrap_or(&0.0),
+					);
+					da.total_cmp(db).then(b.cmp(a))
+				});
+				let label = hub
+					.and_then(|idx| self.node(idx))
+					.map(|node| {
+						let info = &node.data.user_data;
+						info.label.clone().unwrap_or_else(|| info.id.clone())
+					})
+					.unwrap_or_default();
+				ComponentSummary {
+					label,
+					size: component.len(),
+				}
+			})
+			.collect()
+	}
+
+	/// Transform centering the given node at a comfortable zoom level.
+	pub fn focus_node_transform(&self, idx: DefaultNodeIdx) -> Option<ViewTransform> {
+		let (x, y) = self.camera_position(self.node(idx)?);
+		let k = self.transform.k.max(FOCUS_ZOOM);
+		Some(ViewTransform {
+			x: self.width / 2.0 - x * k,
+			y: self.height / 2.0 - y * k,
+			k,
+		})
+	}
+
+	/// Transform panning the given node to the center at the current zoom.
+	pub fn center_node_transform(&self, idx: DefaultNodeIdx) -> Option<ViewTransform> {
+		let (x, y) = self.camera_position(self.node(idx)?);
+		let k = self.transform.k;
+		Some(ViewTransform {
+			x: self.width / 2.0 - x * k,
+			y: self.height / 2.0 - y * k,
+			k,
+		})
+	}
+
+	/// Where the camera should aim to show `node`: its projected position in 3D,
+	/// else its simulated one, so the fisheye lens doesn't pull the target along.
+	fn camera_position(&self, node: &Node<NodeInfo>) -> (f64, f64) {
+		match self.space {
+			Some(_) => {
+				let display = &node.data.user_data.display;
+				(display.x, display.y)
+			}
+			None => (node.x() as f64, node.y() as f64),
+		}
+	}
+
+	/// Chips for selected and highlighted nodes outside the viewport, as placed
+	/// by the last [`prepare_frame`](Self::prepare_frame).
+	pub fn offscreen_indicators(&self) -> &[OffscreenIndicator] {
+		&self.indicators
+	}
+
+	/// Place a chip for each selected and highlighted node outside the
+	/// viewport on the screen edge, along the line from the screen center to
+	/// the node. Chips that would overlap an earlier one are dropped; selected
+	/// nodes come first.
+	fn place_indicators(&self) -> Vec<OffscreenIndicator> {
+		let (cx, cy) = (self.width / 2.0, self.height / 2.0);
+		let (half_w, half_h) = (cx - INDICATOR_MARGIN, cy - INDICATOR_MARGIN);
+		if half_w <= 0.0 || half_h <= 0.0 {
+			return Vec::new();
+		}
+		let mut targets: Vec<DefaultNodeIdx> = self.selection.iter().collect();
+		targets.sort();
+		let mut highlighted: Vec<DefaultNodeIdx> = self
+			.indicator_holds
+			.keys()
+			.copied()
+			.filter(|&idx| !self.selection.contains(idx))
+			.collect();
+		highlighted.sort();
+		targets.extend(highlighted);
+
+		let t = &self.transform;
+		let mut chips: Vec<OffscreenIndicator> = Vec::new();
+		for idx in targets {
+			let Some(node) = self.node(idx) else {
+				continue;
+			};
+			let info = &node.data.user_data;
+			if info.hidden {
+				continue;
+			}
+			let (sx, sy) = (info.display.x * t.k + t.x, info.display.y * t.k + t.y);
+			if (0.0..=self.width).contains(&sx) && (0.0..=self.height).contains(&sy) {
+				continue;
+			}
+			let (dx, dy) = (sx - cx, sy - cy);
+			let reach = (half_w / dx.abs()).min(half_h / dy.abs());
+			let (x, y) = (cx + dx * reach, cy + dy * reach);
+			let overlaps = chips
+				.iter()
+				.any(|c| (c.x - x).hypot(c.y - y) < INDICATOR_RADIUS * 2.0);
+			if !overlaps {
+				chips.push(OffscreenIndicator {
+					node: idx,
+					x,
+					y,
+					angle: dy.atan2(dx),
+				});
+			}
+		}
+		chips
+	}
+
+	/// The node whose off-screen indicator chip is at screen point `(x, y)`.
+	pub fn indicator_at(&self, x: f64, y: f64) -> Option<DefaultNodeIdx> {
+		self.indicators
+			.iter()
+			.find(|c| (c.x - x).hypot(c.y - y) <= INDICATOR_RADIUS + 3.0)
+			.map(|c| c.node)
+	}
+
+	/// Keep indicators for highlighted nodes, lingering after the highlight
+	/// fades. A chip under the pointer stays put.
+	pub fn tick_indicators(&mut self, dt: f64) {
+		let hovered_chip = self.pointer.and_then(|(x, y)| self.indicator_at(x, y));
+		let before = self.indicator_holds.len();
+		self.indicator_holds.retain(|&idx, left| {
+			*left -= dt;
+			*left > 0.0 || Some(idx) == hovered_chip
+		});
+		let mut held = Vec::new();
+		self.graph.visit_nodes(|node| {
+			if self.highlight.node_intensity(node.index()) > 0.5 {
+				held.push(node.index());
+			}
+		});
+		for idx in held {
+			self.indicator_holds.insert(idx, INDICATOR_LINGER);
+		}
+		if self.indicator_holds.len() != before {
+			self.dirty = true;
+		}
+	}
+
+	/// Find the visible edge passing within a few screen pixels of a point, as (source, target).
+	/// Edges are treated as straight segments between their endpoints.
+	pub fn edge_at_position(&self, sx: f64, sy: f64) -> Option<(DefaultNodeIdx, DefaultNodeIdx)> {
+		const EDGE_HIT_PX: f64 = 5.0;
+		let (gx, gy) = self.screen_to_graph(sx, sy);
+		let tolerance = EDGE_HIT_PX / self.transform.k;
+		let mut best: Option<((DefaultNodeIdx, DefaultNodeIdx), f64)> = None;
+		self.graph.visit_edges(|n1, n2, edge| {
+			let (a, b) = (&n1.data.user_data, &n2.data.user_data);
+			if a.hidden || b.hidden || (edge.user_data.hidden && !self.show_hidden_links) {
+				return;
+			}
+			let (x1, y1, x2, y2) = (a.display.x, a.display.y, b.display.x, b.display.y);
+			let (dx, dy) = (x2 - x1, y2 - y1);
+			let len_sq = dx * dx + dy * dy;
+			if len_sq < 1e-9 {
+				return;
+			}
+			let t = (((gx - x1) * dx + (gy - y1) * dy) / len_sq).clamp(0.0, 1.0);
+			let (px, py) = (x1 + dx * t - gx, y1 + dy * t - gy);
+			let dist = (px * px + py * py).sqrt();
+			if dist < tolerance && best.is_none_or(|(_, d)| dist < d) {
+				best = Some(((n1.index(), n2.index()), dist));
+			}
+		});
+		best.map(|(edge, _)| edge)
+	}
+
+	/// Tooltip text for an edge: its endpoints, plus the multiplicity when several
+	/// identical links were aggregated into it.
+	pub fn edge_tooltip(&self, source: DefaultNodeIdx, target: DefaultNodeIdx) -> Option<String> {
+		let graph = self.graph.get_graph();
+		let edge = graph.find_edge(source, target)?;
+		// The simulation graph is undirected; name the ends as the link runs
+		let (from, to) = graph.edge_endpoints(edge)?;
+		let name = |idx: DefaultNodeIdx| {
+			let info = &self.node(idx)?.data.user_data;
+			Some(info.label.clone().unwrap_or_else(|| info.id.clone()))
+		};
+		let (a, b) = (name(from)?, name(to)?);
+		Some(match graph.edge_weight(edge)?.user_data.count {
+			count if count > 1 => format!("{a} \u{2192} {b} (\u{d7}{count})"),
+			_ => format!("{a} \u{2192} {b}"),
+		})
+	}
+
+	/// Tooltip text for a node: its name, in/out degree, and the first few
+	/// neighbors in each direction. Uses one line per part.
+	pub fn node_tooltip(&self, idx: DefaultNodeIdx) -> Option<String> {
+		const LISTED: usize = 3;
+		let name = |idx: DefaultNodeIdx| {
+			let info = &self.node(idx)?.data.user_data;
+			(!info.hidden).then(|| info.label.clone().unwrap_or_else(|| info.id.clone()))
+		};
+		let list = |names: &[String]| match names.len() {
+			n if n > LISTED => format!("{}, +{} more", names[..LISTED].join(", "), n - LISTED),
+			_ => names.join(", "),
+		};
+		let out: Vec<String> = self
+			.adjacency
+			.outgoing(idx)
+			.iter()
+			.filter_map(|&b| name(b))
+			.collect();
+		let inc: Vec<String> = self
+			.adjacency
+			.incoming(idx)
+			.iter()
+			.filter_map(|&a| name(a))
+			.collect();
+
+		let mut lines = vec![
+			name(idx)?,
+			format!("in {} \u{b7} out {}", inc.len(), out.len()),
+		];
+		if !out.is_empty() {
+			lines.push(format!("depends on: {}", list(&out)));
+		}
+		if !inc.is_empty() {
+			lines.push(format!("used by: {}", list(&inc)));
+		}
+		Some(lines.join("\n"))
+	}
+
+	/// Nodes an action on `idx` applies to: the whole selection when `idx` is part of it,
+	/// otherwise just `idx`.
+	pub fn action_targets(&self, idx: DefaultNodeIdx) -> Vec<DefaultNodeIdx> {
+		if self.selection.contains(idx) {
+			self.selection.iter().collect()
+		} else {
+			vec![idx]
+		}
+	}
+
+	/// Pin (or release) nodes in place. Undoable.
+	pub fn set_pinned(&mut self, nodes: &[DefaultNodeIdx], pinned: bool) {
+		self.dirty = true;
+		let before = self.snapshot_nodes(nodes);
+		self.graph.visit_nodes_mut(|node| {
+			if nodes.contains(&node.index()) {
+				node.data.is_anchor = pinned;
+			}
+		});
+		self.record(before);
+	}
+
+	/// Capture position and pin state of `nodes`.
+	pub fn snapshot_nodes(&self, nodes: &[DefaultNodeIdx]) -> Vec<NodeSnapshot> {
+		nodes
+			.iter()
+			.filter_map(|&idx| {
+				let node = self.node(idx)?;
+				Some(NodeSnapshot {
+					idx,
+					x: node.x(),
+					y: node.y(),
+					pinned: node.data.is_anchor,
+				})
+			})
+			.collect()
+	}
+
+	/// Push an undo entry going from `before` to the nodes' current state.
+	pub fn record(&mut self, before: Vec<NodeSnapshot>) {
+		let nodes: Vec<_> = before.iter().map(|s| s.idx).collect();
+		let after = self.snapshot_nodes(&nodes);
+		self.history.push(before, after);
+	}
+
+	fn restore(&mut self, snapshots: &[NodeSnapshot]) {
+		self.dirty = true;
+		self.graph.visit_nodes_mut(|node| {
+			if let Some(s) = snapshots.iter().find(|s| s.idx == node.index()) {
+				node.data.x = s.x;
+				node.data.y = s.y;
+				node.data.is_anchor = s.pinned;
+			}
+		});
+	}
+
+	/// Revert the last drag or pin change. Returns false if there was nothing to undo.
+	pub fn undo(&mut self) -> bool {
+		let Some(snapshots) = self.history.undo() else {
+			return false;
+		};
+		self.restore(&snapshots);
+		true
+	}
+
+	/// Reapply the last undone change. Returns false if there was nothing to redo.
+	pub fn redo(&mut self) -> bool {
+		let Some(snapshots) = self.history.redo() else {
+			return false;
+		};
+		self.restore(&snapshots);
+		true
+	}
+
+	/// Hide (or reveal) nodes along with their edges.
+	pub fn set_hidden(&mut self, nodes: &[DefaultNodeIdx], hidden: bool) {
+		self.graph.visit_nodes_mut(|node| {
+			if nodes.contains(&node.index()) {
+				node.data.user_data.hidden = hidden;
+			}
+		});
+		self.visibility_changed();
+		if hidden {
+			if let Some(h) = self.highlight.hovered_node
+				&& nodes.contains(&h)
+			{
+				self.set_hover(None);
+			}
+			let remaining: Vec<_> = self
+				.selection
+				.iter()
+				.filter(|i| !nodes.contains(i))
+				.collect();
+			self.selection.set(remaining);
+		}
+	}
+
+	/// Hide everything except `idx` and its direct neighbors.
+	pub fn isolate(&mut self, idx: DefaultNodeIdx) {
+		let mut keep: HashSet<DefaultNodeIdx> = HashSet::from([idx]);
+		keep.extend(self.adjacency.neighbors(idx));
+		self.graph.visit_nodes_mut(|node| {
+			node.data.user_data.hidden = !keep.contains(&node.index());
+		});
+		self.visibility_changed();
+		let remaining: Vec<_> = self.selection.iter().filter(|i| keep.contains(i)).collect();
+		self.selection.set(remaining);
+	}
+
+	/// Keep `root` and everything it (transitively) links to at full intensity and
+	/// dim the rest until [`ForceGraphState::clear_focus`].
+	pub fn focus_subtree(&mut self, root: DefaultNodeIdx) {
+		self.dirty = true;
+		let mut nodes = HashSet::from([root]);
+		let mut frontier = vec![root];
+		while let Some(idx) = frontier.pop() {
+			for &tgt in self.adjacency.outgoing(idx) {
+				if nodes.insert(tgt) {
+					frontier.push(tgt);
+				}
+			}
+		}
+		self.focus = Some(FocusState { root, nodes });
+	}
+
+	pub fn clear_focus(&mut self) {
+		self.dirty |= self.focus.is_some();
+		self.focus = None;
+		self.prune_breadcrumbs();
+	}
+
+	/// Record a drill-down into `idx`, remembering the current view so it can be restored.
+	pub fn push_breadcrumb(&mut self, idx: DefaultNodeIdx) {
+		let Some(node) = self.node(idx) else {
+			return;
+		};
+		let info = &node.data.user_data;
+		let label = info.label.clone().unwrap_or_else(|| info.id.clone());
+		let mut hidden = HashSet::new();
+		self.graph.visit_nodes(|node| {
+			if node.data.user_data.hidden {
+				hidden.insert(node.index());
+			}
+		});
+		let before = ViewSnapshot {
+			hidden,
+			focus: self.focus.clone(),
+			transform: self.transform.clone(),
+		};
+		self.breadcrumbs.push(Breadcrumb { label, before });
+	}
+
+	/// Return to the view before breadcrumb `depth` (0 = the full graph), dropping
+	/// that crumb and everything after it.
+	pub fn restore_breadcrumb(&mut self, depth: usize) {
+		if depth >= self.breadcrumbs.len() {
+			return;
+		}
+		let crumb = self.breadcrumbs.swap_remove(depth);
+		self.breadcrumbs.truncate(depth);
+		let before = crumb.before;
+		self.graph.visit_nodes_mut(|node| {
+			node.data.user_data.hidden = before.hidden.contains(&node.index());
+		});
+		self.visibility_changed();
+		self.focus = before.focus;
+		self.animate_to(before.transform);
+	}
+
+	/// Once the view is back to the full graph there is nothing left to return from.
+	fn prune_breadcrumbs(&mut self) {
+		if self.focus.is_none() && !self.has_hidden() {
+			self.breadcrumbs.clear();
+		}
+	}
+
+	/// Alpha multiplier for a node under the current focus (1.0 when unfocused).
+	pub fn focus_alpha(&self, idx: DefaultNodeIdx) -> f64 {
+		match &self.focus {
+			Some(focus) if !focus.nodes.contains(&idx) => FOCUS_DIM_ALPHA,
+			_ => 1.0,
+		}
+	}
+
+	/// Whether any node is currently hidden.
+	pub fn has_hidden(&self) -> bool {
+		let mut any = false;
+		self.graph
+			.visit_nodes(|node| any |= node.data.user_data.hidden);
+		if !self.show_hidden_links {
+			self.graph
+				.visit_edges(|_, _, edge| any |= edge.user_data.hidden);
+		}
+		any
+	}
+
+	/// Create a labeled node at graph position `(x, y)` with a fresh `node-N` ID.
+	pub fn add_node_at(&mut self, x: f64, y: f64, color: String) -> DefaultNodeIdx {
+		self.dirty = true;
+		let mut n = self.source.nodes.len() + 1;
+		// Members of collapsed groups aren't in the simulation, so check the source too
+		while self
+			.source
+			.nodes
+			.iter()
+			.any(|node| node.id == format!("node-{n}"))
+		{
+			n += 1;
+		}
+		let id = format!("node-{n}");
+		let (x, y) = self.snap(x as f32, y as f32);
+		let spec = GraphNode {
+			id: id.clone(),
+			label: Some(id.clone()),
+			color: Some(color.clone()),
+			..GraphNode::default()
+		};
+		let idx = self.graph.add_node(NodeData {
+			x,
+			y,
+			mass: BASE_CHARGE,
+			is_anchor: false,
+			user_data: NodeInfo {
+				id: id.clone(),
+				label: Some(id),
+				fill: Color::parse(&color),
+				fade_from: None,
+				color,
+				size: 1.4,
+				badge: None,
+				badge_color: None,
+				ring: Vec::new(),
+				pulse: 0.0,
+				display: DisplayGeometry {
+					x: x as f64,
+					y: y as f64,
+					magnification: 1.0,
+				},
+				hidden: false,
+				opacity: 1.0,
+				border: None,
+				constraint: NodeConstraint::default(),
+				mass: 1.0,
+				z: 0.0,
+				layer: 0,
+				spec: spec.clone(),
+			},
+		});
+		self.source.nodes.push(spec);
+		self.rank_labels();
+		idx
+	}
+
+	/// The input data with the edits made since, in input format: nodes added or
+	/// removed while editing are reflected, collapsed groups are exported
+	/// expanded, and hidden nodes are left out along with their links.
+	pub fn export_data(&self) -> GraphData {
+		let mut hidden = HashSet::new();
+		let mut hidden_groups = HashSet::new();
+		self.graph.visit_nodes(|node| {
+			let info = &node.data.user_data;
+			if info.hidden {
+				match view_model::meta_group(&info.id) {
+					Some(group) => hidden_groups.insert(group.to_string()),
+					None => hidden.insert(info.id.clone()),
+				};
+			}
+		});
+		// A collapsed member has no simulation node; it's hidden with its meta-node
+		let shown = |node: &GraphNode| match node
+			.group
+			.as_ref()
+			.filter(|group| self.collapsed.contains(*group))
+		{
+			Some(group) => !hidden_groups.contains(group),
+			None => !hidden.contains(&node.id),
+		};
+		let nodes: Vec<GraphNode> = self
+			.source
+			.nodes
+			.iter()
+			.filter(|node| shown(node))
+			.cloned()
+			.collect();
+		let kept: HashSet<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+		let links = self
+			.source
+			.links
+			.iter()
+			.filter(|link| {
+				kept.contains(link.source.as_str())
+					&& kept.contains(link.target.as_str())
+					&& (!link.hidden || self.show_hidden_links)
+			})
+			.map(|link| GraphLink {
+				hidden: false,
+				..link.clone()
+			})
+			.collect();
+		let cluster_colors = self
+			.group_colors
+			.iter()
+			.filter(|&(group, color)| self.cluster_colors.get(group) != Some(color))
+			.map(|(group, color)| (group.clone(), color.clone()))
+			.collect();
+		GraphData {
+			nodes,
+			links,
+			cluster_colors,
+		}
+	}
+
+	/// Link two nodes. Self-links, duplicates (in either direction) and links to
+	/// a collapsed group's meta-node, which has no input node to link, are ignored.
+	pub fn add_link(&mut self, source: DefaultNodeIdx, target: DefaultNodeIdx) -> bool {
+		if source == target || self.adjacency.linked(source, target) {
+			return false;
+		}
+		let (Some(a), Some(b)) = (self.node(source), self.node(target)) else {
+			return false;
+		};
+		let (a, b) = (&a.data.user_data.id, &b.data.user_data.id);
+		if view_model::meta_group(a).is_some() || view_model::meta_group(b).is_some() {
+			return false;
+		}
+		self.source.links.push(GraphLink {
+			source: a.clone(),
+			target: b.clone(),
+			..GraphLink::default()
+		});
+		self.dirty = true;
+		self.graph.add_edge(
+			source,
+			target,
+			EdgeData {
+				user_data: EdgeInfo {
+					weight: 1.0,
+					count: 1,
+					..EdgeInfo::default()
+				},
+			},
+		);
+		self.edges.push((source, target));
+		self.adjacency.insert(source, target);
+		self.apply_charge();
+		self.rank_labels();
+		true
+	}
+
+	/// Delete nodes along with their links. Deleting a collapsed group's
+	/// meta-node deletes every member of the group.
+	pub fn remove_nodes(&mut self, nodes: &[DefaultNodeIdx]) {
+		if nodes.is_empty() {
+			return;
+		}
+		self.dirty = true;
+		let mut ids = HashSet::new();
+		let mut groups = HashSet::new();
+		for &idx in nodes {
+			if let Some(node) = self.node(idx) {
+				let id = &node.data.user_data.id;
+				match view_model::meta_group(id) {
+					Some(group) => groups.insert(group.to_string()),
+					None => ids.insert(id.clone()),
+				};
+			}
+		}
+		self.source.nodes.retain(|node| {
+			let removed = ids.contains(&node.id)
+				|| node
+					.group
+					.as_ref()
+					.is_some_and(|group| groups.contains(group));
+			if removed {
+				ids.insert(node.id.clone());
+			}
+			!removed
+		});
+		self.source
+			.links
+			.retain(|link| !ids.contains(&link.source) && !ids.contains(&link.target));
+		for group in &groups {
+			self.collapsed.remove(group);
+		}
+		self.set_hover(None);
+		for &idx in nodes {
+			self.graph.remove_node(idx);
+		}
+		self.edges
+			.retain(|(a, b)| !nodes.contains(a) && !nodes.contains(b));
+		self.adjacency = Adjacency::new(&self.edges);
+		self.apply_charge();
+		self.rank_labels();
+		self.selection.set([]);
+		self.focus = None;
+		self.breadcrumbs.clear();
+		self.drag = DragState::default();
+		self.link_draft = None;
+		self.hot_nodes = None;
+		// Removed indices get reused by new nodes, so old snapshots would target the wrong node
+		self.history.clear();
+	}
+
+	/// Links in and out of each node.
+	pub fn adjacency(&self) -> &Adjacency {
+		&self.adjacency
+	}
+
+	/// Pinned (anchored) nodes.
+	pub fn pinned_nodes(&self) -> Vec<DefaultNodeIdx> {
+		let mut pinned = Vec::new();
+		self.graph.visit_nodes(|node| {
+			if node.data.is_anchor {
+				pinned.push(node.index());
+			}
+		});
+		pinned
+	}
+
+	/// Release every pinned node back to the simulation. Undoable.
+	pub fn unpin_all(&mut self) {
+		let pinned = self.pinned_nodes();
+		self.set_pinned(&pinned, false);
+		if !pinned.is_empty() {
+			self.reheat(UNPIN_REHEAT);
+		}
+	}
+
+	/// Switch the experimental 3D mode on or off. Entering lifts the layout
+	/// into depth and lets it settle again; leaving flattens it.
+	pub fn set_3d(&mut self, on: bool) {
+		if on == self.space.is_some() {
+			return;
+		}
+		self.space = if on {
+			Some(Space::enter(&mut self.graph))
+		} else {
+			Space::leave(&mut self.graph);
+			None
+		};
+		(self.settled, self.energy_peak) = (false, 0.0);
+		self.dirty = true;
+	}
+
+	/// Turn the fisheye lens on or off.
+	pub fn set_fisheye(&mut self, enabled: bool) {
+		self.fisheye.enabled = enabled;
+		self.dirty = true;
+	}
+
+	/// Snap dragged and new nodes to a grid `spacing` graph units apart, or
+	/// stop snapping.
+	pub fn set_grid(&mut self, spacing: Option<f64>) {
+		self.grid = spacing;
+		self.dirty = true;
+	}
+
+	/// Align still nodes to the pixel grid, or stop aligning them.
+	pub fn set_crisp(&mut self, crisp: bool) {
+		self.crisp = crisp;
+		self.dirty = true;
+	}
+
+	/// Switch edit mode on or off, dropping any link being drawn.
+	pub fn set_editable(&mut self, editable: bool) {
+		self.editable = editable;
+		self.link_draft = None;
+		self.dirty = true;
+	}
+
+	/// Replace the extra forces.
+	pub fn set_physics(&mut self, physics: PhysicsConfig) {
+		self.physics = physics;
+		self.apply_charge();
+		self.dirty = true;
+	}
+
+	/// Switch to `layout`, moving nodes into it as
+	/// [`transitions`](Self::transitions) says.
+	pub fn set_layout(&mut self, layout: Layout) {
+		self.layout = layout;
+		self.layout_fade = advance_transition(0.0, 0.0, self.transitions.layout_duration);
+		self.apply_layout();
+	}
+
+	/// Size nodes by `sizing`.
+	pub fn set_sizing(&mut self, sizing: NodeSizing) {
+		self.sizing = sizing;
+		self.apply_sizing();
+	}
+
+	/// Color nodes by `heatmap`, or by their group again.
+	pub fn set_heatmap(&mut self, heatmap: Option<HeatmapConfig>, theme: &Theme) {
+		self.heatmap = heatmap;
+		self.recolor(theme);
+	}
+
+	/// Show or hide resting labels.
+	pub fn set_show_labels(&mut self, show: bool) {
+		self.show_labels = show;
+		self.dirty_layers |= Layers::NODES;
+	}
+
+	/// Re-energize the layout without rebuilding it, e.g. after pinning,
+	/// filtering or patching data: every free node is nudged up to `alpha` (0..1)
+	/// times [`REHEAT_KICK`] graph units, the settling progress starts over and
+	/// paused physics resumes. Nudges fan out by node index, so a recorded
+	/// session replays the same way.
+	pub fn reheat(&mut self, alpha: f64) {
+		let kick = (alpha.clamp(0.0, 1.0) * REHEAT_KICK) as f32;
+		self.graph.visit_nodes_mut(|node| {
+			if node.data.is_anchor {
+				return;
+			}
+			let i = node.index().index() as f32;
+			let angle = i * GOLDEN_ANGLE;
+			// Vary the length too, so neighbors don't all move in lockstep
+			let length = kick * (0.5 + 0.5 * (i * 0.618_034).fract());
+			node.data.x += length * angle.cos();
+			node.data.y += length * angle.sin();
+		});
+		(self.settled, self.energy_peak) = (false, 0.0);
+		self.hot_nodes = None;
+		self.animation_running = true;
+		self.dirty = true;
+	}
+
+	/// Stop every node where it stands, dropping the velocity the simulation
+	/// has built up, so the steps that follow depend on positions alone.
+	pub fn halt(&mut self) {
+		// Velocities are private to the simulation; a step that carries nothing
+		// over zeroes them along with the forces it computes, moving no node
+		let damping = self.graph.parameters.damping_factor;
+		self.graph.parameters.damping_factor = 0.0;
+		self.graph.update(0.0);
+		self.graph.parameters.damping_factor = damping;
+		if let Some(space) = &mut self.space {
+			space.reset_motion();
+		}
+	}
+
+	/// Note that nodes were hidden or revealed, so the picture and the label
+	/// placement are out of date.
+	fn visibility_changed(&mut self) {
+		self.label_generation += 1;
+		self.dirty = true;
+	}
+
+	/// Reveal every hidden node.
+	pub fn show_all(&mut self) {
+		self.graph
+			.visit_nodes_mut(|node| node.data.user_data.hidden = false);
+		self.visibility_changed();
+		self.show_hidden_links = true;
+		self.prune_breadcrumbs();
+	}
+
+	/// Groups that have nodes hidden by the input data, sorted by name.
+	pub fn hidden_groups(&self) -> Vec<String> {
+		let mut groups = Vec::new();
+		self.graph.visit_nodes(|node| {
+			let info = &node.data.user_data;
+			if let Some(group) = info
+				.spec
+				.group
+				.as_ref()
+				.filter(|_| info.hidden && info.spec.hidden)
+				&& !groups.contains(group)
+			{
+				groups.push(group.clone());
+			}
+		});
+		groups.sort();
+		groups
+	}
+
+	/// Show or re-hide the nodes of `group` that the input data marks as hidden.
+	pub fn set_group_hidden(&mut self, group: &str, hidden: bool) {
+		let mut nodes = Vec::new();
+		self.graph.visit_nodes(|node| {
+			let spec = &node.data.user_data.spec;
+			if spec.hidden && spec.group.as_deref() == Some(group) {
+				nodes.push(node.index());
+			}
+		});
+		self.set_hidden(&nodes, hidden);
+		if !hidden {
+			self.prune_breadcrumbs();
+		}
+	}
+
+	/// Look up a simulation node by index.
+	pub fn node(&self, idx: DefaultNodeIdx) -> Option<&Node<NodeInfo>> {
+		self.graph.get_graph().node_weight(idx)
+	}
+
+	/// Index of the node with the given input-data ID.
+	pub fn node_index(&self, id: &str) -> Option<DefaultNodeIdx> {
+		let mut found = None;
+		self.graph.visit_nodes(|node| {
+			if node.data.user_data.id == id {
+				found = Some(node.index());
+			}
+		});
+		found
+	}
+
+	/// ID of the node at `idx` from the input data.
+	pub fn node_id(&self, idx: DefaultNodeIdx) -> Option<String> {
+		self.node(idx).map(|n| n.data.user_data.id.clone())
+	}
+
+	/// IDs of every node, hidden ones included, in the order
+	/// [`write_positions`](Self::write_positions) uses. It holds until the graph
+	/// is rebuilt.
+	pub fn node_order(&self) -> Vec<String> {
+		let mut ids = Vec::new();
+		self.graph
+			.visit_nodes(|node| ids.push(node.data.user_data.id.clone()));
+		ids
+	}
+
+	/// Overwrite `out` with every node's graph position as interleaved x, y
+	/// pairs, in [`node_order`](Self::node_order).
+	pub fn write_positions(&self, out: &mut Vec<f32>) {
+		out.clear();
+		self.graph
+			.visit_nodes(|node| out.extend([node.x(), node.y()]));
+	}
+
+	/// IDs of the selected nodes, sorted.
+	pub fn selected_ids(&self) -> Vec<String> {
+		let mut ids: Vec<_> = self
+			.selection
+			.iter()
+			.filter_map(|idx| self.node_id(idx))
+			.collect();
+		ids.sort();
+		ids
+	}
+
+	/// Select the nodes with the given IDs, ignoring unknown ones. Returns
+	/// whether the selection changed.
+	pub fn select_ids(&mut self, ids: &[String]) -> bool {
+		let nodes: HashSet<_> = ids.iter().filter_map(|id| self.node_index(id)).collect();
+		if nodes.len() == self.selection.iter().count()
+			&& nodes.iter().all(|&idx| self.selection.contains(idx))
+		{
+			return false;
+		}
+		self.selection.set(nodes);
+		true
+	}
+
+	/// The transform after zooming by `factor` while keeping screen point `(sx, sy)` fixed.
+	/// While a camera move is in flight the zoom is relative to where it is headed,
+	/// so repeated zoom steps compound instead of restarting from mid-flight.
+	pub fn zoom_about(&self, sx: f64, sy: f64, factor: f64) -> ViewTransform {
+		let base = self
+			.camera
+			.as_ref()
+			.map_or(&self.transform, |tween| &tween.to);
+		let k = (base.k * factor).clamp(0.1, 10.0);
+		let ratio = k / base.k;
+		ViewTransform {
+			x: sx - (sx - base.x) * ratio,
+			y: sy - (sy - base.y) * ratio,
+			k,
+		}
+	}
+
+	/// Round a graph position to the nearest grid point when snapping is on.
+	pub fn snap(&self, x: f32, y: f32) -> (f32, f32) {
+		match self.grid {
+			Some(spacing) if spacing > 0.0 => {
+				let s = spacing as f32;
+				((x / s).round() * s, (y / s).round() * s)
+			}
+			_ => (x, y),
+		}
+	}
+
+	/// CSS cursor reflecting the current interaction.
+	pub fn cursor(&self) -> &'static str {
+		if self.drag.active && self.gesture.moved {
+			"move"
+		} else if self.pan.active {
+			"grabbing"
+		} else if self.link_draft.is_some() || self.lasso.active || self.box_zoom.active {
+			"crosshair"
+		} else if (self.highlight.hovered_node.is_some()
+			|| self
+				.pointer
+				.is_some_and(|(x, y)| self.indicator_at(x, y).is_some()))
+			&& !self.pan.space_held
+		{
+			"pointer"
+		} else {
+			"grab"
+		}
+	}
+
+	/// Start panning from screen position `(x, y)`.
+	pub fn begin_pan(&mut self, x: f64, y: f64) {
+		self.follow = None;
+		self.pan.active = true;
+		self.pan.start_x = x;
+		self.pan.start_y = y;
+		self.pan.transform_start_x = self.transform.x;
+		self.pan.transform_start_y = self.transform.y;
+	}
+
+	/// Start animating the camera towards `target`, starting from wherever it is now
+	/// (so a move issued mid-flight continues smoothly). Direct manipulation cancels
+	/// the move by clearing [`ForceGraphState::camera`].
+	pub fn animate_to(&mut self, target: ViewTransform) {
+		self.dirty = true;
+		if self.camera_config.duration <= 0.0 {
+			self.camera = None;
+			self.transform = target;
+			return;
+		}
+		self.camera = Some(CameraTween {
+			from: self.transform.clone(),
+			to: target,
+			elapsed: 0.0,
+			duration: self.camera_config.duration,
+			easing: self.camera_config.easing,
+		});
+	}
+
+	/// Advance any in-flight camera animation. Runs every frame, independent of physics.
+	pub fn tick_camera(&mut self, dt: f64) {
+		let Some(tween) = &mut self.camera else {
+			return;
+		};
+		tween.elapsed += dt;
+		let t = (tween.elapsed / tween.duration).clamp(0.0, 1.0);
+		let eased = tween.easing.apply(t);
+		// Interpolate zoom geometrically so zooming in and out feel symmetric
+		let k = tween.from.k * (tween.to.k / tween.from.k).powf(eased);
+		self.transform = ViewTransform {
+			x: tween.from.x + (tween.to.x - tween.from.x) * eased,
+			y: tween.from.y + (tween.to.y - tween.from.y) * eased,
+			k,
+		};
+		if t >= 1.0 {
+			self.camera = None;
+		}
+	}
+
+	/// Keep the followed node centered, easing towards it so the camera glides
+	/// rather than jitters with the simulation. Yields to camera animations and
+	/// disengages once the node is gone or hidden.
+	pub fn tick_follow(&mut self, dt: f64) {
+		let Some(idx) = self.follow else {
+			return;
+		};
+		if self.camera.is_some() {
+			return;
+		}
+		let Some(node) = self.node(idx).filter(|node| !node.data.user_data.hidden) else {
+			self.follow = None;
+			return;
+		};
+		let ((x, y), k) = (self.camera_position(node), self.transform.k);
+		let ease = 1.0 - (-dt * FOLLOW_RATE).exp();
+		self.transform.x += (self.width / 2.0 - x * k - self.transform.x) * ease;
+		self.transform.y += (self.height / 2.0 - y * k - self.transform.y) * ease;
+	}
+
+	/// Current entity and cache sizes. Frame and particle counts are left for the
+	/// caller, which owns those.
+	pub fn debug_stats(&self) -> DebugStats {
+		let mut hidden_nodes = 0;
+		self.graph
+			.visit_nodes(|node| hidden_nodes += node.data.user_data.hidden as usize);
+		let h = &self.highlight;
+		let (sprites, sprite_bytes) = self.sprites.memory();
+		DebugStats {
+			nodes: self.graph.get_graph().node_count(),
+			hidden_nodes,
+			edges: self.edges.len(),
+			selected: self.selection.iter().count(),
+			highlight_entries: h.target_set.len()
+				+ h.node_intensity.len()
+				+ h.hover_ring_intensity.len()
+				+ h.hold_timer.len(),
+			label_widths: self.labels.cached(),
+			sprites,
+			sprite_bytes,
+			..DebugStats::default()
+		}
+	}
+
+	/// Register user input, ending the idle showcase if it is running.
+	pub fn wake(&mut self) {
+		if self.showcase.wake() {
+			self.set_hover(None);
+		}
+	}
+
+	/// Run the idle showcase once input has been quiet long enough: glide to the
+	/// fitted view, drift around it, and spotlight the best-connected nodes in turn.
+	pub fn tick_showcase(&mut self, dt: f64) {
+		if !self.showcase.advance(dt) {
+			return;
+		}
+		if !self.showcase.is_running() {
+			self.follow = None;
+			let anchor = self
+				.fit_all_transform()
+				.unwrap_or_else(|| self.transform.clone());
+			self.animate_to(anchor.clone());
+			self.showcase.start(anchor);
+		}
+		if self.showcase.spotlights.is_empty() {
+			self.showcase.spotlights = self.hubs(SHOWCASE_SPOTLIGHTS);
+		}
+		// Drift only once the glide into the anchor view has landed
+		if self.camera.is_none()
+			&& let Some(transform) = self.showcase.camera(dt, self.width, self.height)
+		{
+			self.transform = transform;
+		}
+		if let Some(idx) = self.showcase.next_spotlight(dt) {
+			self.set_hover(Some(idx));
+		}
+	}
+
+	/// Up to `n` visible nodes with the most links, highest first.
+	fn hubs(&self, n: usize) -> Vec<DefaultNodeIdx> {
+		let degrees = self.degrees();
+		let mut hubs: Vec<_> = degrees
+			.iter()
+			.filter(|&(&idx, _)| {
+				self.node(idx)
+					.is_some_and(|node| !node.data.user_data.hidden)
+			})
+			.collect();
+		hubs.sort_by(|a, b| b.1.total_cmp(a.1).then(a.0.cmp(b.0)));
+		hubs.into_iter().take(n).map(|(&idx, _)| idx).collect()
+	}
+
+	/// Finish a lasso gesture: select every node whose on-screen position lies inside the path.
+	pub fn complete_lasso(&mut self) {
+		self.lasso.active = false;
+		self.dirty_layers |= Layers::OVERLAY;
+		let (t, lasso) = (&self.transform, &self.lasso);
+		let mut inside = Vec::new();
+		self.graph.visit_nodes(|node| {
+			let d = &node.data.user_data.display;
+			if !node.data.user_data.hidden && lasso.contains(d.x * t.k + t.x, d.y * t.k + t.y) {
+				inside.push(node.index());
+			}
+		});
+		self.selection.set(inside);
+		self.lasso.points.clear();
+	}
+
+	pub fn set_hover(&mut self, node: Option<DefaultNodeIdx>) {
+		if self.highlight.hovered_node != node {
+			self.dirty_layers |= Layers::POINTER;
+		}
+		self.highlight.set_hover(node, &self.adjacency);
+	}
+
+	pub fn tick(&mut self, dt: f32) {
+		let mut before = std::mem::take(&mut self.step_positions);
+		before.clear();
+		self.graph
+			.visit_nodes(|node| before.push((node.data.x, node.data.y, node.data.user_data.z)));
+		match &mut self.space {
+			Some(space) => space.step(&mut self.graph, dt),
+			None => self.graph.update(dt),
+		}
+		self.apply_inertia(&before);
+		if self.layout != Layout::Free {
+			self.constrain();
+		}
+		self.hold_cold(&before);
+		self.apply_forces();
+		let (mut moved, mut i) = (0.0, 0);
+		self.graph.visit_nodes(|node| {
+			if let Some(&(x, y, z)) = before.get(i) {
+				let d = &node.data;
+				let (dx, dy, dz) = (d.x - x, d.y - y, d.user_data.z - z);
+				moved += ((dx * dx + dy * dy + dz * dz) as f64).sqrt();
+			}
+			i += 1;
+		});
+		self.step_positions = before;
+		self.update_energy(moved / i.max(1) as f64);
+	}
+
+	/// Track how far the layout is from rest after a step that moved nodes
+	/// `energy` graph units on average.
+	fn update_energy(&mut self, energy: f64) {
+		self.energy = energy;
+		if self.settled {
+			if energy > SETTLED_ENERGY * UNSETTLE_FACTOR {
+				self.settled = false;
+				self.energy_peak = energy;
+			}
+		} else if energy < SETTLED_ENERGY {
+			self.settled = true;
+			// Held nodes kept gathering velocity in the simulation while put
+			// back each step; drop it before they are let move again
+			if self.hot_nodes.take().is_some() {
+				self.halt();
+			}
+		} else {
+			self.energy_peak = self.energy_peak.max(energy);
+		}
+	}
+
+	/// Whether the layout has come to rest.
+	pub fn is_settled(&self) -> bool {
+		self.settled
+	}
+
+	/// How far the layout has come to rest, from 0.0 (moving as fast as it has
+	/// since it started settling) to 1.0; `None` once settled or while paused.
+	/// Energy falls roughly exponentially, so progress is measured on a log scale.
+	pub fn settle_progress(&self) -> Option<f64> {
+		if self.settled || !self.animation_running {
+			return None;
+		}
+		let span = (self.energy_peak / SETTLED_ENERGY).ln();
+		if span <= 0.0 {
+			return Some(1.0);
+		}
+		let done = (self.energy_peak / self.energy.max(SETTLED_ENERGY)).ln();
+		Some((done / span).clamp(0.0, 1.0))
+	}
+
+	/// Refresh each node's display geometry from its simulated position, applying
+	/// the fisheye lens when active and pixel alignment when crisp, then place
+	/// the off-screen indicators. Call once per frame before rendering.
+	pub fn prepare_frame(&mut self) {
+		let (t, lens) = (&self.transform, &self.fisheye);
+		let active = lens.is_active();
+		// Snapping moving nodes would make them judder; only still layouts align
+		let align = self.crisp && (self.settled || !self.animation_running);
+		let r = self.pixel_ratio;
+		// World coordinate whose device pixel is the nearest pixel center
+		let snap = |world: f64, offset: f64| {
+			let px = (world * t.k + offset) * r;
+			(((px - 0.5).round() + 0.5) / r - offset) / t.k
+		};
+		// Orbit around the middle of the visible nodes
+		let mut center: (f64, f64, f64, f64) = (0.0, 0.0, 0.0, 0.0);
+		if self.space.is_some() {
+			self.graph.visit_nodes(|node| {
+				if !node.data.user_data.hidden {
+					center.0 += node.x() as f64;
+					center.1 += node.y() as f64;
+					center.2 += node.data.user_data.z as f64;
+					center.3 += 1.0;
+				}
+			});
+		}
+		let n = center.3.max(1.0);
+		let center = (center.0 / n, center.1 / n, center.2 / n);
+		let mut depths = Vec::new();
+		let mut motion: f64 = 0.0;
+		let space = self.space.as_ref();
+		self.graph.visit_nodes_mut(|node| {
+			let (x, y) = (node.data.x as f64, node.data.y as f64);
+			let prev = &node.data.user_data.display;
+			let (prev_x, prev_y, prev_mag) = (prev.x, prev.y, prev.magnification);
+			node.data.user_data.display = if let Some(space) = space {
+				let z = node.data.user_data.z as f64;
+				let (px, py, magnification, depth) = space.project((x, y, z), center);
+				if !node.data.user_data.hidden {
+					depths.push((depth, node.index()));
+				}
+				DisplayGeometry {
+					x: px,
+					y: py,
+					magnification,
+				}
+			} else if active {
+				let (sx, sy, magnification) = lens.distort(x * t.k + t.x, y * t.k + t.y);
+				DisplayGeometry {
+					x: (sx - t.x) / t.k,
+					y: (sy - t.y) / t.k,
+					magnification,
+				}
+			} else {
+				DisplayGeometry {
+					x,
+					y,
+					magnification: 1.0,
+				}
+			};
+			if align {
+				let display = &mut node.data.user_data.display;
+				display.x = snap(display.x, t.x);
+				display.y = snap(display.y, t.y);
+			}
+			let d = &node.data.user_data.display;
+			motion = motion
+				.max((d.x - prev_x).abs().max((d.y - prev_y).abs()) * t.k)
+				.max((d.magnification - prev_mag).abs() * 10.0);
+		});
+		self.motion = motion;
+		if motion > 0.0 {
+			self.label_generation += 1;
+		}
+		if let Some(space) = &mut self.space {
+			depths.sort_by(|a, b| b.0.total_cmp(&a.0));
+			space.order = depths.into_iter().map(|(_, idx)| idx).collect();
+		}
+		self.indicators = self.place_indicators();
+	}
+
+	/// The view resting labels have to be placed for now.
+	pub fn placement_key(&self) -> PlacementKey {
+		PlacementKey::new(
+			&self.transform,
+			(self.width, self.height),
+			self.top_labels,
+			self.label_generation,
+		)
+	}
+
+	/// Flag a change the next frame has to show.
+	pub fn mark_dirty(&mut self) {
+		self.dirty = true;
+	}
+
+	/// Flag a change the next frame has to show on `layers` only.
+	pub fn mark_layers_dirty(&mut self, layers: Layers) {
+		self.dirty_layers |= layers;
+	}
+
+	/// Layers the prepared frame would paint differently from the last one
+	/// drawn, leaving aside purely time-driven effects (see
+	/// `render::animated_layers`).
+	pub fn redraw_layers(&self) -> Layers {
+		if self.dirty
+			|| self.motion > REDRAW_MOTION
+			|| self.drawn_transform.as_ref() != Some(&self.transform)
+			|| self.color_fade < 1.0
+		{
+			return Layers::ALL;
+		}
+		let mut layers = self.dirty_layers;
+		// Selection shows on the nodes and in off-screen indicators
+		if self.selection.changed {
+			layers |= Layers::POINTER;
+		}
+		// Highlighting dims edges through the edge layer's opacity
+		layers.nodes |= self.highlight.is_animating();
+		layers
+	}
+
+	/// Record that the prepared frame has been drawn.
+	pub fn frame_drawn(&mut self) {
+		self.dirty = false;
+		self.dirty_layers = Layers::default();
+		self.selection.changed = false;
+		self.drawn_transform = Some(self.transform.clone());
 	}
 
 	pub fn resize(&mut self, width: f64, height: f64) {
 		self.width = width;
 		self.height = height;
+		self.dirty = true;
 	}
 }
@@ -1,62 +1,70 @@
 //! Visual theming for the force graph.
 //!
-//! Provides color palettes, gradients, and visual style configuration.
+//! Provides color palettes, gradients, and visual style configuration. Without
+//! the `themes` feature only the default theme and what it uses are built.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use super::scale::ScaleOverrides;
 
 /// RGBA color representation.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Color {
+	/// Red channel (0-255).
 	pub r: u8,
+	/// Green channel (0-255).
 	pub g: u8,
+	/// Blue channel (0-255).
 	pub b: u8,
+	/// Alpha (0.0 = transparent, 1.0 = opaque).
 	pub a: f64,
 }
 
 impl Color {
+	/// Opaque color from 8-bit channels.
 	pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
 		Self { r, g, b, a: 1.0 }
 	}
 
+	/// Color from 8-bit channels with alpha.
 	pub const fn rgba(r: u8, g: u8, b: u8, a: f64) -> Self {
 		Self { r, g, b, a }
 	}
 
+	/// Same color with a different alpha.
 	pub fn with_alpha(self, a: f64) -> Self {
 		Self { a, ..self }
 	}
 
-	/// Lighten the color by a factor (0.0 = unchanged, 1.0 = white)
+	/// Lighten the color by a factor (0.0 = unchanged, 1.0 = white), blending in
+	/// OKLab so tints keep their hue.
 	pub fn lighten(self, factor: f64) -> Self {
-		let f = factor.clamp(0.0, 1.0);
-		Self {
-			r: (self.r as f64 + (255.0 - self.r as f64) * f) as u8,
-			g: (self.g as f64 + (255.0 - self.g as f64) * f) as u8,
-			b: (self.b as f64 + (255.0 - self.b as f64) * f) as u8,
-			a: self.a,
-		}
+		self.lerp(Color::rgba(255, 255, 255, self.a), factor)
 	}
 
-	/// Darken the color by a factor (0.0 = unchanged, 1.0 = black)
+	/// Darken the color by a factor (0.0 = unchanged, 1.0 = black), blending in
+	/// OKLab so shades keep their hue.
 	pub fn darken(self, factor: f64) -> Self {
-		let f = 1.0 - factor.clamp(0.0, 1.0);
-		Self {
-			r: (self.r as f64 * f) as u8,
-			g: (self.g as f64 * f) as u8,
-			b: (self.b as f64 * f) as u8,
-			a: self.a,
-		}
+		self.lerp(Color::rgba(0, 0, 0, self.a), factor)
 	}
 
-	/// Linear interpolation between two colors
+	/// Interpolation between two colors in OKLab, which avoids the muddy, darkened
+	/// midpoints of blending raw sRGB channels. Alpha is interpolated linearly.
 	pub fn lerp(self, other: Color, t: f64) -> Self {
 		let t = t.clamp(0.0, 1.0);
-		Self {
-			r: (self.r as f64 * (1.0 - t) + other.r as f64 * t) as u8,
-			g: (self.g as f64 * (1.0 - t) + other.g as f64 * t) as u8,
-			b: (self.b as f64 * (1.0 - t) + other.b as f64 * t) as u8,
-			a: self.a * (1.0 - t) + other.a * t,
-		}
+		let ((l1, a1, b1), (l2, a2, b2)) = (self.to_oklab(), other.to_oklab());
+		Self::from_oklab(
+			l1 + (l2 - l1) * t,
+			a1 + (a2 - a1) * t,
+			b1 + (b2 - b1) * t,
+			self.a + (other.a - self.a) * t,
+		)
 	}
 
+	/// CSS representation: `#rrggbb` when opaque, `rgba(...)` otherwise.
 	pub fn to_css(self) -> String {
 		if (self.a - 1.0).abs() < 0.001 {
 			format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
@@ -65,14 +73,164 @@ impl Color {
 		}
 	}
 
+	/// Channels with alpha quantized to 1/255, packed into one integer.
+	fn key(self) -> u32 {
+		let a = (self.a.clamp(0.0, 1.0) * 255.0).round() as u32;
+		(self.r as u32) << 24 | (self.g as u32) << 16 | (self.b as u32) << 8 | a
+	}
+
+	/// CSS `#rrggbb` representation, ignoring alpha.
 	pub fn to_css_rgb(self) -> String {
 		format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
 	}
+
+	/// Parses a CSS color string into a [`Color`].
+	/// Supports hex (`#RRGGBB`) and `rgb()`/`rgba()` functional notation.
+	pub fn parse(color_str: &str) -> Self {
+		if color_str.starts_with('#') && color_str.len() == 7 {
+			let r = u8::from_str_radix(&color_str[1..3], 16).unwrap_or(128);
+			let g = u8::from_str_radix(&color_str[3..5], 16).unwrap_or(128);
+			let b = u8::from_str_radix(&color_str[5..7], 16).unwrap_or(128);
+			Color::rgb(r, g, b)
+		} else if color_str.starts_with("rgb") {
+			let nums: Vec<&str> = color_str
+				.trim_start_matches("rgba(")
+				.trim_start_matches("rgb(")
+				.trim_end_matches(')')
+				.split(',')
+				.collect();
+			let r = nums
+				.first()
+				.and_then(|s| s.trim().parse().ok())
+				.unwrap_or(128);
+			let g = nums
+				.get(1)
+				.and_then(|s| s.trim().parse().ok())
+				.unwrap_or(128);
+			let b = nums
+				.get(2)
+				.and_then(|s| s.trim().parse().ok())
+				.unwrap_or(128);
+			let a = nums
+				.get(3)
+				.and_then(|s| s.trim().parse().ok())
+				.unwrap_or(1.0);
+			Color::rgba(r, g, b, a)
+		} else {
+			Color::rgb(128, 128, 128)
+		}
+	}
+
+	/// OKLab coordinates `(L, a, b)` of this color, ignoring alpha.
+	pub fn to_oklab(self) -> (f64, f64, f64) {
+		let [r, g, b] = [self.r, self.g, self.b].map(|c| srgb_to_linear(c as f64 / 255.0));
+		let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+		let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+		let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+		(
+			0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+			1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+			0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+		)
+	}
+
+	/// Color from OKLab coordinates, clipped to the sRGB gamut.
+	pub fn from_oklab(l: f64, a: f64, b: f64, alpha: f64) -> Self {
+		let [r, g, b] = oklab_to_linear(l, a, b)
+			.map(|c| (linear_to_srgb(c.clamp(0.0, 1.0)) * 255.0).round() as u8);
+		Self { r, g, b, a: alpha }
+	}
+
+	/// OKLCH coordinates `(L, C, h)` of this color, with hue in degrees.
+	pub fn to_oklch(self) -> (f64, f64, f64) {
+		let (l, a, b) = self.to_oklab();
+		(l, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0))
+	}
+
+	/// Opaque color from OKLCH coordinates (hue in degrees). Chroma is reduced as
+	/// needed to stay inside the sRGB gamut, so the hue is preserved.
+	pub fn from_oklch(l: f64, c: f64, h: f64) -> Self {
+		let (sin, cos) = h.to_radians().sin_cos();
+		let mut chroma = c.max(0.0);
+		while chroma > 0.0
+			&& oklab_to_linear(l, chroma * cos, chroma * sin)
+				.iter()
+				.any(|v| !(-1e-4..=1.0 + 1e-4).contains(v))
+		{
+			chroma = (chroma - 0.005).max(0.0);
+		}
+		Self::from_oklab(l, chroma * cos, chroma * sin, 1.0)
+	}
+}
+
+/// sRGB transfer function: encoded channel (0-1) to linear light.
+fn srgb_to_linear(c: f64) -> f64 {
+	if c <= 0.04045 {
+		c / 12.92
+	} else {
+		((c + 0.055) / 1.055).powf(2.4)
+	}
+}
+
+/// Inverse sRGB transfer function: linear light to encoded channel (0-1).
+fn linear_to_srgb(c: f64) -> f64 {
+	if c <= 0.0031308 {
+		12.92 * c
+	} else {
+		1.055 * c.powf(1.0 / 2.4) - 0.055
+	}
+}
+
+/// OKLab to linear sRGB, unclamped (values outside 0-1 are out of gamut).
+fn oklab_to_linear(l: f64, a: f64, b: f64) -> [f64; 3] {
+	let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+	let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+	let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+	[
+		4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+		-1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+		-0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+	]
+}
+
+/// Interned CSS strings for colors. Colors that differ by less than 1/255 in
+/// alpha share a string, so animated alphas still hit the cache; it is emptied
+/// when it grows past a bound, as lerped colors can be endlessly varied.
+#[derive(Debug, Default)]
+pub struct CssCache {
+	strings: RefCell<HashMap<u32, Rc<str>>>,
+}
+
+/// Entries [`CssCache`] keeps before starting over.
+const CSS_CACHE_LIMIT: usize = 8192;
+
+impl CssCache {
+	/// Number of interned strings.
+	pub fn entries(&self) -> usize {
+		self.strings.borrow().len()
+	}
+
+	/// CSS string for `color`, formatted on first use.
+	pub fn get(&self, color: Color) -> Rc<str> {
+		let key = color.key();
+		let mut strings = self.strings.borrow_mut();
+		if let Some(css) = strings.get(&key) {
+			return css.clone();
+		}
+		if strings.len() >= CSS_CACHE_LIMIT {
+			strings.clear();
+		}
+		let quantized = color.with_alpha((key & 0xff) as f64 / 255.0);
+		let css: Rc<str> = quantized.to_css().into();
+		strings.insert(key, css.clone());
+		css
+	}
 }
 
 /// A curated color palette for nodes.
 #[derive(Clone, Debug)]
 pub struct NodePalette {
+	/// Colors cycled through by index.
 	pub colors: Vec<Color>,
 }
 
@@ -94,6 +252,7 @@ impl NodePalette {
 	}
 
 	/// Warm earth tones - muted oranges and browns
+	#[cfg(feature = "themes")]
 	pub fn earth() -> Self {
 		Self {
 			colors: vec![
@@ -110,6 +269,7 @@ impl NodePalette {
 	}
 
 	/// Soft pastel palette - gentle, pleasing colors
+	#[cfg(feature = "themes")]
 	pub fn pastel() -> Self {
 		Self {
 			colors: vec![
@@ -126,6 +286,7 @@ impl NodePalette {
 	}
 
 	/// Ocean depths palette - blues and teals
+	#[cfg(feature = "themes")]
 	pub fn ocean() -> Self {
 		Self {
 			colors: vec![
@@ -142,6 +303,7 @@ impl NodePalette {
 	}
 
 	/// Sunset palette - warm muted tones
+	#[cfg(feature = "themes")]
 	pub fn sunset() -> Self {
 		Self {
 			colors: vec![
@@ -158,6 +320,7 @@ impl NodePalette {
 	}
 
 	/// Aurora palette - cool teals and purples
+	#[cfg(feature = "themes")]
 	pub fn aurora() -> Self {
 		Self {
 			colors: vec![
@@ -173,9 +336,129 @@ impl NodePalette {
 		}
 	}
 
+	/// Color at `index`, wrapping around the palette.
 	pub fn get(&self, index: usize) -> Color {
 		self.colors[index % self.colors.len()]
 	}
+
+	/// `count` distinct colors with hues evenly spaced around the OKLCH wheel,
+	/// matching the average lightness and chroma of `like` so they sit well in the
+	/// same theme. Chroma is kept high enough for neighboring hues to stay apart.
+	pub fn generate(count: usize, like: &NodePalette) -> Self {
+		let n = like.colors.len().max(1) as f64;
+		let (l, c, h) = like
+			.colors
+			.iter()
+			.map(|color| color.to_oklch())
+			.fold((0.0, 0.0, None), |(l, c, h), (cl, cc, ch)| {
+				(l + cl / n, c + cc / n, h.or(Some(ch)))
+			});
+		let (l, c) = if like.colors.is_empty() {
+			(0.7, 0.12)
+		} else {
+			(l.clamp(0.55, 0.8), c.max(0.09))
+		};
+		let start = h.unwrap_or(0.0);
+		let step = 360.0 / count.max(1) as f64;
+		Self {
+			colors: (0..count.max(1))
+				.map(|i| Color::from_oklch(l, c, start + step * i as f64))
+				.collect(),
+		}
+	}
+}
+
+/// A sequential color scale for mapping numeric values to colors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorRamp {
+	/// Evenly spaced color stops from the low end to the high end.
+	pub stops: Vec<Color>,
+}
+
+impl ColorRamp {
+	/// Perceptually uniform blue-green-yellow ramp (default)
+	pub fn viridis() -> Self {
+		Self {
+			stops: vec![
+				Color::rgb(68, 1, 84),
+				Color::rgb(59, 82, 139),
+				Color::rgb(33, 145, 140),
+				Color::rgb(94, 201, 98),
+				Color::rgb(253, 231, 37),
+			],
+		}
+	}
+
+	/// Dark purple through orange to pale yellow
+	#[cfg(feature = "themes")]
+	pub fn magma() -> Self {
+		Self {
+			stops: vec![
+				Color::rgb(28, 16, 68),
+				Color::rgb(114, 31, 129),
+				Color::rgb(205, 64, 113),
+				Color::rgb(253, 150, 104),
+				Color::rgb(252, 253, 191),
+			],
+		}
+	}
+
+	/// Single-hue ramp from deep navy to pale blue
+	#[cfg(feature = "themes")]
+	pub fn blues() -> Self {
+		Self {
+			stops: vec![
+				Color::rgb(22, 50, 92),
+				Color::rgb(45, 100, 160),
+				Color::rgb(90, 150, 200),
+				Color::rgb(160, 200, 228),
+			],
+		}
+	}
+
+	/// Color at position `t` (0.0 = low end, 1.0 = high end).
+	pub fn sample(&self, t: f64) -> Color {
+		match self.stops.len() {
+			0 => Color::rgb(128, 128, 128),
+			1 => self.stops[0],
+			n => {
+				let pos = t.clamp(0.0, 1.0) * (n - 1) as f64;
+				let i = (pos.floor() as usize).min(n - 2);
+				self.stops[i].lerp(self.stops[i + 1], pos - i as f64)
+			}
+		}
+	}
+
+	/// CSS `linear-gradient` running left to right through the stops.
+	pub fn to_css_gradient(&self) -> String {
+		let stops: Vec<String> = self.stops.iter().map(|c| c.to_css()).collect();
+		format!("linear-gradient(to right, {})", stops.join(", "))
+	}
+}
+
+/// Decorative pattern drawn beneath the graph for a sense of place and motion.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BackgroundPattern {
+	/// No pattern.
+	#[default]
+	None,
+	/// A grid of dots `spacing` pixels apart.
+	Dots {
+		/// Distance between dots at 100% zoom, in pixels.
+		spacing: f64,
+		/// Dot radius in pixels.
+		radius: f64,
+	},
+	/// A grid of hairlines `spacing` pixels apart.
+	Lines {
+		/// Distance between lines at 100% zoom, in pixels.
+		spacing: f64,
+	},
+	/// Scattered stars of varying brightness.
+	Starfield {
+		/// Stars per 256×256 pixel tile.
+		density: u32,
+	},
 }
 
 /// Background style configuration.
@@ -189,6 +472,14 @@ pub struct BackgroundStyle {
 	pub use_gradient: bool,
 	/// Vignette intensity (0.0 = none, 1.0 = strong)
 	pub vignette: f64,
+	/// Line color of the snap grid, when grid snapping is on
+	pub grid_color: Color,
+	/// Pattern layer beneath the graph
+	pub pattern: BackgroundPattern,
+	/// Pattern color (dots, lines, brightest stars)
+	pub pattern_color: Color,
+	/// How far the pattern follows pan and zoom (0.0 = fixed, 1.0 = moves with the graph)
+	pub pattern_parallax: f64,
 }
 
 /// Edge visual style.
@@ -204,6 +495,36 @@ pub struct EdgeStyle {
 	pub curved: bool,
 	/// Curve tension (0.0 = straight, 1.0 = very curved)
 	pub curve_tension: f64,
+	/// How each edge's color is chosen
+	pub coloring: EdgeColoring,
+}
+
+impl EdgeStyle {
+	/// Color for an edge of `kind` leaving a node filled with `source`.
+	pub fn color_for(&self, kind: Option<&str>, source: Color) -> Color {
+		match &self.coloring {
+			EdgeColoring::Uniform => self.color,
+			EdgeColoring::Source { alpha } => source.with_alpha(*alpha),
+			EdgeColoring::Kind(colors) => kind
+				.and_then(|k| colors.get(k).copied())
+				.unwrap_or(self.color),
+		}
+	}
+}
+
+/// Edge color resolver.
+#[derive(Clone, Debug)]
+pub enum EdgeColoring {
+	/// Every edge uses [`EdgeStyle::color`].
+	Uniform,
+	/// Edges take their source node's color at the given alpha, tinting each
+	/// cluster's outgoing links.
+	Source {
+		/// Opacity applied to the source color
+		alpha: f64,
+	},
+	/// Edges are colored by their `kind`; unknown kinds use [`EdgeStyle::color`].
+	Kind(HashMap<String, Color>),
 }
 
 /// Node visual style.
@@ -219,10 +540,64 @@ pub struct NodeStyle {
 	pub border_width: f64,
 	/// Border color
 	pub border_color: Color,
+	/// Label text color
+	pub label_color: Color,
 	/// Pulsing animation intensity (0.0 = none)
 	pub pulse_intensity: f64,
 	/// Pulsing animation speed
 	pub pulse_speed: f64,
+	/// Border overrides by group, checked in order; the first match wins
+	pub group_borders: Vec<GroupBorder>,
+}
+
+impl NodeStyle {
+	/// Border override for a node in `group`, if any rule matches.
+	pub fn border_for(&self, group: Option<&str>) -> Option<&GroupBorder> {
+		let group = group?;
+		self.group_borders.iter().find(|b| b.matches(group))
+	}
+}
+
+/// Border drawn around nodes of matching groups, as a second encoding channel
+/// alongside the fill color.
+#[derive(Clone, Debug)]
+pub struct GroupBorder {
+	/// Group name, or a prefix ending in `*` (e.g. `hosts.*`)
+	pub group: String,
+	/// Border color
+	pub color: Color,
+	/// Border width in screen pixels
+	pub width: f64,
+}
+
+impl GroupBorder {
+	/// Whether this rule applies to `group`.
+	pub fn matches(&self, group: &str) -> bool {
+		match self.group.strip_suffix('*') {
+			Some(prefix) => group.starts_with(prefix),
+			None => self.group == group,
+		}
+	}
+}
+
+/// Node badge (status marker) style.
+#[derive(Clone, Debug)]
+pub struct BadgeStyle {
+	/// Default badge fill color (nodes may override via `badge_color`)
+	pub color: Color,
+	/// Badge text color
+	pub text_color: Color,
+}
+
+/// How selected nodes stand out from hovered and highlighted ones.
+#[derive(Clone, Debug)]
+pub struct SelectionStyle {
+	/// Color of the ring around selected nodes
+	pub ring_color: Color,
+	/// Tint laid over the node fill; its alpha sets the strength
+	pub fill_tint: Color,
+	/// Glow intensity in the ring color (0.0 = none)
+	pub glow: f64,
 }
 
 /// Particle effect configuration.
@@ -232,6 +607,9 @@ pub struct ParticleStyle {
 	pub enabled: bool,
 	/// Number of particles
 	pub count: usize,
+	/// Particles per 10,000 px² of canvas; when set, replaces `count` so large
+	/// screens and small embeds look equally busy
+	pub density: Option<f64>,
 	/// Particle color
 	pub color: Color,
 	/// Minimum particle size
@@ -242,17 +620,44 @@ pub struct ParticleStyle {
 	pub speed: f64,
 	/// Particle opacity
 	pub opacity: f64,
+	/// How strongly particles stream around nodes instead of drifting straight
+	/// through them (0.0 = not at all, 1.0 = fully)
+	pub deflect: f64,
 }
 
 /// Complete visual theme.
 #[derive(Clone, Debug)]
 pub struct Theme {
+	/// Theme identifier.
 	pub name: &'static str,
+	/// Canvas background.
 	pub background: BackgroundStyle,
+	/// Edge appearance.
 	pub edge: EdgeStyle,
+	/// Node appearance.
 	pub node: NodeStyle,
+	/// Node badge appearance.
+	pub badge: BadgeStyle,
+	/// Selected node appearance.
+	pub selection: SelectionStyle,
+	/// Ambient particle effects.
 	pub particles: ParticleStyle,
+	/// Fallback node colors for nodes without a group color.
 	pub palette: NodePalette,
+	/// Color scale for metric heatmaps.
+	pub heat_ramp: ColorRamp,
+	/// Changes to the configured scaling this theme's look depends on.
+	pub scale: ScaleOverrides,
+}
+
+impl ParticleStyle {
+	/// Number of particles for a `width` × `height` canvas.
+	pub fn count_for(&self, width: f64, height: f64) -> usize {
+		match self.density {
+			Some(density) => (density.max(0.0) * width * height / 10_000.0).round() as usize,
+			None => self.count,
+		}
+	}
 }
 
 impl Theme {
@@ -265,6 +670,10 @@ impl Theme {
 				color_secondary: Color::rgb(30, 35, 42),
 				use_gradient: true,
 				vignette: 0.15,
+				grid_color: Color::rgba(160, 180, 200, 0.06),
+				pattern: BackgroundPattern::None,
+				pattern_color: Color::rgba(160, 180, 200, 0.08),
+				pattern_parallax: 0.3,
 			},
 			edge: EdgeStyle {
 				color: Color::rgba(140, 160, 180, 0.5),
@@ -272,6 +681,7 @@ impl Theme {
 				glow_intensity: 0.0,
 				curved: false,
 				curve_tension: 0.0,
+				coloring: EdgeColoring::Uniform,
 			},
 			node: NodeStyle {
 				use_gradient: true,
@@ -279,23 +689,103 @@ impl Theme {
 				glow_saturation: 0.0,
 				border_width: 0.0,
 				border_color: Color::rgba(255, 255, 255, 0.0),
+				label_color: Color::rgba(255, 255, 255, 0.95),
 				pulse_intensity: 0.0,
 				pulse_speed: 0.0,
+				group_borders: Vec::new(),
+			},
+			badge: BadgeStyle {
+				color: Color::rgb(218, 54, 51),
+				text_color: Color::rgb(255, 255, 255),
+			},
+			selection: SelectionStyle {
+				ring_color: Color::rgba(140, 180, 255, 0.9),
+				fill_tint: Color::rgba(140, 180, 255, 0.15),
+				glow: 0.6,
 			},
 			particles: ParticleStyle {
 				enabled: false,
 				count: 0,
+				density: None,
 				color: Color::rgba(0, 0, 0, 0.0),
 				size_min: 0.0,
 				size_max: 0.0,
 				speed: 0.0,
 				opacity: 0.0,
+				deflect: 0.0,
 			},
 			palette: NodePalette::slate(),
+			heat_ramp: ColorRamp::viridis(),
+			scale: ScaleOverrides::default(),
+		}
+	}
+
+	/// Flat light theme for printing: white paper, dark edges and labels, no
+	/// effects.
+	pub fn print() -> Self {
+		Self {
+			name: "print",
+			background: BackgroundStyle {
+				color: Color::rgb(255, 255, 255),
+				color_secondary: Color::rgb(255, 255, 255),
+				use_gradient: false,
+				vignette: 0.0,
+				grid_color: Color::rgba(0, 0, 0, 0.08),
+				pattern: BackgroundPattern::None,
+				pattern_color: Color::rgba(0, 0, 0, 0.0),
+				pattern_parallax: 0.0,
+			},
+			edge: EdgeStyle {
+				color: Color::rgba(40, 45, 55, 0.7),
+				glow_color: Color::rgba(0, 0, 0, 0.0),
+				glow_intensity: 0.0,
+				curved: false,
+				curve_tension: 0.0,
+				coloring: EdgeColoring::Uniform,
+			},
+			node: NodeStyle {
+				use_gradient: false,
+				glow_intensity: 0.0,
+				glow_saturation: 0.0,
+				border_width: 1.0,
+				border_color: Color::rgba(30, 35, 45, 0.8),
+				label_color: Color::rgb(20, 24, 30),
+				pulse_intensity: 0.0,
+				pulse_speed: 0.0,
+				group_borders: Vec::new(),
+			},
+			badge: BadgeStyle {
+				color: Color::rgb(190, 40, 40),
+				text_color: Color::rgb(255, 255, 255),
+			},
+			selection: SelectionStyle {
+				ring_color: Color::rgb(20, 24, 30),
+				fill_tint: Color::rgba(0, 0, 0, 0.0),
+				glow: 0.0,
+			},
+			particles: ParticleStyle {
+				enabled: false,
+				count: 0,
+				density: None,
+				color: Color::rgba(0, 0, 0, 0.0),
+				size_min: 0.0,
+				size_max: 0.0,
+				speed: 0.0,
+				opacity: 0.0,
+				deflect: 0.0,
+			},
+			palette: NodePalette::slate(),
+			heat_ramp: ColorRamp::viridis(),
+			scale: ScaleOverrides {
+				dash_pattern: Some((8.0, 0.0)),
+				flow_speed: Some(0.0),
+				..ScaleOverrides::default()
+			},
 		}
 	}
 
 	/// Elegant dark theme with subtle effects
+	#[cfg(feature = "themes")]
 	pub fn midnight() -> Self {
 		Self {
 			name: "midnight",
@@ -304,6 +794,10 @@ impl Theme {
 				color_secondary: Color::rgb(25, 28, 38),
 				use_gradient: true,
 				vignette: 0.2,
+				grid_color: Color::rgba(150, 160, 220, 0.06),
+				pattern: BackgroundPattern::Starfield { density: 24 },
+				pattern_color: Color::rgba(200, 210, 255, 0.5),
+				pattern_parallax: 0.3,
 			},
 			edge: EdgeStyle {
 				color: Color::rgba(100, 120, 150, 0.45),
@@ -311,6 +805,7 @@ impl Theme {
 				glow_intensity: 0.0,
 				curved: false,
 				curve_tension: 0.0,
+				coloring: EdgeColoring::Uniform,
 			},
 			node: NodeStyle {
 				use_gradient: true,
@@ -318,23 +813,39 @@ impl Theme {
 				glow_saturation: 0.0,
 				border_width: 0.0,
 				border_color: Color::rgba(255, 255, 255, 0.0),
+				label_color: Color::rgba(255, 255, 255, 0.95),
 				pulse_intensity: 0.0,
 				pulse_speed: 0.0,
+				group_borders: Vec::new(),
+			},
+			badge: BadgeStyle {
+				color: Color::rgb(200, 70, 90),
+				text_color: Color::rgb(255, 255, 255),
+			},
+			selection: SelectionStyle {
+				ring_color: Color::rgba(150, 160, 255, 0.9),
+				fill_tint: Color::rgba(150, 160, 255, 0.15),
+				glow: 0.7,
 			},
 			particles: ParticleStyle {
 				enabled: false,
 				count: 0,
+				density: None,
 				color: Color::rgba(0, 0, 0, 0.0),
 				size_min: 0.0,
 				size_max: 0.0,
 				speed: 0.0,
 				opacity: 0.0,
+				deflect: 0.0,
 			},
 			palette: NodePalette::aurora(),
+			heat_ramp: ColorRamp::viridis(),
+			scale: ScaleOverrides::default(),
 		}
 	}
 
 	/// Warm earth tones theme
+	#[cfg(feature = "themes")]
 	pub fn ember() -> Self {
 		Self {
 			name: "ember",
@@ -343,6 +854,10 @@ impl Theme {
 				color_secondary: Color::rgb(35, 30, 28),
 				use_gradient: true,
 				vignette: 0.18,
+				grid_color: Color::rgba(220, 170, 130, 0.06),
+				pattern: BackgroundPattern::None,
+				pattern_color: Color::rgba(220, 170, 130, 0.08),
+				pattern_parallax: 0.3,
 			},
 			edge: EdgeStyle {
 				color: Color::rgba(160, 130, 110, 0.45),
@@ -350,6 +865,7 @@ impl Theme {
 				glow_intensity: 0.0,
 				curved: false,
 				curve_tension: 0.0,
+				coloring: EdgeColoring::Uniform,
 			},
 			node: NodeStyle {
 				use_gradient: true,
@@ -357,23 +873,39 @@ impl Theme {
 				glow_saturation: 0.0,
 				border_width: 0.0,
 				border_color: Color::rgba(255, 255, 255, 0.0),
+				label_color: Color::rgba(255, 255, 255, 0.95),
 				pulse_intensity: 0.0,
 				pulse_speed: 0.0,
+				group_borders: Vec::new(),
+			},
+			badge: BadgeStyle {
+				color: Color::rgb(210, 95, 60),
+				text_color: Color::rgb(255, 255, 255),
+			},
+			selection: SelectionStyle {
+				ring_color: Color::rgba(255, 190, 120, 0.9),
+				fill_tint: Color::rgba(255, 190, 120, 0.15),
+				glow: 0.6,
 			},
 			particles: ParticleStyle {
 				enabled: false,
 				count: 0,
+				density: None,
 				color: Color::rgba(0, 0, 0, 0.0),
 				size_min: 0.0,
 				size_max: 0.0,
 				speed: 0.0,
 				opacity: 0.0,
+				deflect: 0.0,
 			},
 			palette: NodePalette::earth(),
+			heat_ramp: ColorRamp::magma(),
+			scale: ScaleOverrides::default(),
 		}
 	}
 
 	/// Ocean/deep blue theme
+	#[cfg(feature = "themes")]
 	pub fn deep_sea() -> Self {
 		Self {
 			name: "deep_sea",
@@ -382,6 +914,10 @@ impl Theme {
 				color_secondary: Color::rgb(20, 32, 45),
 				use_gradient: true,
 				vignette: 0.2,
+				grid_color: Color::rgba(120, 190, 210, 0.06),
+				pattern: BackgroundPattern::None,
+				pattern_color: Color::rgba(120, 190, 210, 0.08),
+				pattern_parallax: 0.3,
 			},
 			edge: EdgeStyle {
 				color: Color::rgba(90, 130, 160, 0.45),
@@ -389,6 +925,7 @@ impl Theme {
 				glow_intensity: 0.0,
 				curved: false,
 				curve_tension: 0.0,
+				coloring: EdgeColoring::Uniform,
 			},
 			node: NodeStyle {
 				use_gradient: true,
@@ -396,23 +933,39 @@ impl Theme {
 				glow_saturation: 0.0,
 				border_width: 0.0,
 				border_color: Color::rgba(255, 255, 255, 0.0),
+				label_color: Color::rgba(255, 255, 255, 0.95),
 				pulse_intensity: 0.0,
 				pulse_speed: 0.0,
+				group_borders: Vec::new(),
+			},
+			badge: BadgeStyle {
+				color: Color::rgb(220, 80, 80),
+				text_color: Color::rgb(255, 255, 255),
+			},
+			selection: SelectionStyle {
+				ring_color: Color::rgba(100, 220, 230, 0.9),
+				fill_tint: Color::rgba(100, 220, 230, 0.15),
+				glow: 0.6,
 			},
 			particles: ParticleStyle {
 				enabled: false,
 				count: 0,
+				density: None,
 				color: Color::rgba(0, 0, 0, 0.0),
 				size_min: 0.0,
 				size_max: 0.0,
 				speed: 0.0,
 				opacity: 0.0,
+				deflect: 0.0,
 			},
 			palette: NodePalette::ocean(),
+			heat_ramp: ColorRamp::blues(),
+			scale: ScaleOverrides::default(),
 		}
 	}
 
 	/// Minimal, ultra-clean theme
+	#[cfg(feature = "themes")]
 	pub fn minimal() -> Self {
 		Self {
 			name: "minimal",
@@ -421,6 +974,13 @@ impl Theme {
 				color_secondary: Color::rgb(25, 28, 35),
 				use_gradient: false,
 				vignette: 0.0,
+				grid_color: Color::rgba(160, 170, 185, 0.05),
+				pattern: BackgroundPattern::Dots {
+					spacing: 32.0,
+					radius: 1.0,
+				},
+				pattern_color: Color::rgba(160, 170, 185, 0.12),
+				pattern_parallax: 0.3,
 			},
 			edge: EdgeStyle {
 				color: Color::rgba(130, 145, 165, 0.4),
@@ -428,6 +988,7 @@ impl Theme {
 				glow_intensity: 0.0,
 				curved: false,
 				curve_tension: 0.0,
+				coloring: EdgeColoring::Uniform,
 			},
 			node: NodeStyle {
 				use_gradient: false,
@@ -435,19 +996,38 @@ impl Theme {
 				glow_saturation: 0.0,
 				border_width: 0.0,
 				border_color: Color::rgba(255, 255, 255, 0.0),
+				label_color: Color::rgba(255, 255, 255, 0.95),
 				pulse_intensity: 0.0,
 				pulse_speed: 0.0,
+				group_borders: Vec::new(),
+			},
+			badge: BadgeStyle {
+				color: Color::rgb(200, 90, 90),
+				text_color: Color::rgb(255, 255, 255),
+			},
+			selection: SelectionStyle {
+				ring_color: Color::rgba(140, 180, 255, 0.9),
+				fill_tint: Color::rgba(140, 180, 255, 0.12),
+				glow: 0.0,
 			},
 			particles: ParticleStyle {
 				enabled: false,
 				count: 0,
+				density: None,
 				color: Color::rgba(0, 0, 0, 0.0),
 				size_min: 0.0,
 				size_max: 0.0,
 				speed: 0.0,
 				opacity: 0.0,
+				deflect: 0.0,
 			},
 			palette: NodePalette::pastel(),
+			heat_ramp: ColorRamp::viridis(),
+			scale: ScaleOverrides {
+				edge_line_width: Some(1.0),
+				dash_pattern: Some((8.0, 0.0)),
+				..ScaleOverrides::default()
+			},
 		}
 	}
 }
@@ -457,3 +1037,185 @@ impl Default for Theme {
 		Self::default_theme()
 	}
 }
+
+impl Theme {
+	/// The look `t` of the way (0.0 to 1.0) from `self` to `other`, for cross-fading
+	/// between themes. Colors and intensities blend; settings that can't, such as
+	/// the background pattern, switch halfway.
+	pub fn blend(&self, other: &Theme, t: f64) -> Theme {
+		let t = t.clamp(0.0, 1.0);
+		let mix = |a: f64, b: f64| a + (b - a) * t;
+		let (bg1, bg2) = (&self.background, &other.background);
+		// A flat background is a gradient between equal colors
+		let secondary = |bg: &BackgroundStyle| {
+			if bg.use_gradient {
+				bg.color_secondary
+			} else {
+				bg.color
+			}
+		};
+		// A straight edge is a curve without tension
+		let tension = |edge: &EdgeStyle| if edge.curved { edge.curve_tension } else { 0.0 };
+		let (e1, e2) = (&self.edge, &other.edge);
+		let (n1, n2) = (&self.node, &other.node);
+		Theme {
+			name: halfway(self.name, other.name, t),
+			background: BackgroundStyle {
+				color: bg1.color.lerp(bg2.color, t),
+				color_secondary: secondary(bg1).lerp(secondary(bg2), t),
+				use_gradient: bg1.use_gradient || bg2.use_gradient,
+				vignette: mix(bg1.vignette, bg2.vignette),
+				grid_color: bg1.grid_color.lerp(bg2.grid_color, t),
+				pattern: halfway(bg1.pattern, bg2.pattern, t),
+				pattern_color: bg1.pattern_color.lerp(bg2.pattern_color, t),
+				pattern_parallax: mix(bg1.pattern_parallax, bg2.pattern_parallax),
+			},
+			edge: EdgeStyle {
+				color: e1.color.lerp(e2.color, t),
+				glow_color: e1.glow_color.lerp(e2.glow_color, t),
+				glow_intensity: mix(e1.glow_intensity, e2.glow_intensity),
+				curved: e1.curved || e2.curved,
+				curve_tension: mix(tension(e1), tension(e2)),
+				coloring: halfway(&e1.coloring, &e2.coloring, t).clone(),
+			},
+			node: NodeStyle {
+				use_gradient: halfway(n1.use_gradient, n2.use_gradient, t),
+				glow_intensity: mix(n1.glow_intensity, n2.glow_intensity),
+				glow_saturation: mix(n1.glow_saturation, n2.glow_saturation),
+				border_width: mix(n1.border_width, n2.border_width),
+				border_color: n1.border_color.lerp(n2.border_color, t),
+				label_color: n1.label_color.lerp(n2.label_color, t),
+				pulse_intensity: mix(n1.pulse_intensity, n2.pulse_intensity),
+				// Blending the speed would jump the pulse phase
+				pulse_speed: n2.pulse_speed,
+				group_borders: halfway(&n1.group_borders, &n2.group_borders, t).clone(),
+			},
+			badge: BadgeStyle {
+				color: self.badge.color.lerp(other.badge.color, t),
+				text_color: self.badge.text_color.lerp(other.badge.text_color, t),
+			},
+			selection: SelectionStyle {
+				ring_color: self
+					.selection
+					.ring_color
+					.lerp(other.selection.ring_color, t),
+				fill_tint: self.selection.fill_tint.lerp(other.selection.fill_tint, t),
+				glow: mix(self.selection.glow, other.selection.glow),
+			},
+			particles: other.particles.clone(),
+			// Node fills fade to the new colors on their own (see `recolor`)
+			palette: other.palette.clone(),
+			heat_ramp: other.heat_ramp.clone(),
+			scale: other.scale.clone(),
+		}
+	}
+}
+
+/// A theme setting that has no effect as configured, from [`Theme::validate`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThemeWarning {
+	/// Path of the offending field (e.g. `particles.count`).
+	pub field: &'static str,
+	/// What is wrong and how to fix it.
+	pub message: &'static str,
+}
+
+impl fmt::Display for ThemeWarning {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}", self.field, self.message)
+	}
+}
+
+impl Theme {
+	/// Settings that silently do nothing, such as a glow in a fully transparent
+	/// color or curve tension on straight edges. An empty list means every
+	/// setting takes effect.
+	pub fn validate(&self) -> Vec<ThemeWarning> {
+		let (bg, edge, node, particles) =
+			(&self.background, &self.edge, &self.node, &self.particles);
+		let checks = [
+			(
+				particles.enabled
+					&& particles.count == 0
+					&& particles.density.is_none_or(|d| d <= 0.0),
+				"particles.count",
+				"particles are enabled but the count is 0; set a count or density, or disable them",
+			),
+			(
+				particles.density.is_some() && particles.count > 0,
+				"particles.count",
+				"a density is set, so the fixed particle count is ignored",
+			),
+			(
+				particles.enabled && (particles.opacity <= 0.0 || particles.color.a <= 0.0),
+				"particles.opacity",
+				"particles are enabled but fully transparent; raise the opacity or color alpha",
+			),
+			(
+				particles.size_min > particles.size_max,
+				"particles.size_min",
+				"the minimum particle size exceeds the maximum",
+			),
+			(
+				!(0.0..=1.0).contains(&particles.deflect),
+				"particles.deflect",
+				"particle deflection should be between 0.0 and 1.0",
+			),
+			(
+				edge.glow_intensity > 0.0 && edge.glow_color.a <= 0.0,
+				"edge.glow_color",
+				"edge glow intensity is set but the glow color is fully transparent",
+			),
+			(
+				edge.curve_tension != 0.0 && !edge.curved,
+				"edge.curve_tension",
+				"curve tension has no effect unless `curved` is set",
+			),
+			(
+				edge.curved && edge.curve_tension == 0.0,
+				"edge.curved",
+				"edges are curved with a tension of 0, so they stay straight",
+			),
+			(
+				node.border_width > 0.0 && node.border_color.a <= 0.0,
+				"node.border_color",
+				"a border width is set but the border color is fully transparent",
+			),
+			(
+				node.pulse_intensity > 0.0 && node.pulse_speed == 0.0,
+				"node.pulse_speed",
+				"pulse intensity is set but the pulse speed is 0, so nodes never pulse",
+			),
+			(
+				node.label_color.a <= 0.0,
+				"node.label_color",
+				"labels are fully transparent",
+			),
+			(
+				self.selection.glow > 0.0 && self.selection.ring_color.a <= 0.0,
+				"selection.ring_color",
+				"selection glow is set but the ring color is fully transparent",
+			),
+			(
+				bg.pattern != BackgroundPattern::None && bg.pattern_color.a <= 0.0,
+				"background.pattern_color",
+				"a background pattern is set but its color is fully transparent",
+			),
+			(
+				!(0.0..=1.0).contains(&bg.vignette),
+				"background.vignette",
+				"vignette intensity should be between 0.0 and 1.0",
+			),
+		];
+		checks
+			.into_iter()
+			.filter(|(failed, ..)| *failed)
+			.map(|(_, field, message)| ThemeWarning { field, message })
+			.collect()
+	}
+}
+
+/// `a` for the first half of a blend, `b` for the second.
+fn halfway<T>(a: T, b: T, t: f64) -> T {
+	if t < 0.5 { a } else { b }
+}
@@ -1,33 +1,249 @@
 //! Graph data structures for input to the force graph component.
+//!
+//! The same types serialize back out, so edited graphs can be exported as JSON
+//! in the input format.
 
-use serde::Deserialize;
+use std::collections::HashMap;
+use std::ops::Not;
+
+use serde::{Deserialize, Serialize};
 
 /// A node in the graph.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct GraphNode {
 	/// Unique identifier for this node. Used to reference nodes in links.
 	pub id: String,
 	/// Optional display label. Labeled nodes are rendered larger.
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub label: Option<String>,
+	/// Optional label priority. Where labels would overlap, the higher-priority one
+	/// is shown; defaults to the node's number of links.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub label_priority: Option<f64>,
 	/// Optional CSS color override (e.g., "#ff0000" or "rgb(255, 0, 0)").
-	/// If not set, color is derived from the theme palette based on `group`.
+	/// If not set, color is derived from `group`: its cluster color, or else a palette
+	/// color assigned to the group (generated with distinct hues for many groups).
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub color: Option<String>,
 	/// Optional group name for cluster-based coloring (e.g., "modules.home").
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub group: Option<String>,
+	/// Optional status marker drawn at the node's top-right (e.g., pending update count).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub badge: Option<NodeBadge>,
+	/// Optional CSS color override for the badge. Defaults to the theme's badge color.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub badge_color: Option<String>,
+	/// Optional segmented ring drawn around the node (e.g., share of hosts per input version).
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub ring: Vec<RingSegment>,
+	/// Optional per-node pulse, independent of the theme's global pulse settings.
+	/// `true` pulses at the default intensity; a number sets the intensity directly.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pulse: Option<NodePulse>,
+	/// Optional opacity (0.0 to 1.0, default 1.0) applied to the node and its links,
+	/// e.g. to fade out stale or less relevant nodes.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub opacity: Option<f64>,
+	/// Start hidden; revealed from the context menu ("Show all", or per group) or
+	/// the `revealed_groups` prop.
+	#[serde(default, skip_serializing_if = "<&bool as Not>::not")]
+	pub hidden: bool,
+	/// Named categorical values (e.g., owner, system) that `group_by` can regroup on.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub attributes: HashMap<String, String>,
+	/// Named numeric values (e.g., closure size, build time) that can drive heatmap coloring.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub metrics: HashMap<String, f64>,
+	/// Optional point in time (Unix seconds, e.g. when an input was last updated)
+	/// for the timeline layout.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub timestamp: Option<f64>,
+	/// Optional draw layer (default 0). Nodes on higher layers are drawn above,
+	/// and win clicks over, nodes on lower ones whatever is highlighted, so
+	/// important nodes stay on top of background clutter.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub layer: Option<i32>,
+	/// Optional mass relative to a default node (default 1.0). Heavier nodes
+	/// respond more slowly to forces, so hubs hold still while leaves orbit them.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub mass: Option<f64>,
+}
+
+/// Per-node pulse setting: a flag or an explicit intensity.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum NodePulse {
+	/// Pulse at the default intensity when `true`.
+	Enabled(bool),
+	/// Pulse with the given radius intensity (0.3 = grows by up to 30%).
+	Intensity(f64),
+}
+
+impl NodePulse {
+	/// Intensity used when a node is flagged with `pulse: true`.
+	pub const DEFAULT_INTENSITY: f64 = 0.3;
+
+	/// Effective pulse intensity (0.0 = no pulse).
+	pub fn intensity(self) -> f64 {
+		match self {
+			NodePulse::Enabled(true) => Self::DEFAULT_INTENSITY,
+			NodePulse::Enabled(false) => 0.0,
+			NodePulse::Intensity(i) => i.max(0.0),
+		}
+	}
+}
+
+/// One colored arc of a node's segmented ring.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RingSegment {
+	/// Portion of the full circle (0.0 to 1.0). Segments summing past 1.0 are normalized.
+	pub fraction: f64,
+	/// CSS color for this segment.
+	pub color: String,
+}
+
+/// A small status marker attached to a node: either a count or a short string.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum NodeBadge {
+	/// Numeric count. Values above 99 are displayed as "99+".
+	Count(u32),
+	/// Short text (e.g. "!" or "new"). Longer text is cut to three
+	/// characters, ending in an ellipsis.
+	Text(String),
 }
 
-/// A directed edge between two nodes.
-#[derive(Clone, Debug, Deserialize)]
+/// Most characters a text badge shows, an ellipsis included.
+pub const BADGE_TEXT_MAX: usize = 3;
+
+impl NodeBadge {
+	/// Text rendered inside the badge circle.
+	pub fn display_text(&self) -> String {
+		match self {
+			NodeBadge::Count(n) if *n > 99 => "99+".to_string(),
+			NodeBadge::Count(n) => n.to_string(),
+			NodeBadge::Text(s) if s.chars().count() > BADGE_TEXT_MAX => s
+				.chars()
+				.take(BADGE_TEXT_MAX - 1)
+				.chain(['\u{2026}'])
+				.collect(),
+			NodeBadge::Text(s) => s.clone(),
+		}
+	}
+}
+
+/// A directed edge between two nodes. Repeated (source, target) pairs are drawn as
+/// a single, thicker edge.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct GraphLink {
 	/// Source node ID.
 	pub source: String,
 	/// Target node ID.
 	pub target: String,
+	/// Optional edge weight (default 1.0). Drives traffic dot speed and density.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub weight: Option<f64>,
+	/// Animate small dots traveling from source to target along this edge.
+	#[serde(default, skip_serializing_if = "<&bool as Not>::not")]
+	pub traffic: bool,
+	/// Optional dash-flow speed override (world units per second) for this edge.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub flow_speed: Option<f64>,
+	/// Run the flow animation from target to source instead of source to target.
+	#[serde(default, skip_serializing_if = "<&bool as Not>::not")]
+	pub flow_reverse: bool,
+	/// Optional curvature override (0.0 = straight). Negative values bend to the other side,
+	/// so parallel edges can fan out. Overrides the theme's `curved`/`curve_tension`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub curvature: Option<f64>,
+	/// Optional edge category (e.g., "input", "import") for per-kind edge coloring.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub kind: Option<String>,
+	/// Start hidden; revealed by the context menu's "Show all".
+	#[serde(default, skip_serializing_if = "<&bool as Not>::not")]
+	pub hidden: bool,
+	/// Optional preferred length in graph units, so closely related nodes (a
+	/// module and its host) can sit nearer than loosely related ones. Unset
+	/// edges keep the simulation's plain spring.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub length: Option<f64>,
 }
 
 /// Complete graph data: nodes and links.
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct GraphData {
+	/// All nodes in the graph.
 	pub nodes: Vec<GraphNode>,
+	/// Directed edges referencing nodes by ID.
 	pub links: Vec<GraphLink>,
+	/// Group name to CSS color, layered over the component's cluster colors so a
+	/// registry can ship colors for its own group taxonomy.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub cluster_colors: HashMap<String, String>,
+}
+
+impl GraphData {
+	/// Prefix every node ID with `source/` so graphs from different sources can share a
+	/// scene without colliding. Ungrouped nodes are grouped by source.
+	pub fn namespaced(mut self, source: &str) -> Self {
+		let prefix = |id: &str| format!("{source}/{id}");
+		for node in &mut self.nodes {
+			node.id = prefix(&node.id);
+			node.group.get_or_insert_with(|| source.to_string());
+		}
+		for link in &mut self.links {
+			link.source = prefix(&link.source);
+			link.target = prefix(&link.target);
+		}
+		self
+	}
+
+	/// Re-derive each node's `group` from its `attributes[field]`. Nodes without the
+	/// attribute end up ungrouped.
+	pub fn grouped_by(mut self, field: &str) -> Self {
+		for node in &mut self.nodes {
+			node.group = node.attributes.get(field).cloned();
+		}
+		self
+	}
+
+	/// Merge named graphs into one, namespacing each with [`GraphData::namespaced`].
+	/// With `link_shared`, copies of a node ID present in several sources are chained
+	/// together by links so common dependencies line up across sources.
+	pub fn merge(
+		sources: impl IntoIterator<Item = (String, GraphData)>,
+		link_shared: bool,
+	) -> Self {
+		let mut merged = GraphData::default();
+		let mut seen: HashMap<String, String> = HashMap::new();
+		for (source, data) in sources {
+			if link_shared {
+				for node in &data.nodes {
+					let id = format!("{source}/{}", node.id);
+					if let Some(previous) = seen.insert(node.id.clone(), id.clone()) {
+						merged.links.push(GraphLink {
+							source: previous,
+							target: id,
+							..GraphLink::default()
+						});
+					}
+				}
+			}
+			let data = data.namespaced(&source);
+			merged.nodes.extend(data.nodes);
+			merged.links.extend(data.links);
+			merged.cluster_colors.extend(data.cluster_colors);
+		}
+		merged
+	}
+}
+
+/// The graph as it stood at one point in time, for timeline playback.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct GraphSnapshot {
+	/// Timestamp or caption shown next to the timeline scrubber.
+	pub label: String,
+	/// Full graph at this point in time.
+	pub data: GraphData,
 }
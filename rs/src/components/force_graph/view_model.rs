@@ -0,0 +1,81 @@
+//! View model between the input data and the simulation.
+//!
+//! Derives the graph that is actually simulated from the input: each collapsed
+//! group becomes a single meta-node, and links touching its members are rerouted
+//! to it. Links that end up parallel are kept as duplicates, so the simulation
+//! aggregates them into one weighted meta-edge carrying their count. Expanding a
+//! group is simply deriving the view again without it.
+
+use std::collections::{HashMap, HashSet};
+
+use super::types::{GraphData, GraphLink, GraphNode, NodeBadge};
+
+/// ID prefix of the meta-node standing in for a collapsed group. Starts with a
+/// NUL, which input node IDs never contain, so a real node can't pass for one.
+const META_PREFIX: &str = "\0group:";
+
+/// ID of the meta-node for `group`.
+pub fn meta_id(group: &str) -> String {
+	format!("{META_PREFIX}{group}")
+}
+
+/// The group a meta-node stands for, if `id` is one.
+pub fn meta_group(id: &str) -> Option<&str> {
+	id.strip_prefix(META_PREFIX)
+}
+
+/// The graph to simulate for `data` with the given groups collapsed.
+pub fn collapse(data: &GraphData, collapsed: &HashSet<String>) -> GraphData {
+	if collapsed.is_empty() {
+		return data.clone();
+	}
+
+	let mut nodes = Vec::new();
+	let mut groups: Vec<(&str, Vec<&GraphNode>)> = Vec::new();
+	let mut owner: HashMap<&str, String> = HashMap::new();
+	for node in &data.nodes {
+		match node.group.as_deref().filter(|g| collapsed.contains(*g)) {
+			Some(group) => {
+				owner.insert(&node.id, meta_id(group));
+				match groups.iter_mut().find(|(g, _)| *g == group) {
+					Some((_, members)) => members.push(node),
+					None => groups.push((group, vec![node])),
+				}
+			}
+			None => nodes.push(node.clone()),
+		}
+	}
+	for (group, members) in groups {
+		nodes.push(GraphNode {
+			id: meta_id(group),
+			label: Some(group.to_string()),
+			group: Some(group.to_string()),
+			badge: Some(NodeBadge::Count(members.len() as u32)),
+			hidden: members.iter().all(|m| m.hidden),
+			// The group is as recent as its most recent member
+			timestamp: members.iter().filter_map(|m| m.timestamp).reduce(f64::max),
+			..GraphNode::default()
+		});
+	}
+
+	let reroute = |id: &String| owner.get(id.as_str()).unwrap_or(id).clone();
+	let links = data
+		.links
+		.iter()
+		.filter_map(|link| {
+			let (source, target) = (reroute(&link.source), reroute(&link.target));
+			// Links inside a collapsed group disappear into its meta-node
+			(source != target).then(|| GraphLink {
+				source,
+				target,
+				..link.clone()
+			})
+		})
+		.collect();
+
+	GraphData {
+		nodes,
+		links,
+		cluster_colors: data.cluster_colors.clone(),
+	}
+}
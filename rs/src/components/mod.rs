@@ -1 +1,3 @@
+//! UI components provided by this crate.
+
 pub mod force_graph;
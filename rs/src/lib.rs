@@ -4,14 +4,25 @@
 //! dependency graphs with physics-based layout, pan/zoom, and hover effects.
 
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use leptos_meta::*;
 use log::{Level, info, warn};
-use wasm_bindgen::JsCast;
-use web_sys::{HtmlScriptElement, Window};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Element, HtmlScriptElement, Response, Window};
 
+/// Leptos UI components.
 pub mod components;
 
-pub use components::force_graph::{ForceGraphCanvas, GraphData, GraphLink, GraphNode};
+use components::force_graph::generators;
+pub use components::force_graph::{
+	Axis, AxisField, CameraConfig, ContextMenuItem, ContextTarget, Easing, EdgeDrawHook,
+	EdgeRenderInfo, ForceGraphCanvas, GraphData, GraphHandle, GraphLink, GraphModel, GraphNode,
+	GraphProvider, GraphSnapshot, HeatmapConfig, HighlightConfig, InputKind, KeyAction, Keymap,
+	Layout, NodeBadge, NodeDrawHook, NodePulse, NodeRenderInfo, NodeSizing, PhysicsConfig,
+	RecordedInput, Recording, RenderFrame, RenderPlugin, RingSegment, ScaleConfig,
+	TransitionConfig, ViewLink, ViewTransform, use_graph,
+};
 
 /// Initialize logging and panic hooks for the WASM target.
 pub fn init_logging() {
@@ -20,14 +31,12 @@ pub fn init_logging() {
 	info!("imp-graph: logging initialized");
 }
 
-/// Load graph data from a script element with id="graph-data".
-/// Expected format: JSON with { nodes: [...], links: [...] }
-fn load_graph_data() -> Option<GraphData> {
-	let window: Window = web_sys::window()?;
-	let document = window.document()?;
-	let element = document.get_element_by_id("graph-data")?;
-	let script: HtmlScriptElement = element.dyn_into().ok()?;
-	let json_text = script.text().ok()?;
+/// Parse the JSON graph held in a script element, or describe why it can't be.
+fn parse_graph_script(element: Element) -> Result<GraphData, String> {
+	let script: HtmlScriptElement = element
+		.dyn_into()
+		.map_err(|_| "graph data element is not a script".to_string())?;
+	let json_text = script.text().unwrap_or_default();
 
 	match serde_json::from_str::<GraphData>(&json_text) {
 		Ok(data) => {
@@ -36,24 +45,159 @@ fn load_graph_data() -> Option<GraphData> {
 				data.nodes.len(),
 				data.links.len()
 			);
-			Some(data)
+			Ok(data)
 		}
 		Err(e) => {
 			warn!("imp-graph: failed to parse graph data: {}", e);
-			None
+			Err(e.to_string())
+		}
+	}
+}
+
+/// Load graph data from the DOM.
+///
+/// Script elements carrying a `data-graph-source="name"` attribute are merged into
+/// one scene, with node IDs prefixed by their source name; if any of them also sets
+/// `data-link-shared`, nodes present in several sources are linked together.
+/// Otherwise the single script element with id="graph-data" is used.
+/// Expected format: JSON with { nodes: [...], links: [...] }
+///
+/// A page without graph data shows a generated demo graph; data that fails to
+/// parse is an error, naming the source it came from.
+fn load_graph_data() -> Result<GraphData, String> {
+	let Some(document) = web_sys::window().and_then(|w: Window| w.document()) else {
+		return Ok(GraphData::default());
+	};
+
+	let sources = document
+		.query_selector_all("script[data-graph-source]")
+		.map_err(|_| "couldn't query graph sources".to_string())?;
+	if sources.length() > 0 {
+		let mut link_shared = false;
+		let mut graphs = Vec::new();
+		for i in 0..sources.length() {
+			let Some(element) = sources.get(i).and_then(|n| n.dyn_into::<Element>().ok()) else {
+				continue;
+			};
+			let name = element
+				.get_attribute("data-graph-source")
+				.unwrap_or_default();
+			link_shared |= element.has_attribute("data-link-shared");
+			let data = parse_graph_script(element).map_err(|e| format!("{name}: {e}"))?;
+			graphs.push((name, data));
+		}
+		info!("imp-graph: merging {} graph sources", graphs.len());
+		return Ok(GraphData::merge(graphs, link_shared));
+	}
+
+	match document.get_element_by_id("graph-data") {
+		Some(element) => parse_graph_script(element),
+		None => {
+			info!("imp-graph: no graph data, showing a generated demo graph");
+			Ok(generators::scale_free(150, 2, 7))
 		}
 	}
 }
 
+/// URL to fetch graph data from, given as `data-src` on the `graph-data` script.
+fn graph_data_url() -> Option<String> {
+	web_sys::window()?
+		.document()?
+		.get_element_by_id("graph-data")?
+		.get_attribute("data-src")
+}
+
+/// Readable message for a failed JS call.
+fn js_error(value: JsValue) -> String {
+	value
+		.dyn_ref::<js_sys::Error>()
+		.map(|e| String::from(e.message()))
+		.or_else(|| value.as_string())
+		.unwrap_or_else(|| format!("{value:?}"))
+}
+
+/// Fetch and parse graph data from `url`.
+async fn fetch_graph_data(url: &str) -> Result<GraphData, String> {
+	let window = web_sys::window().ok_or("no window")?;
+	let response = JsFuture::from(window.fetch_with_str(url))
+		.await
+		.map_err(js_error)?;
+	let response: Response = response
+		.dyn_into()
+		.map_err(|_| "fetch returned no response".to_string())?;
+	if !response.ok() {
+		return Err(format!(
+			"{url}: HTTP {} {}",
+			response.status(),
+			response.status_text()
+		));
+	}
+	let text = JsFuture::from(response.text().map_err(js_error)?)
+		.await
+		.map_err(js_error)?;
+	let data: GraphData = serde_json::from_str(&text.as_string().unwrap_or_default())
+		.map_err(|e| format!("{url}: {e}"))?;
+	info!(
+		"imp-graph: fetched {} nodes, {} links from {url}",
+		data.nodes.len(),
+		data.links.len()
+	);
+	Ok(data)
+}
+
+/// Where the App's graph data stands.
+#[derive(Clone, Debug, PartialEq)]
+enum LoadState {
+	/// Being fetched from the `data-src` URL
+	Loading,
+	Loaded,
+	/// Couldn't be read; the message says why
+	Failed(String),
+}
+
 /// Main application component.
-/// Loads graph data from DOM and renders the force-directed visualization.
+/// Loads graph data from DOM and renders the force-directed visualization, or
+/// an empty or error state when there is nothing to show. With a `data-src`
+/// attribute on the `graph-data` script the data is fetched from that URL
+/// instead, with a spinner meanwhile and a retry button if it fails. The page
+/// can push replacement data with `window.__impGraph.graph.setData(json)`.
 #[component]
 pub fn App() -> impl IntoView {
 	provide_meta_context();
 
-	// Load graph data from the DOM
-	let graph_data = load_graph_data().unwrap_or_default();
-	let graph_signal = Signal::derive(move || graph_data.clone());
+	let graph_data = RwSignal::new(GraphData::default());
+	let load = RwSignal::new(LoadState::Loading);
+	// Fetch the data, or read it from the DOM; the canvas shows why if it can't be read
+	let reload = move || match graph_data_url() {
+		Some(url) => {
+			load.set(LoadState::Loading);
+			spawn_local(async move {
+				match fetch_graph_data(&url).await {
+					Ok(data) => {
+						graph_data.set(data);
+						load.set(LoadState::Loaded);
+					}
+					Err(e) => {
+						warn!("imp-graph: failed to fetch graph data: {e}");
+						load.set(LoadState::Failed(e));
+					}
+				}
+			});
+		}
+		None => match load_graph_data() {
+			Ok(data) => {
+				graph_data.set(data);
+				load.set(LoadState::Loaded);
+			}
+			Err(e) => load.set(LoadState::Failed(e)),
+		},
+	};
+	reload();
+	let loading = Signal::derive(move || load.with(|s| *s == LoadState::Loading));
+	let load_error = Signal::derive(move || match load.get() {
+		LoadState::Failed(e) => Some(e),
+		_ => None,
+	});
 
 	view! {
 		<Html attr:lang="en" attr:dir="ltr" attr:data-theme="dark" />
@@ -62,10 +206,20 @@ pub fn App() -> impl IntoView {
 		<Meta name="viewport" content="width=device-width, initial-scale=1.0" />
 
 		<div class="fullscreen-graph">
-			<ForceGraphCanvas data=graph_signal fullscreen=true />
+			<ForceGraphCanvas
+				data=graph_data
+				fullscreen=true
+				js_handle="graph"
+				error=load_error
+				loading=loading
+				on_retry=Callback::new(move |()| reload())
+				settle_indicator=true
+			/>
 			<div class="graph-overlay">
 				<h1>"imp Registry"</h1>
-				<p class="subtitle">"Drag nodes to reposition. Scroll to zoom. Drag background to pan."</p>
+				<p class="subtitle">
+					"Drag nodes to reposition. Scroll to zoom. Drag background to pan. Shift-drag to zoom to an area."
+				</p>
 			</div>
 		</div>
 	}